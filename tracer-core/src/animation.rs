@@ -0,0 +1,236 @@
+use std::ops::{Add, Mul};
+
+use serde::{Deserialize, Serialize};
+
+/// Way a track's value evolves between two keyframes.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Interpolation {
+    /// The value jumps to the next keyframe's value when reaching it.
+    Step,
+    /// The value moves at a constant speed from a keyframe to the next.
+    #[default]
+    Linear,
+    /// The value follows a smooth curve going through every keyframe (Catmull-Rom spline).
+    Cubic,
+}
+
+/// Value taken by a track at a given time, in seconds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Keyframe<T> {
+    pub time: f32,
+    pub value: T,
+}
+
+/// Values that can be blended together by the interpolations.
+pub trait Animatable: Clone {
+    /// Get the sum of the given values, each one multiplied by its weight.
+    fn weighted_sum(values: &[(&Self, f32)]) -> Self;
+}
+
+impl<T> Animatable for T
+where
+    T: Copy + Default + Add<Output = T> + Mul<f32, Output = T>,
+{
+    fn weighted_sum(values: &[(&Self, f32)]) -> Self {
+        values
+            .iter()
+            .fold(T::default(), |sum, (value, weight)| sum + **value * *weight)
+    }
+}
+
+/// Value of any animatable property of a scene file, a number or a vector of any size.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum AnimatedValue {
+    Scalar(f32),
+    Vector(Vec<f32>),
+}
+
+impl AnimatedValue {
+    pub fn get_components(&self) -> &[f32] {
+        match self {
+            AnimatedValue::Scalar(x) => std::slice::from_ref(x),
+            AnimatedValue::Vector(x) => x,
+        }
+    }
+}
+
+impl Animatable for AnimatedValue {
+    /// Blend the values component-wise, the values being expected to have the same shape as the first one.
+    fn weighted_sum(values: &[(&Self, f32)]) -> Self {
+        let size = values.first().map_or(0, |x| x.0.get_components().len());
+        let mut sum = vec![0.; size];
+        values.iter().for_each(|(value, weight)| {
+            sum.iter_mut()
+                .zip(value.get_components())
+                .for_each(|(sum, x)| *sum += x * weight);
+        });
+        match values.first() {
+            Some((AnimatedValue::Scalar(_), _)) => AnimatedValue::Scalar(sum[0]),
+            _ => AnimatedValue::Vector(sum),
+        }
+    }
+}
+
+/// Sequence of keyframes, sorted by time, along with the way to interpolate between them.
+/// Before the first keyframe and after the last one, the track holds the first and last values.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Track<T> {
+    pub interpolation: Interpolation,
+    pub keyframes: Vec<Keyframe<T>>,
+}
+
+impl<T: Animatable> Track<T> {
+    pub fn new(interpolation: Interpolation, keyframes: Vec<Keyframe<T>>) -> Self {
+        Self {
+            interpolation,
+            keyframes,
+        }
+    }
+
+    /// Get the time of the last keyframe, if any.
+    pub fn get_end(&self) -> Option<f32> {
+        self.keyframes.last().map(|x| x.time)
+    }
+
+    /// Get the track's value at the given time, if it has any keyframe.
+    pub fn evaluate(&self, time: f32) -> Option<T> {
+        let next = self.keyframes.partition_point(|x| x.time <= time);
+        let (start, end) = match next {
+            0 => return self.keyframes.first().map(|x| x.value.clone()),
+            x if x == self.keyframes.len() => {
+                return self.keyframes.last().map(|x| x.value.clone());
+            }
+            x => (&self.keyframes[x - 1], &self.keyframes[x]),
+        };
+        let t = (time - start.time) / (end.time - start.time);
+
+        Some(match self.interpolation {
+            Interpolation::Step => start.value.clone(),
+            Interpolation::Linear => T::weighted_sum(&[(&start.value, 1. - t), (&end.value, t)]),
+            Interpolation::Cubic => {
+                // Hermite curve, whose tangents are the slopes between the surrounding keyframes.
+                let duration = end.time - start.time;
+                let before = &self.keyframes[next.saturating_sub(2)];
+                let after = &self.keyframes[(next + 1).min(self.keyframes.len() - 1)];
+                let s0 = duration / (end.time - before.time);
+                let s1 = duration / (after.time - start.time);
+
+                let (t2, t3) = (t * t, t * t * t);
+                let h00 = 2. * t3 - 3. * t2 + 1.;
+                let h10 = t3 - 2. * t2 + t;
+                let h01 = -2. * t3 + 3. * t2;
+                let h11 = t3 - t2;
+                T::weighted_sum(&[
+                    (&start.value, h00),
+                    (&end.value, h01),
+                    (&end.value, h10 * s0),
+                    (&before.value, -h10 * s0),
+                    (&after.value, h11 * s1),
+                    (&start.value, -h11 * s1),
+                ])
+            }
+        })
+    }
+}
+
+// #####################################
+
+#[cfg(test)]
+mod tests {
+    use glam::Vec3A;
+
+    use crate::animation::{AnimatedValue, Interpolation, Keyframe, Track};
+
+    fn track(interpolation: Interpolation) -> Track<f32> {
+        Track::new(
+            interpolation,
+            vec![
+                Keyframe {
+                    time: 0.,
+                    value: 0.,
+                },
+                Keyframe {
+                    time: 1.,
+                    value: 10.,
+                },
+                Keyframe {
+                    time: 3.,
+                    value: 30.,
+                },
+            ],
+        )
+    }
+
+    #[test]
+    fn test_success_evaluate() {
+        let step = track(Interpolation::Step);
+        assert_eq!(step.evaluate(0.5), Some(0.));
+        assert_eq!(step.evaluate(1.), Some(10.));
+
+        let linear = track(Interpolation::Linear);
+        assert_eq!(linear.evaluate(0.5), Some(5.));
+        assert_eq!(linear.evaluate(2.), Some(20.));
+
+        // Keyframes along a straight line give a straight curve.
+        let cubic = track(Interpolation::Cubic);
+        [0., 0.25, 1., 1.5, 2., 2.9, 3.].iter().for_each(|time| {
+            assert!((cubic.evaluate(*time).unwrap() - time * 10.).abs() < 1e-4);
+        });
+        assert_eq!(cubic.get_end(), Some(3.));
+    }
+
+    #[test]
+    fn test_success_evaluate_vector() {
+        let track = Track::new(
+            Interpolation::Cubic,
+            vec![
+                Keyframe {
+                    time: 0.,
+                    value: Vec3A::ZERO,
+                },
+                Keyframe {
+                    time: 1.,
+                    value: Vec3A::X,
+                },
+                Keyframe {
+                    time: 2.,
+                    value: Vec3A::ZERO,
+                },
+            ],
+        );
+        assert_eq!(track.evaluate(1.), Some(Vec3A::X));
+        assert!(track.evaluate(0.9).unwrap().x > 0.9);
+
+        let track = Track::new(
+            Interpolation::Linear,
+            vec![
+                Keyframe {
+                    time: 0.,
+                    value: AnimatedValue::Vector(vec![0., 2.]),
+                },
+                Keyframe {
+                    time: 2.,
+                    value: AnimatedValue::Vector(vec![2., 0.]),
+                },
+            ],
+        );
+        assert_eq!(
+            track.evaluate(0.5),
+            Some(AnimatedValue::Vector(vec![0.5, 1.5]))
+        );
+    }
+
+    #[test]
+    fn test_failure_evaluate_out_of_range() {
+        let track = track(Interpolation::Cubic);
+        assert_eq!(track.evaluate(-1.), Some(0.));
+        assert_eq!(track.evaluate(10.), Some(30.));
+        assert_eq!(
+            Track::<f32>::new(Interpolation::Linear, vec![]).evaluate(0.),
+            None
+        );
+    }
+}
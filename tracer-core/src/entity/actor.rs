@@ -1,35 +1,55 @@
 use glam::Vec3A;
+use serde::{Deserialize, Serialize};
 
 // #####################################
 
 /// base 'class' inherited by any object allowing interaction with the current scene.
-#[derive(Debug)]
+/// The velocity (in units per second) moves the actor away from its position as time goes by.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Actor {
     pub position: Vec3A,
+    #[serde(default)]
+    pub velocity: Vec3A,
 }
 
 pub trait ActorTrait {
     /// Get the actor's current position.
     fn get_position(&self) -> Vec3A;
+
+    /// Get the actor's velocity, in units per second.
+    fn get_velocity(&self) -> Vec3A {
+        Vec3A::ZERO
+    }
+
+    /// Get the actor's position at the given time, relative to the frame's time.
+    fn get_position_at(&self, time: f32) -> Vec3A {
+        self.get_position() + self.get_velocity() * time
+    }
 }
 
 impl ActorTrait for Actor {
     fn get_position(&self) -> Vec3A {
         self.position
     }
+
+    fn get_velocity(&self) -> Vec3A {
+        self.velocity
+    }
 }
 
 impl Actor {
     pub const fn new(position: &Vec3A) -> Self {
         Self {
             position: *position,
+            velocity: Vec3A::ZERO,
         }
     }
 }
 
 // #####################################
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct DirectionalActor {
+    #[serde(flatten)]
     actor: Actor,
     direction: Vec3A,
 }
@@ -43,6 +63,10 @@ impl ActorTrait for DirectionalActor {
     fn get_position(&self) -> Vec3A {
         self.actor.get_position()
     }
+
+    fn get_velocity(&self) -> Vec3A {
+        self.actor.get_velocity()
+    }
 }
 
 impl DirectionalActorTrait for DirectionalActor {
@@ -59,4 +83,8 @@ impl DirectionalActor {
             direction: *direction,
         }
     }
+
+    pub fn set_velocity(&mut self, velocity: &Vec3A) {
+        self.actor.velocity = *velocity;
+    }
 }
@@ -0,0 +1,106 @@
+use glam::Vec3A;
+
+use crate::entity::{
+    actor::{ActorTrait, DirectionalActorTrait},
+    geometry::ray::Ray,
+};
+
+/// Axis aligned bounding box, used to skip the geometries a ray cannot reach.
+/// The empty box contains nothing, and the infinite box everything.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min: Vec3A,
+    pub max: Vec3A,
+}
+
+impl Aabb {
+    pub const EMPTY: Aabb = Aabb::new(Vec3A::INFINITY, Vec3A::NEG_INFINITY);
+    pub const INFINITE: Aabb = Aabb::new(Vec3A::NEG_INFINITY, Vec3A::INFINITY);
+
+    pub const fn new(min: Vec3A, max: Vec3A) -> Self {
+        Self { min, max }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.min.cmpgt(self.max).any()
+    }
+
+    /// Get the smallest box containing both boxes.
+    pub fn union(&self, other: &Aabb) -> Aabb {
+        Aabb::new(self.min.min(other.min), self.max.max(other.max))
+    }
+
+    /// Get the box contained by both boxes, empty if they do not overlap.
+    pub fn intersection(&self, other: &Aabb) -> Aabb {
+        Aabb::new(self.min.max(other.min), self.max.min(other.max))
+    }
+
+    pub fn contains(&self, point: &Vec3A) -> bool {
+        point.cmpge(self.min).all() && point.cmple(self.max).all()
+    }
+
+    /// Get the range of the ray's parameter inside the box, if the ray's line crosses it (slab test).
+    pub fn intersect(&self, ray: &Ray) -> Option<(f32, f32)> {
+        if self.is_empty() {
+            return None;
+        }
+        let inverse = ray.get_direction().recip();
+        let t0 = (self.min - ray.get_position()) * inverse;
+        let t1 = (self.max - ray.get_position()) * inverse;
+        let near = t0.min(t1).max_element();
+        let far = t0.max(t1).min_element();
+        (near <= far).then_some((near, far))
+    }
+}
+
+// #####################################
+
+#[cfg(test)]
+mod tests {
+    use glam::{Vec3A, Vec4};
+
+    use crate::entity::{
+        actor::ActorTrait,
+        geometry::{Geometry, aabb::Aabb, plane::Plane, ray::Ray, sphere::Sphere},
+        rendering::material::{ColorMaterial, MaterialType},
+    };
+
+    #[test]
+    fn test_success_moving_bounds() {
+        let material = MaterialType::Color(ColorMaterial::new(Vec4::ONE));
+        let mut sphere = Sphere::new(&Vec3A::ZERO, 1., &material).unwrap();
+        sphere.set_velocity(&Vec3A::new(10., 0., 0.));
+
+        let bounds = sphere.get_bounds(0., 0.5);
+        assert_eq!(bounds, Aabb::new(Vec3A::splat(-1.), Vec3A::new(6., 1., 1.)));
+        assert!(bounds.contains(&sphere.get_position_at(0.25)));
+
+        let mut plane = Plane::new(&Vec3A::ZERO, &Vec3A::new(0., -1., 0.), &material).unwrap();
+        plane.set_velocity(&Vec3A::new(3., 2., 0.));
+        let bounds = plane.get_bounds(0., 1.);
+        assert_eq!((bounds.min.y, bounds.max.y), (0., 2.));
+        assert_eq!(bounds.max.x, f32::INFINITY);
+
+        let plane = Plane::new(&Vec3A::ZERO, &Vec3A::new(0., 1., 1.), &material).unwrap();
+        assert_eq!(plane.get_bounds(0., 1.), Aabb::INFINITE);
+    }
+
+    #[test]
+    fn test_success_intersect() {
+        let bounds = Aabb::new(Vec3A::splat(-1.), Vec3A::splat(1.));
+        let ray = Ray::new(&Vec3A::new(-5., 0., 0.), &Vec3A::X);
+        assert_eq!(bounds.intersect(&ray), Some((4., 6.)));
+
+        let union = bounds.union(&Aabb::EMPTY);
+        assert_eq!(union, bounds);
+        assert!(Aabb::EMPTY.is_empty() && !bounds.is_empty());
+    }
+
+    #[test]
+    fn test_failure_intersect() {
+        let bounds = Aabb::new(Vec3A::splat(-1.), Vec3A::splat(1.));
+        let ray = Ray::new(&Vec3A::new(-5., 2., 0.), &Vec3A::X);
+        assert_eq!(bounds.intersect(&ray), None);
+        assert_eq!(Aabb::EMPTY.intersect(&ray), None);
+    }
+}
@@ -0,0 +1,347 @@
+use std::f32::consts::{PI, TAU};
+
+use glam::{Vec2, Vec3A};
+use serde::{Deserialize, Serialize};
+
+use crate::entity::actor::{Actor, ActorTrait, DirectionalActorTrait};
+use crate::entity::geometry::polynomial::find_roots;
+use crate::entity::geometry::{Geometry, RayType, aabb::Aabb, ray::Ray};
+use crate::entity::rendering::material::{MaterialBound, MaterialType};
+
+/// Term of a polynomial, as its coefficient times the coordinates raised to the exponents, along X, Y then Z.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Monomial {
+    pub coefficient: f32,
+    pub exponents: [u32; 3],
+}
+
+/// Highest total degree of a term, beyond which the roots along a ray are no longer found reliably.
+pub const MAX_DEGREE: u32 = 16;
+
+/// Multiply two polynomials, their coefficients being sorted by increasing degree.
+fn multiply(left: &[f64], right: &[f64]) -> Vec<f64> {
+    let mut product = vec![0.; left.len() + right.len() - 1];
+    for (i, a) in left.iter().enumerate() {
+        for (j, b) in right.iter().enumerate() {
+            product[i + j] += a * b;
+        }
+    }
+    product
+}
+
+/// Structure representing the surface where a polynomial in the coordinates relative to its position is zero, its
+/// inside being where the polynomial is negative, e.g. x² + y² + z² - 1 for a unit sphere.
+/// The surface is clipped to the box spanning the extent around the position, which bounds the unbounded ones.
+/// Along a ray, the polynomial is expanded into a polynomial of the ray's parameter, whose roots are found within
+/// the box.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(try_from = "AlgebraicData", into = "AlgebraicData")]
+pub struct Algebraic {
+    pub actor: Actor,
+    terms: Vec<Monomial>,
+    extent: Vec3A,
+    material: MaterialType,
+}
+
+/// Serialized fields of an algebraic surface, checked while being deserialized.
+#[derive(Serialize, Deserialize)]
+struct AlgebraicData {
+    #[serde(flatten)]
+    actor: Actor,
+    terms: Vec<Monomial>,
+    extent: Vec3A,
+    material: MaterialType,
+}
+
+impl TryFrom<AlgebraicData> for Algebraic {
+    type Error = String;
+
+    fn try_from(data: AlgebraicData) -> Result<Self, Self::Error> {
+        let mut algebraic = Algebraic::new(
+            &data.actor.position,
+            data.terms,
+            &data.extent,
+            &data.material,
+        )?;
+        algebraic.set_velocity(&data.actor.velocity);
+        Ok(algebraic)
+    }
+}
+
+impl From<Algebraic> for AlgebraicData {
+    fn from(algebraic: Algebraic) -> Self {
+        Self {
+            actor: algebraic.actor,
+            terms: algebraic.terms,
+            extent: algebraic.extent,
+            material: algebraic.material,
+        }
+    }
+}
+
+impl std::ops::Deref for Algebraic {
+    type Target = Actor;
+    fn deref(&self) -> &Self::Target {
+        &self.actor
+    }
+}
+
+impl MaterialBound for Algebraic {
+    fn get_material(&self) -> &MaterialType {
+        &self.material
+    }
+}
+
+impl Algebraic {
+    /// Fail without any term, if a term's total degree is above the maximum, or if the extent is not finite and above
+    /// zero along every axis.
+    pub fn new(
+        position: &Vec3A,
+        terms: Vec<Monomial>,
+        extent: &Vec3A,
+        material: &MaterialType,
+    ) -> Result<Self, String> {
+        if terms.is_empty() {
+            return Err("an algebraic surface needs at least one term".to_string());
+        }
+        if terms.iter().any(|x| {
+            x.exponents
+                .iter()
+                .try_fold(0_u32, |sum, x| sum.checked_add(*x))
+                .is_none_or(|x| x > MAX_DEGREE)
+        }) {
+            return Err(format!(
+                "the terms' total degree must be at most {MAX_DEGREE}"
+            ));
+        }
+        if !extent.is_finite() || extent.min_element() <= 0. {
+            return Err("the extent must be finite and above zero along every axis".to_string());
+        }
+        Ok(Self {
+            actor: Actor::new(position),
+            terms,
+            extent: *extent,
+            material: material.to_owned(),
+        })
+    }
+
+    pub fn get_terms(&self) -> &Vec<Monomial> {
+        &self.terms
+    }
+
+    pub fn get_extent(&self) -> Vec3A {
+        self.extent
+    }
+
+    pub fn set_velocity(&mut self, velocity: &Vec3A) {
+        self.actor.velocity = *velocity;
+    }
+
+    fn get_box(&self) -> Aabb {
+        Aabb::new(-self.extent, self.extent)
+    }
+
+    /// Get the coefficients of the polynomial along the ray, by increasing degree, out of the powers of each
+    /// coordinate along it.
+    fn expand(&self, origin: &Vec3A, direction: &Vec3A) -> Vec<f64> {
+        let degree = |axis: usize| self.terms.iter().map(|x| x.exponents[axis]).max();
+        let powers = [0, 1, 2].map(|axis| {
+            let line = [origin[axis] as f64, direction[axis] as f64];
+            (0..degree(axis).unwrap_or(0)).fold(vec![vec![1.]], |mut powers, _| {
+                powers.push(multiply(powers.last().unwrap(), &line));
+                powers
+            })
+        });
+
+        self.terms.iter().fold(Vec::new(), |mut sum, term| {
+            let [x, y, z] = term.exponents.map(|x| x as usize);
+            let product = multiply(&multiply(&powers[0][x], &powers[1][y]), &powers[2][z]);
+            sum.resize(sum.len().max(product.len()), 0.);
+            product
+                .iter()
+                .enumerate()
+                .for_each(|(i, x)| sum[i] += term.coefficient as f64 * x);
+            sum
+        })
+    }
+}
+
+impl ActorTrait for Algebraic {
+    fn get_position(&self) -> Vec3A {
+        self.actor.get_position()
+    }
+
+    fn get_velocity(&self) -> Vec3A {
+        self.actor.get_velocity()
+    }
+}
+
+impl Geometry for Algebraic {
+    /// Get the polynomial's gradient, pointing towards its positive values.
+    fn get_surface_normal(&self, point: &Vec3A, time: f32) -> Vec3A {
+        let local = point - self.get_position_at(time);
+        let gradient = self.terms.iter().fold(Vec3A::ZERO, |gradient, term| {
+            let derivative = |axis: usize| match term.exponents[axis] {
+                0 => 0.,
+                exponent => {
+                    (0..3)
+                        .map(|x| match x == axis {
+                            true => exponent as f32 * local[x].powi(exponent as i32 - 1),
+                            false => local[x].powi(term.exponents[x] as i32),
+                        })
+                        .product::<f32>()
+                        * term.coefficient
+                }
+            };
+            gradient + Vec3A::new(derivative(0), derivative(1), derivative(2))
+        });
+        gradient.normalize_or(Vec3A::Y)
+    }
+
+    /// Map the normal's direction, as on a sphere.
+    fn get_uv(&self, point: &Vec3A, time: f32) -> Vec2 {
+        let normal = self.get_surface_normal(point, time);
+        Vec2::new(
+            0.5 + normal.z.atan2(normal.x) / TAU,
+            0.5 + normal.y.clamp(-1., 1.).asin() / PI,
+        )
+    }
+
+    fn get_bounds(&self, time_start: f32, time_end: f32) -> Aabb {
+        [time_start, time_end]
+            .iter()
+            .map(|x| self.get_position_at(*x))
+            .fold(Aabb::EMPTY, |moved, position| {
+                moved.union(&Aabb::new(position - self.extent, position + self.extent))
+            })
+    }
+
+    /// Find the roots of the polynomial along the ray, within the part of the ray crossing the box.
+    fn intersect(&self, ray: &Ray, ray_type: &RayType) -> Option<(f32, Vec3A)> {
+        let origin = ray.get_position() - self.get_position_at(ray.get_time());
+        let direction = ray.get_direction();
+        let (near, far) = self.get_box().intersect(&Ray::new(&origin, &direction))?;
+        let (start, end) = ray_type.get_range();
+        let (start, end) = (near.max(start), far.min(end));
+        if start > end {
+            return None;
+        }
+
+        find_roots(&self.expand(&origin, &direction), start as f64, end as f64)
+            .into_iter()
+            .map(|t| t as f32)
+            .find(|t| ray_type.accepts(*t))
+            .map(|t| (t, ray.get_position() + t * direction))
+    }
+}
+
+// #####################################
+
+#[cfg(test)]
+mod tests {
+    use glam::{Vec3A, Vec4};
+
+    use crate::entity::{
+        geometry::{
+            Geometry, RayType,
+            algebraic::{Algebraic, MAX_DEGREE, Monomial},
+            ray::Ray,
+        },
+        rendering::material::{ColorMaterial, MaterialType},
+    };
+
+    fn algebraic(terms: &[(f32, [u32; 3])], extent: Vec3A) -> Result<Algebraic, String> {
+        Algebraic::new(
+            &Vec3A::ZERO,
+            terms
+                .iter()
+                .map(|(coefficient, exponents)| Monomial {
+                    coefficient: *coefficient,
+                    exponents: *exponents,
+                })
+                .collect(),
+            &extent,
+            &MaterialType::Color(ColorMaterial::new(Vec4::ONE)),
+        )
+    }
+
+    #[test]
+    fn test_success_intersect() {
+        // x² + y² + z² - 4, a sphere of radius 2.
+        let sphere = algebraic(
+            &[
+                (1., [2, 0, 0]),
+                (1., [0, 2, 0]),
+                (1., [0, 0, 2]),
+                (-4., [0, 0, 0]),
+            ],
+            Vec3A::splat(3.),
+        )
+        .unwrap();
+        let ray = Ray::new(&Vec3A::new(0., 0., -10.), &Vec3A::Z);
+        let (distance, point) = sphere.intersect(&ray, &RayType::Camera).unwrap();
+        assert!((distance - 8.).abs() < 1e-4);
+        assert!((sphere.get_surface_normal(&point, 0.) - Vec3A::NEG_Z).length() < 1e-4);
+
+        // x⁴ + y⁴ + z⁴ - 16, a rounded cube reaching 2 along the axes.
+        let cube = algebraic(
+            &[
+                (1., [4, 0, 0]),
+                (1., [0, 4, 0]),
+                (1., [0, 0, 4]),
+                (-16., [0, 0, 0]),
+            ],
+            Vec3A::splat(3.),
+        )
+        .unwrap();
+        let ray = Ray::new(&Vec3A::new(1., 1., -10.), &Vec3A::Z);
+        let (distance, point) = cube.intersect(&ray, &RayType::Camera).unwrap();
+        assert!((distance - (10. - 14_f32.powf(0.25))).abs() < 1e-4);
+        assert!(cube.get_surface_normal(&point, 0.).z < -0.9);
+
+        // x² + y² - z² - 1, a hyperboloid of one sheet clipped by the box.
+        let hyperboloid = algebraic(
+            &[
+                (1., [2, 0, 0]),
+                (1., [0, 2, 0]),
+                (-1., [0, 0, 2]),
+                (-1., [0, 0, 0]),
+            ],
+            Vec3A::new(5., 5., 2.),
+        )
+        .unwrap();
+        let ray = Ray::new(&Vec3A::new(-10., 0., 0.), &Vec3A::X);
+        let (distance, point) = hyperboloid.intersect(&ray, &RayType::Camera).unwrap();
+        assert!((distance - 9.).abs() < 1e-4);
+        assert!((hyperboloid.get_surface_normal(&point, 0.) - Vec3A::NEG_X).length() < 1e-4);
+    }
+
+    #[test]
+    fn test_failure_intersect() {
+        let hyperboloid = algebraic(
+            &[
+                (1., [2, 0, 0]),
+                (1., [0, 2, 0]),
+                (-1., [0, 0, 2]),
+                (-1., [0, 0, 0]),
+            ],
+            Vec3A::new(5., 5., 2.),
+        )
+        .unwrap();
+        // Along its axis, the ray stays inside the hyperboloid.
+        let ray = Ray::new(&Vec3A::new(0., 0., -10.), &Vec3A::Z);
+        assert_eq!(hyperboloid.intersect(&ray, &RayType::Camera), None);
+        // Beyond the box, the hyperboloid is clipped.
+        let ray = Ray::new(&Vec3A::new(-10., 0., 3.), &Vec3A::X);
+        assert_eq!(hyperboloid.intersect(&ray, &RayType::Camera), None);
+        let ray = Ray::new(&Vec3A::new(-10., 0., 0.), &Vec3A::new(5., 0., 0.));
+        assert_eq!(hyperboloid.intersect(&ray, &RayType::Light), None);
+
+        assert!(algebraic(&[], Vec3A::ONE).is_err());
+        assert!(algebraic(&[(1., [1, 0, 0])], Vec3A::new(1., 0., 1.)).is_err());
+        assert!(algebraic(&[(1., [MAX_DEGREE, 0, 0])], Vec3A::ONE).is_ok());
+        assert!(algebraic(&[(1., [MAX_DEGREE, 1, 0])], Vec3A::ONE).is_err());
+        assert!(algebraic(&[(1., [u32::MAX, u32::MAX, 0])], Vec3A::ONE).is_err());
+    }
+}
@@ -0,0 +1,217 @@
+use std::f32::consts::TAU;
+
+use glam::{Vec2, Vec3A};
+use serde::{Deserialize, Serialize};
+
+use crate::entity::actor::{Actor, ActorTrait, DirectionalActorTrait};
+use crate::entity::geometry::{Geometry, RayType, aabb::Aabb, ray::Ray};
+use crate::entity::rendering::material::{MaterialBound, MaterialType};
+
+/// Structure representing a cone standing along the Y axis, capped at its base, its position being the base's center
+/// and its apex lying at the given height above.
+#[derive(Serialize, Deserialize)]
+pub struct Cone {
+    #[serde(flatten)]
+    pub actor: Actor,
+    pub radius: f32,
+    pub height: f32,
+    material: MaterialType,
+}
+
+impl std::ops::Deref for Cone {
+    type Target = Actor;
+    fn deref(&self) -> &Self::Target {
+        &self.actor
+    }
+}
+
+impl MaterialBound for Cone {
+    fn get_material(&self) -> &MaterialType {
+        &self.material
+    }
+}
+
+impl Cone {
+    /// Fail if the radius or the height is not finite and above zero, the cone then being empty.
+    pub fn new(
+        position: &Vec3A,
+        radius: f32,
+        height: f32,
+        material: &MaterialType,
+    ) -> Result<Self, String> {
+        if [radius, height].iter().any(|x| !x.is_finite() || *x <= 0.) {
+            return Err("the radius and the height must be finite and above zero".to_string());
+        }
+        Ok(Self {
+            actor: Actor::new(position),
+            radius,
+            height,
+            material: material.to_owned(),
+        })
+    }
+
+    pub fn set_velocity(&mut self, velocity: &Vec3A) {
+        self.actor.velocity = *velocity;
+    }
+
+    /// Get the ratio between the radius and the distance to the apex, constant along the side.
+    fn get_slope(&self) -> f32 {
+        self.radius / self.height
+    }
+
+    /// Tell whether the point, relative to the base's center, lies on the base rather than on the side.
+    fn is_on_base(&self, local: &Vec3A) -> bool {
+        let side = (local.x.hypot(local.z) - self.get_slope() * (self.height - local.y)).abs();
+        local.y.abs() < side
+    }
+}
+
+impl ActorTrait for Cone {
+    fn get_position(&self) -> Vec3A {
+        self.actor.get_position()
+    }
+
+    fn get_velocity(&self) -> Vec3A {
+        self.actor.get_velocity()
+    }
+}
+
+impl Geometry for Cone {
+    /// The side's normal follows the gradient of x² + z² - (slope (height - y))², pointing up at the apex.
+    fn get_surface_normal(&self, point: &Vec3A, time: f32) -> Vec3A {
+        let local = point - self.get_position_at(time);
+        if self.is_on_base(&local) {
+            return -Vec3A::Y;
+        }
+        let distance = local.x.hypot(local.z);
+        Vec3A::new(local.x, self.get_slope() * distance, local.z)
+            .try_normalize()
+            .unwrap_or(Vec3A::Y)
+    }
+
+    /// Map the side around and along the axis, and the base on the unit square.
+    fn get_uv(&self, point: &Vec3A, time: f32) -> Vec2 {
+        let local = point - self.get_position_at(time);
+        match self.is_on_base(&local) {
+            true => Vec2::new(local.x, local.z) / self.radius * 0.5 + 0.5,
+            false => Vec2::new(0.5 + local.z.atan2(local.x) / TAU, local.y / self.height),
+        }
+    }
+
+    fn get_bounds(&self, time_start: f32, time_end: f32) -> Aabb {
+        let low = Vec3A::new(self.radius, 0., self.radius);
+        let high = Vec3A::new(self.radius, self.height, self.radius);
+        [time_start, time_end]
+            .iter()
+            .map(|x| self.get_position_at(*x))
+            .fold(Aabb::EMPTY, |bounds, base| {
+                bounds.union(&Aabb::new(base - low, base + high))
+            })
+    }
+
+    /// Check the ray against the double cone within the base's and apex's heights, then against the base's disk.
+    fn intersect(&self, ray: &Ray, ray_type: &RayType) -> Option<(f32, Vec3A)> {
+        let d = ray.get_direction();
+        let o = ray.get_position() - self.get_position_at(ray.get_time());
+        let k2 = self.get_slope() * self.get_slope();
+        let h = self.height - o.y;
+
+        let a = d.x * d.x + d.z * d.z - k2 * d.y * d.y;
+        let b = 2. * (o.x * d.x + o.z * d.z + k2 * h * d.y);
+        let c = o.x * o.x + o.z * o.z - k2 * h * h;
+        let side = match (a, b * b - 4. * a * c) {
+            (0., _) if b != 0. => vec![-c / b],
+            (a, discr) if a != 0. && discr >= 0. => {
+                let root = discr.sqrt();
+                vec![(-b - root) / (2. * a), (-b + root) / (2. * a)]
+            }
+            _ => Vec::new(),
+        };
+        let base = match d.y {
+            0. => None,
+            y => Some(-o.y / y),
+        };
+
+        let side = side.into_iter().filter(|t| {
+            let y = o.y + t * d.y;
+            (0. ..=self.height).contains(&y)
+        });
+        let base = base.into_iter().filter(|t| {
+            let p = o + t * d;
+            p.x * p.x + p.z * p.z <= self.radius * self.radius
+        });
+        ray_type
+            .nearest(side.chain(base))
+            .map(|t| (t, ray.get_position() + t * d))
+    }
+}
+
+// #####################################
+
+#[cfg(test)]
+mod tests {
+    use glam::{Vec2, Vec3A, Vec4};
+
+    use crate::entity::{
+        geometry::{Geometry, RayType, cone::Cone, ray::Ray},
+        rendering::material::{ColorMaterial, MaterialType},
+    };
+
+    fn cone() -> Cone {
+        Cone::new(
+            &Vec3A::new(0., -2., 10.),
+            2.,
+            4.,
+            &MaterialType::Color(ColorMaterial::new(Vec4::ONE)),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_success_intersect() {
+        let cone = cone();
+        // Halfway up, the cone's radius is 1.
+        let ray = Ray::new(&Vec3A::ZERO, &Vec3A::Z);
+        let (distance, point) = cone.intersect(&ray, &RayType::Camera).unwrap();
+        assert_eq!(distance, 9.);
+        let normal = cone.get_surface_normal(&point, 0.);
+        assert!((normal - Vec3A::new(0., 1., -2.).normalize()).length() < 1e-6);
+        assert_eq!(cone.get_uv(&point, 0.), Vec2::new(0.25, 0.5));
+
+        // From below, the ray hits the base.
+        let ray = Ray::new(&Vec3A::new(0., -10., 11.), &Vec3A::Y);
+        let (distance, point) = cone.intersect(&ray, &RayType::Camera).unwrap();
+        assert_eq!(distance, 8.);
+        assert_eq!(cone.get_surface_normal(&point, 0.), -Vec3A::Y);
+        assert_eq!(cone.get_uv(&point, 0.), Vec2::new(0.5, 0.75));
+    }
+
+    #[test]
+    fn test_failure_intersect() {
+        let cone = cone();
+        // Beside the cone near its apex, where the double cone's other nappe is not part of the cone.
+        let ray = Ray::new(&Vec3A::new(1.5, 1.5, 0.), &Vec3A::Z);
+        assert_eq!(cone.intersect(&ray, &RayType::Camera), None);
+        let ray = Ray::new(&Vec3A::new(0., 3., 10.), &Vec3A::new(0., 2., 0.));
+        assert_eq!(cone.intersect(&ray, &RayType::Camera), None);
+
+        assert!(
+            Cone::new(
+                &Vec3A::ZERO,
+                -1.,
+                1.,
+                &MaterialType::Color(ColorMaterial::new(Vec4::ONE))
+            )
+            .is_err()
+        );
+        assert!(
+            Cone::new(
+                &Vec3A::ZERO,
+                1.,
+                0.,
+                &MaterialType::Color(ColorMaterial::new(Vec4::ONE))
+            )
+            .is_err()
+        );
+    }
+}
@@ -0,0 +1,369 @@
+use glam::{Vec2, Vec3A};
+use serde::{Deserialize, Serialize};
+
+use crate::entity::actor::{ActorTrait, DirectionalActorTrait};
+use crate::entity::geometry::{Geometry, GeometryType, RayType, Solid, aabb::Aabb, ray::Ray};
+use crate::entity::rendering::material::{MaterialBound, MaterialType};
+
+/// Boolean operation combining two solids.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CsgOperation {
+    Union,
+    Intersection,
+    /// The left solid, minus the right one.
+    Difference,
+}
+
+impl CsgOperation {
+    /// Tell whether a point inside or outside each operand is inside the combination.
+    fn contains(&self, left: bool, right: bool) -> bool {
+        match self {
+            CsgOperation::Union => left || right,
+            CsgOperation::Intersection => left && right,
+            CsgOperation::Difference => left && !right,
+        }
+    }
+
+    /// Combine the operands' intervals along a ray, sweeping through their boundaries in order.
+    pub fn combine(&self, left: &[(f32, f32)], right: &[(f32, f32)]) -> Vec<(f32, f32)> {
+        let mut boundaries: Vec<(f32, bool)> = left
+            .iter()
+            .flat_map(|(start, end)| [(*start, true), (*end, true)])
+            .chain(
+                right
+                    .iter()
+                    .flat_map(|(start, end)| [(*start, false), (*end, false)]),
+            )
+            .collect();
+        boundaries.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let (mut inside_left, mut inside_right) = (false, false);
+        let mut start = None;
+        let mut intervals = Vec::new();
+        for (t, is_left) in boundaries {
+            match is_left {
+                true => inside_left = !inside_left,
+                false => inside_right = !inside_right,
+            }
+            match (start, self.contains(inside_left, inside_right)) {
+                (None, true) => start = Some(t),
+                (Some(s), false) => {
+                    if s < t {
+                        intervals.push((s, t));
+                    }
+                    start = None;
+                }
+                _ => (),
+            }
+        }
+        intervals
+    }
+}
+
+/// Structure representing the constructive solid geometry combination of two solids, which can be combinations
+/// themselves. The whole combination is rendered with its own material, and moves at its own velocity on top of its
+/// operands' ones.
+#[derive(Serialize, Deserialize)]
+#[serde(try_from = "CsgData")]
+pub struct Csg {
+    pub operation: CsgOperation,
+    left: Box<GeometryType>,
+    right: Box<GeometryType>,
+    material: MaterialType,
+    velocity: Vec3A,
+}
+
+/// Serialized fields of a CSG combination, checked while being deserialized.
+#[derive(Deserialize)]
+struct CsgData {
+    operation: CsgOperation,
+    left: Box<GeometryType>,
+    right: Box<GeometryType>,
+    material: MaterialType,
+    #[serde(default)]
+    velocity: Vec3A,
+}
+
+impl TryFrom<CsgData> for Csg {
+    type Error = String;
+
+    fn try_from(data: CsgData) -> Result<Self, Self::Error> {
+        let mut csg = Csg::new(data.operation, *data.left, *data.right, &data.material)?;
+        csg.set_velocity(&data.velocity);
+        Ok(csg)
+    }
+}
+
+impl MaterialBound for Csg {
+    fn get_material(&self) -> &MaterialType {
+        &self.material
+    }
+}
+
+impl Csg {
+    /// Fail if any of the operands is not a closed solid, its inside then being undefined.
+    pub fn new(
+        operation: CsgOperation,
+        left: GeometryType,
+        right: GeometryType,
+        material: &MaterialType,
+    ) -> Result<Self, String> {
+        if let Some(operand) = [&left, &right].into_iter().find(|x| x.as_solid().is_none()) {
+            return Err(format!("a {} is not a closed solid", operand.get_name()));
+        }
+        Ok(Self {
+            operation,
+            left: Box::new(left),
+            right: Box::new(right),
+            material: material.to_owned(),
+            velocity: Vec3A::ZERO,
+        })
+    }
+
+    pub fn get_left(&self) -> &GeometryType {
+        &self.left
+    }
+
+    pub fn get_right(&self) -> &GeometryType {
+        &self.right
+    }
+
+    /// Move the whole combination at the given velocity, the operands keeping their own ones.
+    pub fn set_velocity(&mut self, velocity: &Vec3A) {
+        self.velocity = *velocity;
+    }
+
+    /// Get the offset of the combination at the given time, due to its velocity.
+    fn get_offset(&self, time: f32) -> Vec3A {
+        self.velocity * time
+    }
+
+    /// Get the ray relative to the operands, keeping the same ray's parameter.
+    fn get_operands_ray(&self, ray: &Ray) -> Ray {
+        let offset = self.get_offset(ray.get_time());
+        Ray::new(&(ray.get_position() - offset), &ray.get_direction()).with_time(ray.get_time())
+    }
+
+    /// Get the intervals of an operand, which is always a solid.
+    fn get_operand_intervals(operand: &GeometryType, ray: &Ray) -> Vec<(f32, f32)> {
+        operand
+            .as_solid()
+            .map(|x| x.get_intervals(ray))
+            .unwrap_or_default()
+    }
+
+    /// Get the distance to the surface of an operand, which is always a solid.
+    fn get_operand_distance(operand: &GeometryType, point: &Vec3A, time: f32) -> f32 {
+        operand
+            .as_solid()
+            .map_or(f32::INFINITY, |x| x.get_surface_distance(point, time))
+    }
+
+    /// Get the operand whose surface the point (relative to the operands) lies on, along with whether its normal is
+    /// flipped, which is the case for the surface subtracted by a difference.
+    fn get_surface(&self, point: &Vec3A, time: f32) -> (&GeometryType, bool) {
+        let left = Self::get_operand_distance(&self.left, point, time);
+        let right = Self::get_operand_distance(&self.right, point, time);
+        match left <= right {
+            true => (&self.left, false),
+            false => (&self.right, self.operation == CsgOperation::Difference),
+        }
+    }
+}
+
+impl ActorTrait for Csg {
+    fn get_position(&self) -> Vec3A {
+        self.left.get_position()
+    }
+
+    fn get_velocity(&self) -> Vec3A {
+        self.velocity
+    }
+}
+
+impl Geometry for Csg {
+    fn get_surface_normal(&self, point: &Vec3A, time: f32) -> Vec3A {
+        let point = point - self.get_offset(time);
+        match self.get_surface(&point, time) {
+            (operand, false) => operand.get_surface_normal(&point, time),
+            (operand, true) => -operand.get_surface_normal(&point, time),
+        }
+    }
+
+    fn get_uv(&self, point: &Vec3A, time: f32) -> Vec2 {
+        let point = point - self.get_offset(time);
+        self.get_surface(&point, time).0.get_uv(&point, time)
+    }
+
+    fn get_bounds(&self, time_start: f32, time_end: f32) -> Aabb {
+        let left = self.left.get_bounds(time_start, time_end);
+        let bounds = match self.operation {
+            CsgOperation::Union => left.union(&self.right.get_bounds(time_start, time_end)),
+            CsgOperation::Intersection => {
+                left.intersection(&self.right.get_bounds(time_start, time_end))
+            }
+            CsgOperation::Difference => left,
+        };
+        [time_start, time_end]
+            .iter()
+            .map(|x| self.get_offset(*x))
+            .fold(Aabb::EMPTY, |moved, offset| {
+                moved.union(&Aabb::new(bounds.min + offset, bounds.max + offset))
+            })
+    }
+
+    /// The ray hits the combination's surface on the nearest boundary of its intervals.
+    fn intersect(&self, ray: &Ray, ray_type: &RayType) -> Option<(f32, Vec3A)> {
+        ray_type
+            .nearest(
+                self.get_intervals(ray)
+                    .into_iter()
+                    .flat_map(|(start, end)| [start, end]),
+            )
+            .map(|t| (t, ray.get_position() + t * ray.get_direction()))
+    }
+}
+
+impl Solid for Csg {
+    fn get_intervals(&self, ray: &Ray) -> Vec<(f32, f32)> {
+        let ray = self.get_operands_ray(ray);
+        let left = Self::get_operand_intervals(&self.left, &ray);
+        if left.is_empty() && self.operation != CsgOperation::Union {
+            return left;
+        }
+        self.operation
+            .combine(&left, &Self::get_operand_intervals(&self.right, &ray))
+    }
+
+    fn get_surface_distance(&self, point: &Vec3A, time: f32) -> f32 {
+        let point = point - self.get_offset(time);
+        let left = Self::get_operand_distance(&self.left, &point, time);
+        left.min(Self::get_operand_distance(&self.right, &point, time))
+    }
+}
+
+// #####################################
+
+#[cfg(test)]
+mod tests {
+    use glam::{Vec3A, Vec4};
+
+    use crate::entity::{
+        actor::ActorTrait,
+        geometry::{
+            Geometry, GeometryType, RayType,
+            csg::{Csg, CsgOperation},
+            cuboid::Cuboid,
+            cylinder::Cylinder,
+            plane::Plane,
+            ray::Ray,
+            sphere::Sphere,
+        },
+        rendering::material::{ColorMaterial, MaterialType},
+    };
+
+    fn material() -> MaterialType {
+        MaterialType::Color(ColorMaterial::new(Vec4::ONE))
+    }
+
+    fn csg(operation: CsgOperation) -> Csg {
+        let cuboid = Cuboid::new(&Vec3A::new(0., 0., 10.), &Vec3A::splat(4.), &material()).unwrap();
+        let sphere = Sphere::new(&Vec3A::new(0., 0., 8.), 1., &material()).unwrap();
+        Csg::new(
+            operation,
+            GeometryType::Cuboid(cuboid),
+            GeometryType::Sphere(sphere),
+            &material(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_success_intersect() {
+        let ray = Ray::new(&Vec3A::ZERO, &Vec3A::Z);
+        let (distance, point) = csg(CsgOperation::Union)
+            .intersect(&ray, &RayType::Camera)
+            .unwrap();
+        assert_eq!(distance, 7.);
+        assert_eq!(point, Vec3A::new(0., 0., 7.));
+
+        // Moving the whole combination, its operands keeping their own velocities.
+        let mut moving = csg(CsgOperation::Union);
+        moving.set_velocity(&Vec3A::new(0., 10., 0.));
+        assert_eq!(moving.get_left().get_velocity(), Vec3A::ZERO);
+        let moved = Ray::new(&Vec3A::new(0., 10., 0.), &Vec3A::Z).with_time(1.);
+        assert_eq!(
+            moving.intersect(&moved, &RayType::Camera),
+            Some((7., Vec3A::new(0., 10., 7.)))
+        );
+        let serialized = toml::to_string(&GeometryType::Csg(moving)).unwrap();
+        let deserialized: GeometryType = toml::from_str(&serialized).unwrap();
+        assert_eq!(deserialized.get_velocity(), Vec3A::new(0., 10., 0.));
+
+        let intersection = csg(CsgOperation::Intersection);
+        let (distance, point) = intersection.intersect(&ray, &RayType::Camera).unwrap();
+        assert_eq!(distance, 8.);
+        assert_eq!(intersection.get_surface_normal(&point, 0.), -Vec3A::Z);
+
+        // The hole's surface faces inwards, towards the sphere's center.
+        let difference = csg(CsgOperation::Difference);
+        let (distance, point) = difference.intersect(&ray, &RayType::Camera).unwrap();
+        assert_eq!(distance, 9.);
+        assert_eq!(difference.get_surface_normal(&point, 0.), -Vec3A::Z);
+
+        // Nested: drilling a cylinder through the difference.
+        let cylinder = Cylinder::new(&Vec3A::new(1., -5., 10.), 0.5, 10., &material()).unwrap();
+        let nested = Csg::new(
+            CsgOperation::Difference,
+            GeometryType::Csg(difference),
+            GeometryType::Cylinder(cylinder),
+            &material(),
+        )
+        .unwrap();
+        let ray = Ray::new(&Vec3A::new(0., 0., 10.), &Vec3A::new(0.25, 0., 0.));
+        let (distance, point) = nested.intersect(&ray, &RayType::Camera).unwrap();
+        assert_eq!(distance, 2.);
+        assert_eq!(point, Vec3A::new(0.5, 0., 10.));
+        assert_eq!(nested.get_surface_normal(&point, 0.), Vec3A::X);
+    }
+
+    #[test]
+    fn test_failure_intersect() {
+        // Through the sphere's hole, missing the box.
+        let ray = Ray::new(&Vec3A::new(0., 2.5, 0.), &Vec3A::Z);
+        assert_eq!(
+            csg(CsgOperation::Intersection).intersect(&ray, &RayType::Camera),
+            None
+        );
+
+        // The whole intersection lies before the screen.
+        let ray = Ray::new(&Vec3A::new(0., 0., 8.), &Vec3A::X);
+        assert_eq!(
+            csg(CsgOperation::Intersection).intersect(&ray, &RayType::Camera),
+            None
+        );
+
+        // Planes are not closed.
+        let plane = Plane::new(&Vec3A::ZERO, &Vec3A::Y, &material()).unwrap();
+        let sphere = Sphere::new(&Vec3A::ZERO, 1., &material()).unwrap();
+        assert!(
+            Csg::new(
+                CsgOperation::Union,
+                GeometryType::Plane(plane),
+                GeometryType::Sphere(sphere),
+                &material()
+            )
+            .is_err()
+        );
+        let serialized = toml::to_string(&GeometryType::Csg(csg(CsgOperation::Union))).unwrap();
+        let error = toml::from_str::<GeometryType>(
+            &serialized
+                .replace("type = \"sphere\"", "type = \"plane\"")
+                .replace("radius = 1.0", "direction = [0.0, 1.0, 0.0]"),
+        )
+        .err()
+        .unwrap();
+        assert!(error.message().contains("a plane is not a closed solid"));
+    }
+}
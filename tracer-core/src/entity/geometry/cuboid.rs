@@ -0,0 +1,185 @@
+use glam::{Vec2, Vec3A};
+use serde::{Deserialize, Serialize};
+
+use crate::entity::actor::{Actor, ActorTrait, DirectionalActorTrait};
+use crate::entity::geometry::{Geometry, RayType, Solid, aabb::Aabb, ray::Ray};
+use crate::entity::rendering::material::{MaterialBound, MaterialType};
+
+/// Structure representing an axis aligned box, centered on its position.
+#[derive(Serialize, Deserialize)]
+pub struct Cuboid {
+    #[serde(flatten)]
+    pub actor: Actor,
+    pub size: Vec3A,
+    material: MaterialType,
+}
+
+impl std::ops::Deref for Cuboid {
+    type Target = Actor;
+    fn deref(&self) -> &Self::Target {
+        &self.actor
+    }
+}
+
+impl MaterialBound for Cuboid {
+    fn get_material(&self) -> &MaterialType {
+        &self.material
+    }
+}
+
+impl Cuboid {
+    /// Fail if the size is not finite and above zero along every axis, the box then being empty.
+    pub fn new(position: &Vec3A, size: &Vec3A, material: &MaterialType) -> Result<Self, String> {
+        if !size.is_finite() || size.min_element() <= 0. {
+            return Err("the size must be finite and above zero along every axis".to_string());
+        }
+        Ok(Self {
+            actor: Actor::new(position),
+            size: *size,
+            material: material.to_owned(),
+        })
+    }
+
+    pub fn set_velocity(&mut self, velocity: &Vec3A) {
+        self.actor.velocity = *velocity;
+    }
+
+    /// Get the point relative to the box's half size, each coordinate of a point on the surface being within
+    /// [-1, 1], and one of them being -1 or 1.
+    fn get_relative(&self, point: &Vec3A, time: f32) -> Vec3A {
+        (point - self.get_position_at(time)) / (self.size / 2.)
+    }
+
+    /// Get the axis perpendicular to the face containing the point.
+    fn get_face_axis(relative: &Vec3A) -> usize {
+        let distance = relative.abs();
+        (0..3)
+            .max_by(|a, b| distance[*a].total_cmp(&distance[*b]))
+            .unwrap()
+    }
+}
+
+impl ActorTrait for Cuboid {
+    fn get_position(&self) -> Vec3A {
+        self.actor.get_position()
+    }
+
+    fn get_velocity(&self) -> Vec3A {
+        self.actor.get_velocity()
+    }
+}
+
+impl Geometry for Cuboid {
+    fn get_surface_normal(&self, point: &Vec3A, time: f32) -> Vec3A {
+        let relative = self.get_relative(point, time);
+        let axis = Self::get_face_axis(&relative);
+        Vec3A::AXES[axis] * relative[axis].signum()
+    }
+
+    /// Map each face on the unit square, along the two axes following the face's one.
+    fn get_uv(&self, point: &Vec3A, time: f32) -> Vec2 {
+        let relative = self.get_relative(point, time);
+        let axis = Self::get_face_axis(&relative);
+        Vec2::new(relative[(axis + 1) % 3], relative[(axis + 2) % 3]) * 0.5 + 0.5
+    }
+
+    fn get_bounds(&self, time_start: f32, time_end: f32) -> Aabb {
+        let half = self.size / 2.;
+        [time_start, time_end]
+            .iter()
+            .map(|x| self.get_position_at(*x))
+            .fold(Aabb::EMPTY, |bounds, center| {
+                bounds.union(&Aabb::new(center - half, center + half))
+            })
+    }
+
+    /// Check the ray against the box's slabs, the ray entering the box when crossing the last of its near slabs.
+    fn intersect(&self, ray: &Ray, ray_type: &RayType) -> Option<(f32, Vec3A)> {
+        let (near, far) = self
+            .get_bounds(ray.get_time(), ray.get_time())
+            .intersect(ray)?;
+        ray_type
+            .nearest([near, far])
+            .map(|t| (t, ray.get_position() + t * ray.get_direction()))
+    }
+}
+
+impl Solid for Cuboid {
+    fn get_intervals(&self, ray: &Ray) -> Vec<(f32, f32)> {
+        self.get_bounds(ray.get_time(), ray.get_time())
+            .intersect(ray)
+            .into_iter()
+            .collect()
+    }
+
+    /// Get the absolute value of the box's signed distance.
+    fn get_surface_distance(&self, point: &Vec3A, time: f32) -> f32 {
+        let q = (point - self.get_position_at(time)).abs() - self.size / 2.;
+        (q.max(Vec3A::ZERO).length() + q.max_element().min(0.)).abs()
+    }
+}
+
+// #####################################
+
+#[cfg(test)]
+mod tests {
+    use glam::{Vec2, Vec3A, Vec4};
+
+    use crate::entity::{
+        geometry::{Geometry, RayType, cuboid::Cuboid, ray::Ray},
+        rendering::material::{ColorMaterial, MaterialType},
+    };
+
+    fn cuboid() -> Cuboid {
+        Cuboid::new(
+            &Vec3A::new(0., 0., 10.),
+            &Vec3A::new(2., 4., 6.),
+            &MaterialType::Color(ColorMaterial::new(Vec4::ONE)),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_success_intersect() {
+        let cuboid = cuboid();
+        let ray = Ray::new(&Vec3A::new(0.5, 1., 0.), &Vec3A::Z);
+        let (distance, point) = cuboid.intersect(&ray, &RayType::Camera).unwrap();
+        assert_eq!(distance, 7.);
+        assert_eq!(cuboid.get_surface_normal(&point, 0.), -Vec3A::Z);
+        assert_eq!(cuboid.get_uv(&point, 0.), Vec2::new(0.75, 0.75));
+
+        // From inside, the ray leaves through the far face.
+        let ray = Ray::new(&Vec3A::new(0., 0., 10.), &Vec3A::new(-0.5, 0., 0.));
+        let (distance, point) = cuboid.intersect(&ray, &RayType::Camera).unwrap();
+        assert_eq!(distance, 2.);
+        assert_eq!(cuboid.get_surface_normal(&point, 0.), -Vec3A::X);
+    }
+
+    #[test]
+    fn test_failure_intersect() {
+        let cuboid = cuboid();
+        let ray = Ray::new(&Vec3A::new(1.5, 0., 0.), &Vec3A::Z);
+        assert_eq!(cuboid.intersect(&ray, &RayType::Camera), None);
+
+        // The light ray stops before reaching the box.
+        let ray = Ray::new(&Vec3A::ZERO, &Vec3A::new(0., 0., 5.));
+        assert_eq!(cuboid.intersect(&ray, &RayType::Light), None);
+
+        assert!(
+            Cuboid::new(
+                &Vec3A::ZERO,
+                &Vec3A::ZERO,
+                &MaterialType::Color(ColorMaterial::new(Vec4::ONE))
+            )
+            .is_err()
+        );
+        assert!(
+            Cuboid::new(
+                &Vec3A::ZERO,
+                &Vec3A::new(1., -1., 1.),
+                &MaterialType::Color(ColorMaterial::new(Vec4::ONE))
+            )
+            .is_err()
+        );
+    }
+}
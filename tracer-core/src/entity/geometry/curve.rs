@@ -0,0 +1,431 @@
+use glam::{Vec2, Vec3A};
+use serde::{Deserialize, Serialize};
+
+use crate::entity::actor::{Actor, ActorTrait, DirectionalActorTrait};
+use crate::entity::geometry::patch::{bernstein, split_curve};
+use crate::entity::geometry::{Geometry, RayType, aabb::Aabb, ray::Ray};
+use crate::entity::rendering::material::{MaterialBound, MaterialType};
+
+/// Deepest subdivision of a segment, each level halving it.
+const MAX_SUBDIVISIONS: i32 = 10;
+/// Number of points sampled along each segment, to seed the search of the closest point of the curve.
+const SEARCH_SAMPLES: usize = 8;
+const NEWTON_ITERATIONS: usize = 8;
+
+/// Get the point of a cubic Bézier curve, and its derivative, at the given parameter.
+fn evaluate(points: &[Vec3A; 4], t: f32) -> (Vec3A, Vec3A) {
+    let (basis, derivative) = bernstein(t);
+    points.iter().enumerate().fold(
+        (Vec3A::ZERO, Vec3A::ZERO),
+        |(point, tangent), (index, x)| {
+            (point + *x * basis[index], tangent + *x * derivative[index])
+        },
+    )
+}
+
+/// Structure representing a fiber, such as a hair, a grass blade or a wire: a tube swept along a piecewise cubic
+/// Bézier curve, relative to its position. The curve has 3n + 1 control points, consecutive segments sharing their
+/// ends, and its widths are spread evenly along it and linearly interpolated.
+/// Rays are intersected in their own space, where the segments are split until flat enough to be approached by
+/// lines facing the ray.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(try_from = "CurveData", into = "CurveData")]
+pub struct Curve {
+    pub actor: Actor,
+    control_points: Vec<Vec3A>,
+    widths: Vec<f32>,
+    segments: Vec<[Vec3A; 4]>,
+    max_width: f32,
+    material: MaterialType,
+}
+
+/// Serialized fields of a curve, the segments being rebuilt out of the control points.
+#[derive(Serialize, Deserialize)]
+struct CurveData {
+    #[serde(flatten)]
+    actor: Actor,
+    control_points: Vec<Vec3A>,
+    widths: Vec<f32>,
+    material: MaterialType,
+}
+
+impl TryFrom<CurveData> for Curve {
+    type Error = String;
+
+    fn try_from(data: CurveData) -> Result<Self, Self::Error> {
+        let mut curve = Curve::new(
+            &data.actor.position,
+            data.control_points,
+            data.widths,
+            &data.material,
+        )?;
+        curve.set_velocity(&data.actor.velocity);
+        Ok(curve)
+    }
+}
+
+impl From<Curve> for CurveData {
+    fn from(curve: Curve) -> Self {
+        Self {
+            actor: curve.actor,
+            control_points: curve.control_points,
+            widths: curve.widths,
+            material: curve.material,
+        }
+    }
+}
+
+impl std::ops::Deref for Curve {
+    type Target = Actor;
+    fn deref(&self) -> &Self::Target {
+        &self.actor
+    }
+}
+
+impl MaterialBound for Curve {
+    fn get_material(&self) -> &MaterialType {
+        &self.material
+    }
+}
+
+impl Curve {
+    /// Cut the control points in segments, failing if their count does not fit, or if a width is negative.
+    pub fn new(
+        position: &Vec3A,
+        control_points: Vec<Vec3A>,
+        widths: Vec<f32>,
+        material: &MaterialType,
+    ) -> Result<Self, String> {
+        if control_points.len() < 4 || control_points.len() % 3 != 1 {
+            return Err("a curve needs 3n + 1 control points".to_string());
+        }
+        if widths.is_empty() || widths.iter().any(|x| x.is_nan() || *x < 0.) {
+            return Err("a curve needs at least one width, none of them negative".to_string());
+        }
+
+        let segments = control_points
+            .windows(4)
+            .step_by(3)
+            .map(|x| [x[0], x[1], x[2], x[3]])
+            .collect();
+        Ok(Self {
+            actor: Actor::new(position),
+            max_width: widths.iter().copied().fold(0., f32::max),
+            control_points,
+            widths,
+            segments,
+            material: material.to_owned(),
+        })
+    }
+
+    pub fn get_control_points(&self) -> &Vec<Vec3A> {
+        &self.control_points
+    }
+
+    pub fn get_widths(&self) -> &Vec<f32> {
+        &self.widths
+    }
+
+    pub fn set_velocity(&mut self, velocity: &Vec3A) {
+        self.actor.velocity = *velocity;
+    }
+
+    /// Get the width at the given parameter along the whole curve, within [0, 1].
+    fn get_width(&self, u: f32) -> f32 {
+        match self.widths.len() {
+            1 => self.widths[0],
+            count => {
+                let x = u.clamp(0., 1.) * (count - 1) as f32;
+                let index = (x as usize).min(count - 2);
+                let fraction = x - index as f32;
+                self.widths[index] * (1. - fraction) + self.widths[index + 1] * fraction
+            }
+        }
+    }
+
+    /// Find the segment and parameter of the curve's point closest to the given point, relative to the position,
+    /// by Gauss-Newton iterations from the nearest sample.
+    fn find_parameter(&self, local: &Vec3A) -> (usize, f32) {
+        let mut best = (0, 0., f32::INFINITY);
+        for (index, segment) in self.segments.iter().enumerate() {
+            for sample in 0..=SEARCH_SAMPLES {
+                let t = sample as f32 / SEARCH_SAMPLES as f32;
+                let distance = evaluate(segment, t).0.distance_squared(*local);
+                if distance < best.2 {
+                    best = (index, t, distance);
+                }
+            }
+        }
+
+        let (index, mut t, _) = best;
+        for _ in 0..NEWTON_ITERATIONS {
+            let (point, tangent) = evaluate(&self.segments[index], t);
+            let length = tangent.length_squared();
+            if length == 0. {
+                break;
+            }
+            t = (t + tangent.dot(local - point) / length).clamp(0., 1.);
+        }
+        (index, t)
+    }
+
+    /// Get the number of times to halve a segment, given in the ray's space, so that its lines stay within a
+    /// fraction of its width from it.
+    fn get_depth(&self, points: &[Vec3A; 4]) -> i32 {
+        let curvature = (0..2)
+            .map(|i| (points[i] - 2. * points[i + 1] + points[i + 2]).length())
+            .fold(0., f32::max);
+        let tolerance = self.max_width / 20.;
+        let depth = (std::f32::consts::SQRT_2 * 6. * curvature / (8. * tolerance)).log2() / 2.;
+        match depth.is_finite() {
+            true => (depth.ceil() as i32).clamp(0, MAX_SUBDIVISIONS),
+            false => MAX_SUBDIVISIONS,
+        }
+    }
+
+    /// Intersect a part of a segment, given in the ray's space where the ray runs along Z, keeping the nearest
+    /// accepted depth along the ray.
+    #[allow(clippy::too_many_arguments)]
+    fn intersect_segment(
+        &self,
+        points: &[Vec3A; 4],
+        index: usize,
+        (t_start, t_end): (f32, f32),
+        depth: i32,
+        length: f32,
+        ray_type: &RayType,
+        nearest: &mut Option<f32>,
+    ) {
+        let radius = self.max_width / 2.;
+        let (min, max) = points
+            .iter()
+            .fold((Vec3A::INFINITY, Vec3A::NEG_INFINITY), |(min, max), x| {
+                (min.min(*x), max.max(*x))
+            });
+        let (start, end) = ray_type.get_range();
+        let end = nearest.map_or(end, |x| x.min(end));
+        if min.x > radius
+            || max.x < -radius
+            || min.y > radius
+            || max.y < -radius
+            || max.z + radius < start * length
+            || min.z - radius > end * length
+        {
+            return;
+        }
+
+        if depth > 0 {
+            let middle = (t_start + t_end) / 2.;
+            let (first, second) = split_curve(*points);
+            self.intersect_segment(
+                &first,
+                index,
+                (t_start, middle),
+                depth - 1,
+                length,
+                ray_type,
+                nearest,
+            );
+            self.intersect_segment(
+                &second,
+                index,
+                (middle, t_end),
+                depth - 1,
+                length,
+                ray_type,
+                nearest,
+            );
+            return;
+        }
+
+        // Take the point of the line closest to the ray, then measure the curve's distance to the ray there.
+        let line = Vec2::new(points[3].x - points[0].x, points[3].y - points[0].y);
+        let w = match line.length_squared() {
+            0. => 0.5,
+            x => (-Vec2::new(points[0].x, points[0].y).dot(line) / x).clamp(0., 1.),
+        };
+        let center = evaluate(points, w).0;
+        let u = (index as f32 + t_start + w * (t_end - t_start)) / self.segments.len() as f32;
+        let radius = self.get_width(u) / 2.;
+        let distance = center.x * center.x + center.y * center.y;
+        if distance > radius * radius {
+            return;
+        }
+
+        let offset = (radius * radius - distance).sqrt();
+        if let Some(t) =
+            ray_type.nearest([(center.z - offset) / length, (center.z + offset) / length])
+            && nearest.is_none_or(|x| t < x)
+        {
+            *nearest = Some(t);
+        }
+    }
+}
+
+impl ActorTrait for Curve {
+    fn get_position(&self) -> Vec3A {
+        self.actor.get_position()
+    }
+
+    fn get_velocity(&self) -> Vec3A {
+        self.actor.get_velocity()
+    }
+}
+
+impl Geometry for Curve {
+    /// Get the direction from the closest point of the curve, perpendicular to the curve.
+    fn get_surface_normal(&self, point: &Vec3A, time: f32) -> Vec3A {
+        let local = point - self.get_position_at(time);
+        let (index, t) = self.find_parameter(&local);
+        let (center, tangent) = evaluate(&self.segments[index], t);
+        let tangent = tangent.normalize_or(Vec3A::Y);
+        let offset = local - center;
+        (offset - tangent * offset.dot(tangent)).normalize_or(tangent.any_orthonormal_vector())
+    }
+
+    /// Map U along the whole curve, and V around it.
+    fn get_uv(&self, point: &Vec3A, time: f32) -> Vec2 {
+        let local = point - self.get_position_at(time);
+        let (index, t) = self.find_parameter(&local);
+        let (center, tangent) = evaluate(&self.segments[index], t);
+        let (x_axis, y_axis) = tangent.normalize_or(Vec3A::Y).any_orthonormal_pair();
+        let offset = local - center;
+        let angle = offset.dot(y_axis).atan2(offset.dot(x_axis));
+        Vec2::new(
+            (index as f32 + t) / self.segments.len() as f32,
+            angle / std::f32::consts::TAU + 0.5,
+        )
+    }
+
+    /// Get the box containing the control points, which contain the curve, grown by half the widest width.
+    fn get_bounds(&self, time_start: f32, time_end: f32) -> Aabb {
+        let margin = Vec3A::splat(self.max_width / 2.);
+        let bounds = self.control_points.iter().fold(Aabb::EMPTY, |bounds, x| {
+            bounds.union(&Aabb::new(x - margin, x + margin))
+        });
+        [time_start, time_end]
+            .iter()
+            .map(|x| self.get_position_at(*x))
+            .fold(Aabb::EMPTY, |moved, position| {
+                moved.union(&Aabb::new(bounds.min + position, bounds.max + position))
+            })
+    }
+
+    /// Move the segments into the ray's space, where the ray starts at the origin and runs along Z.
+    fn intersect(&self, ray: &Ray, ray_type: &RayType) -> Option<(f32, Vec3A)> {
+        let length = ray.get_direction().length();
+        if length == 0. || self.max_width == 0. {
+            return None;
+        }
+        let origin = ray.get_position() - self.get_position_at(ray.get_time());
+        let z_axis = ray.get_direction() / length;
+        let (x_axis, y_axis) = z_axis.any_orthonormal_pair();
+        let to_ray = |x: Vec3A| {
+            let x = x - origin;
+            Vec3A::new(x.dot(x_axis), x.dot(y_axis), x.dot(z_axis))
+        };
+
+        let mut nearest: Option<f32> = None;
+        for (index, segment) in self.segments.iter().enumerate() {
+            let points = segment.map(to_ray);
+            let depth = self.get_depth(&points);
+            self.intersect_segment(
+                &points,
+                index,
+                (0., 1.),
+                depth,
+                length,
+                ray_type,
+                &mut nearest,
+            );
+        }
+        nearest.map(|t| (t, ray.get_position() + t * ray.get_direction()))
+    }
+
+    fn get_tangent(&self, point: &Vec3A, time: f32) -> Option<Vec3A> {
+        let (index, t) = self.find_parameter(&(point - self.get_position_at(time)));
+        Some(evaluate(&self.segments[index], t).1.normalize_or(Vec3A::Y))
+    }
+}
+
+// #####################################
+
+#[cfg(test)]
+mod tests {
+    use glam::{Vec3A, Vec4};
+
+    use crate::entity::{
+        geometry::{Geometry, RayType, curve::Curve, ray::Ray},
+        rendering::material::{ColorMaterial, MaterialType},
+    };
+
+    fn curve(control_points: Vec<Vec3A>, widths: Vec<f32>) -> Result<Curve, String> {
+        Curve::new(
+            &Vec3A::ZERO,
+            control_points,
+            widths,
+            &MaterialType::Color(ColorMaterial::new(Vec4::ONE)),
+        )
+    }
+
+    /// Get the control points of a straight curve along X, from -10 to 10.
+    fn straight() -> Vec<Vec3A> {
+        (0..7)
+            .map(|x| Vec3A::new(-10. + x as f32 * 20. / 6., 0., 0.))
+            .collect()
+    }
+
+    #[test]
+    fn test_success_intersect() {
+        let tube = curve(straight(), vec![2.]).unwrap();
+        let ray = Ray::new(&Vec3A::new(0., 0., -10.), &Vec3A::Z);
+        let (distance, point) = tube.intersect(&ray, &RayType::Camera).unwrap();
+        assert!((distance - 9.).abs() < 1e-3);
+        assert!((tube.get_surface_normal(&point, 0.) - Vec3A::NEG_Z).length() < 1e-3);
+        assert!((tube.get_tangent(&point, 0.).unwrap() - Vec3A::X).length() < 1e-3);
+        assert!((tube.get_uv(&point, 0.).x - 0.5).abs() < 1e-3);
+
+        // Off the axis, the ray hits the tube's round side.
+        let ray = Ray::new(&Vec3A::new(3., 0.6, -10.), &Vec3A::Z);
+        let (distance, point) = tube.intersect(&ray, &RayType::Camera).unwrap();
+        assert!((distance - 9.2).abs() < 1e-3);
+        let normal = tube.get_surface_normal(&point, 0.);
+        assert!((normal - Vec3A::new(0., 0.6, -0.8)).length() < 1e-3);
+
+        // The width shrinks from 4 to 0 along the curve.
+        let tapered = curve(straight(), vec![4., 0.]).unwrap();
+        let ray = Ray::new(&Vec3A::new(-5., 1.2, -10.), &Vec3A::Z);
+        assert!(tapered.intersect(&ray, &RayType::Camera).is_some());
+
+        // A bent curve is hit along its bend, its tangent following it.
+        let arc = curve(
+            vec![
+                Vec3A::new(-10., 0., 0.),
+                Vec3A::new(-10., 10., 0.),
+                Vec3A::new(10., 10., 0.),
+                Vec3A::new(10., 0., 0.),
+            ],
+            vec![1.],
+        )
+        .unwrap();
+        let ray = Ray::new(&Vec3A::new(0., 7.5, -10.), &Vec3A::Z);
+        let (distance, point) = arc.intersect(&ray, &RayType::Camera).unwrap();
+        assert!((distance - 9.5).abs() < 1e-2);
+        assert!(arc.get_tangent(&point, 0.).unwrap().x.abs() > 0.999);
+    }
+
+    #[test]
+    fn test_failure_intersect() {
+        let tapered = curve(straight(), vec![4., 0.]).unwrap();
+        let ray = Ray::new(&Vec3A::new(5., 1.2, -10.), &Vec3A::Z);
+        assert_eq!(tapered.intersect(&ray, &RayType::Camera), None);
+        let ray = Ray::new(&Vec3A::new(-12.5, 0., -10.), &Vec3A::Z);
+        assert_eq!(tapered.intersect(&ray, &RayType::Camera), None);
+        let ray = Ray::new(&Vec3A::new(0., 0., -10.), &Vec3A::new(0., 0., 5.));
+        assert_eq!(tapered.intersect(&ray, &RayType::Light), None);
+
+        assert!(curve(straight()[..6].to_vec(), vec![1.]).is_err());
+        assert!(curve(straight(), vec![]).is_err());
+        assert!(curve(straight(), vec![1., -1.]).is_err());
+    }
+}
@@ -0,0 +1,235 @@
+use std::f32::consts::TAU;
+
+use glam::{Vec2, Vec3A};
+use serde::{Deserialize, Serialize};
+
+use crate::entity::actor::{Actor, ActorTrait, DirectionalActorTrait};
+use crate::entity::geometry::{Geometry, RayType, Solid, aabb::Aabb, ray::Ray};
+use crate::entity::rendering::material::{MaterialBound, MaterialType};
+
+/// Structure representing a capped cylinder standing along the Y axis, its position being the bottom cap's center.
+#[derive(Serialize, Deserialize)]
+pub struct Cylinder {
+    #[serde(flatten)]
+    pub actor: Actor,
+    pub radius: f32,
+    pub height: f32,
+    material: MaterialType,
+}
+
+impl std::ops::Deref for Cylinder {
+    type Target = Actor;
+    fn deref(&self) -> &Self::Target {
+        &self.actor
+    }
+}
+
+impl MaterialBound for Cylinder {
+    fn get_material(&self) -> &MaterialType {
+        &self.material
+    }
+}
+
+impl Cylinder {
+    /// Fail if the radius or the height is not finite and above zero, the cylinder then being empty.
+    pub fn new(
+        position: &Vec3A,
+        radius: f32,
+        height: f32,
+        material: &MaterialType,
+    ) -> Result<Self, String> {
+        if [radius, height].iter().any(|x| !x.is_finite() || *x <= 0.) {
+            return Err("the radius and the height must be finite and above zero".to_string());
+        }
+        Ok(Self {
+            actor: Actor::new(position),
+            radius,
+            height,
+            material: material.to_owned(),
+        })
+    }
+
+    pub fn set_velocity(&mut self, velocity: &Vec3A) {
+        self.actor.velocity = *velocity;
+    }
+
+    /// Tell whether the point, relative to the bottom cap's center, lies on a cap rather than on the side.
+    fn is_on_cap(&self, local: &Vec3A) -> bool {
+        let side = (local.x.hypot(local.z) - self.radius).abs();
+        local.y.abs().min((local.y - self.height).abs()) < side
+    }
+
+    /// Get the ray's parameters where it crosses the infinite cylinder within the caps' heights, then the caps'
+    /// disks.
+    fn get_crossings(&self, ray: &Ray) -> Vec<f32> {
+        let d = ray.get_direction();
+        let o = ray.get_position() - self.get_position_at(ray.get_time());
+
+        let a = d.x * d.x + d.z * d.z;
+        let b = 2. * (o.x * d.x + o.z * d.z);
+        let c = o.x * o.x + o.z * o.z - self.radius * self.radius;
+        let discr = b * b - 4. * a * c;
+        let side = match a > 0. && discr >= 0. {
+            true => {
+                let root = discr.sqrt();
+                vec![(-b - root) / (2. * a), (-b + root) / (2. * a)]
+            }
+            false => Vec::new(),
+        };
+        let caps = match d.y {
+            0. => Vec::new(),
+            y => vec![-o.y / y, (self.height - o.y) / y],
+        };
+
+        let side = side.into_iter().filter(|t| {
+            let y = o.y + t * d.y;
+            (0. ..=self.height).contains(&y)
+        });
+        let caps = caps.into_iter().filter(|t| {
+            let p = o + t * d;
+            p.x * p.x + p.z * p.z <= self.radius * self.radius
+        });
+        side.chain(caps).collect()
+    }
+}
+
+impl ActorTrait for Cylinder {
+    fn get_position(&self) -> Vec3A {
+        self.actor.get_position()
+    }
+
+    fn get_velocity(&self) -> Vec3A {
+        self.actor.get_velocity()
+    }
+}
+
+impl Geometry for Cylinder {
+    fn get_surface_normal(&self, point: &Vec3A, time: f32) -> Vec3A {
+        let local = point - self.get_position_at(time);
+        match self.is_on_cap(&local) {
+            true if local.y > self.height / 2. => Vec3A::Y,
+            true => -Vec3A::Y,
+            false => Vec3A::new(local.x, 0., local.z) / self.radius,
+        }
+    }
+
+    /// Map the side around and along the axis, and each cap on the unit square.
+    fn get_uv(&self, point: &Vec3A, time: f32) -> Vec2 {
+        let local = point - self.get_position_at(time);
+        match self.is_on_cap(&local) {
+            true => Vec2::new(local.x, local.z) / self.radius * 0.5 + 0.5,
+            false => Vec2::new(0.5 + local.z.atan2(local.x) / TAU, local.y / self.height),
+        }
+    }
+
+    fn get_bounds(&self, time_start: f32, time_end: f32) -> Aabb {
+        let low = Vec3A::new(self.radius, 0., self.radius);
+        let high = Vec3A::new(self.radius, self.height, self.radius);
+        [time_start, time_end]
+            .iter()
+            .map(|x| self.get_position_at(*x))
+            .fold(Aabb::EMPTY, |bounds, base| {
+                bounds.union(&Aabb::new(base - low, base + high))
+            })
+    }
+
+    fn intersect(&self, ray: &Ray, ray_type: &RayType) -> Option<(f32, Vec3A)> {
+        ray_type
+            .nearest(self.get_crossings(ray))
+            .map(|t| (t, ray.get_position() + t * ray.get_direction()))
+    }
+}
+
+impl Solid for Cylinder {
+    /// The cylinder being convex, the ray is inside between its first and last crossings.
+    fn get_intervals(&self, ray: &Ray) -> Vec<(f32, f32)> {
+        let crossings = self.get_crossings(ray);
+        let first = crossings.iter().copied().min_by(f32::total_cmp);
+        let last = crossings.iter().copied().max_by(f32::total_cmp);
+        first.zip(last).filter(|(a, b)| a < b).into_iter().collect()
+    }
+
+    /// Get the absolute value of the cylinder's signed distance.
+    fn get_surface_distance(&self, point: &Vec3A, time: f32) -> f32 {
+        let local = point - self.get_position_at(time);
+        let d = Vec2::new(
+            local.x.hypot(local.z) - self.radius,
+            (local.y - self.height / 2.).abs() - self.height / 2.,
+        );
+        (d.max_element().min(0.) + d.max(Vec2::ZERO).length()).abs()
+    }
+}
+
+// #####################################
+
+#[cfg(test)]
+mod tests {
+    use glam::{Vec2, Vec3A, Vec4};
+
+    use crate::entity::{
+        geometry::{Geometry, RayType, aabb::Aabb, cylinder::Cylinder, ray::Ray},
+        rendering::material::{ColorMaterial, MaterialType},
+    };
+
+    fn cylinder() -> Cylinder {
+        Cylinder::new(
+            &Vec3A::new(0., -1., 10.),
+            2.,
+            4.,
+            &MaterialType::Color(ColorMaterial::new(Vec4::ONE)),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_success_intersect() {
+        let cylinder = cylinder();
+        let ray = Ray::new(&Vec3A::ZERO, &Vec3A::Z);
+        let (distance, point) = cylinder.intersect(&ray, &RayType::Camera).unwrap();
+        assert_eq!(distance, 8.);
+        assert_eq!(cylinder.get_surface_normal(&point, 0.), -Vec3A::Z);
+        assert_eq!(cylinder.get_uv(&point, 0.), Vec2::new(0.25, 0.25));
+
+        // From above, the ray hits the top cap.
+        let ray = Ray::new(&Vec3A::new(1., 10., 10.), &-Vec3A::Y);
+        let (distance, point) = cylinder.intersect(&ray, &RayType::Camera).unwrap();
+        assert_eq!(distance, 7.);
+        assert_eq!(cylinder.get_surface_normal(&point, 0.), Vec3A::Y);
+        assert_eq!(cylinder.get_uv(&point, 0.), Vec2::new(0.75, 0.5));
+
+        assert_eq!(
+            cylinder.get_bounds(0., 0.),
+            Aabb::new(Vec3A::new(-2., -1., 8.), Vec3A::new(2., 3., 12.))
+        );
+    }
+
+    #[test]
+    fn test_failure_intersect() {
+        let cylinder = cylinder();
+        // Above the top cap.
+        let ray = Ray::new(&Vec3A::new(0., 3.5, 0.), &Vec3A::Z);
+        assert_eq!(cylinder.intersect(&ray, &RayType::Camera), None);
+        // Along the axis, but beside the cylinder.
+        let ray = Ray::new(&Vec3A::new(3., 10., 10.), &-Vec3A::Y);
+        assert_eq!(cylinder.intersect(&ray, &RayType::Camera), None);
+
+        assert!(
+            Cylinder::new(
+                &Vec3A::ZERO,
+                0.,
+                1.,
+                &MaterialType::Color(ColorMaterial::new(Vec4::ONE))
+            )
+            .is_err()
+        );
+        assert!(
+            Cylinder::new(
+                &Vec3A::ZERO,
+                1.,
+                f32::INFINITY,
+                &MaterialType::Color(ColorMaterial::new(Vec4::ONE))
+            )
+            .is_err()
+        );
+    }
+}
@@ -0,0 +1,175 @@
+use glam::{Vec2, Vec3A};
+use serde::{Deserialize, Serialize};
+
+use crate::entity::actor::{ActorTrait, DirectionalActor, DirectionalActorTrait};
+use crate::entity::geometry::{Geometry, RayType, aabb::Aabb, ray::Ray};
+use crate::entity::rendering::material::{MaterialBound, MaterialType};
+
+/// Structure representing a flat disk, centered on its position and facing its direction.
+/// Unlike planes, disks are hit from both sides.
+#[derive(Serialize, Deserialize)]
+pub struct Disk {
+    #[serde(flatten)]
+    dir_actor: DirectionalActor,
+    pub radius: f32,
+    material: MaterialType,
+}
+
+impl std::ops::Deref for Disk {
+    type Target = DirectionalActor;
+    fn deref(&self) -> &Self::Target {
+        &self.dir_actor
+    }
+}
+
+impl MaterialBound for Disk {
+    fn get_material(&self) -> &MaterialType {
+        &self.material
+    }
+}
+
+impl Disk {
+    /// Fail if the normal is not finite and non-zero, or if the radius is not finite and above zero, the disk then
+    /// having no orientation or being empty.
+    pub fn new(
+        position: &Vec3A,
+        direction: &Vec3A,
+        radius: f32,
+        material: &MaterialType,
+    ) -> Result<Self, String> {
+        if !direction.is_finite() || *direction == Vec3A::ZERO {
+            return Err("the normal must be finite and non-zero".to_string());
+        }
+        if !radius.is_finite() || radius <= 0. {
+            return Err("the radius must be finite and above zero".to_string());
+        }
+        Ok(Self {
+            dir_actor: DirectionalActor::new(position, direction),
+            radius,
+            material: material.to_owned(),
+        })
+    }
+
+    pub fn set_velocity(&mut self, velocity: &Vec3A) {
+        self.dir_actor.set_velocity(velocity);
+    }
+}
+
+impl ActorTrait for Disk {
+    fn get_position(&self) -> Vec3A {
+        self.dir_actor.get_position()
+    }
+
+    fn get_velocity(&self) -> Vec3A {
+        self.dir_actor.get_velocity()
+    }
+}
+
+impl Geometry for Disk {
+    fn get_surface_normal(&self, _point: &Vec3A, _time: f32) -> Vec3A {
+        self.get_direction().normalize()
+    }
+
+    /// Map the disk on the unit square, along two axes perpendicular to its normal.
+    fn get_uv(&self, point: &Vec3A, time: f32) -> Vec2 {
+        let (tangent, bitangent) = self.get_direction().normalize().any_orthonormal_pair();
+        let local = point - self.get_position_at(time);
+        Vec2::new(local.dot(tangent), local.dot(bitangent)) / self.radius * 0.5 + 0.5
+    }
+
+    /// Along each axis, the disk extends by its radius times the sine of the angle between its normal and the axis.
+    fn get_bounds(&self, time_start: f32, time_end: f32) -> Aabb {
+        let normal = self.get_direction().normalize();
+        let extent = (Vec3A::ONE - normal * normal).max(Vec3A::ZERO).sqrt() * self.radius;
+        [time_start, time_end]
+            .iter()
+            .map(|x| self.get_position_at(*x))
+            .fold(Aabb::EMPTY, |bounds, center| {
+                bounds.union(&Aabb::new(center - extent, center + extent))
+            })
+    }
+
+    fn intersect(&self, ray: &Ray, ray_type: &RayType) -> Option<(f32, Vec3A)> {
+        let normal = self.get_direction();
+        let n_dot_d = ray.get_direction().dot(normal);
+        if n_dot_d == 0. {
+            return None;
+        }
+        let center = self.get_position_at(ray.get_time());
+        let t = (center - ray.get_position()).dot(normal) / n_dot_d;
+        let point = ray.get_position() + t * ray.get_direction();
+        match point.distance_squared(center) <= self.radius * self.radius {
+            true => ray_type.nearest([t]).map(|t| (t, point)),
+            false => None,
+        }
+    }
+}
+
+// #####################################
+
+#[cfg(test)]
+mod tests {
+    use glam::{Vec3A, Vec4};
+
+    use crate::entity::{
+        geometry::{Geometry, RayType, disk::Disk, ray::Ray},
+        rendering::material::{ColorMaterial, MaterialType},
+    };
+
+    fn disk() -> Disk {
+        Disk::new(
+            &Vec3A::new(0., 0., 10.),
+            &Vec3A::new(0., 0., -2.),
+            3.,
+            &MaterialType::Color(ColorMaterial::new(Vec4::ONE)),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_success_intersect() {
+        let disk = disk();
+        let ray = Ray::new(&Vec3A::new(2., 0., 0.), &Vec3A::Z);
+        let (distance, point) = disk.intersect(&ray, &RayType::Camera).unwrap();
+        assert_eq!(distance, 10.);
+        assert_eq!(disk.get_surface_normal(&point, 0.), -Vec3A::Z);
+        let uv = disk.get_uv(&point, 0.);
+        assert!(((uv - 0.5).length() - 1. / 3.).abs() < 1e-6);
+
+        // Disks are hit from behind too.
+        let ray = Ray::new(&Vec3A::new(0., 0., 20.), &-Vec3A::Z);
+        assert!(disk.intersect(&ray, &RayType::Camera).is_some());
+
+        let bounds = disk.get_bounds(0., 0.);
+        assert_eq!((bounds.min.z, bounds.max.z), (10., 10.));
+        assert_eq!((bounds.min.x, bounds.max.y), (-3., 3.));
+    }
+
+    #[test]
+    fn test_failure_intersect() {
+        let disk = disk();
+        let ray = Ray::new(&Vec3A::new(2.5, 2.5, 0.), &Vec3A::Z);
+        assert_eq!(disk.intersect(&ray, &RayType::Camera), None);
+        let ray = Ray::new(&Vec3A::ZERO, &Vec3A::X);
+        assert_eq!(disk.intersect(&ray, &RayType::Camera), None);
+
+        assert!(
+            Disk::new(
+                &Vec3A::ZERO,
+                &Vec3A::ZERO,
+                1.,
+                &MaterialType::Color(ColorMaterial::new(Vec4::ONE))
+            )
+            .is_err()
+        );
+        assert!(
+            Disk::new(
+                &Vec3A::ZERO,
+                &Vec3A::Z,
+                0.,
+                &MaterialType::Color(ColorMaterial::new(Vec4::ONE))
+            )
+            .is_err()
+        );
+    }
+}
@@ -0,0 +1,644 @@
+use std::{fs, io::BufReader, path::PathBuf};
+
+use glam::{Vec2, Vec3A};
+use serde::{Deserialize, Serialize};
+
+use crate::entity::actor::{Actor, ActorTrait, DirectionalActorTrait};
+use crate::entity::geometry::{Geometry, RayType, aabb::Aabb, ray::Ray};
+use crate::entity::rendering::material::{MaterialBound, MaterialType};
+
+/// Origin of a heightfield's elevations, each sample being a fraction of the heightfield's height.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum HeightfieldSource {
+    /// Grayscale PNG image, one sample per pixel, its rows running along X and its columns along Z.
+    /// The path is kept as written, while the image is read relative to the directory (the scene file's one).
+    Image {
+        path: PathBuf,
+        #[serde(skip)]
+        directory: PathBuf,
+    },
+    /// Square grid of fractal value noise, summing octaves of doubling frequency and halving amplitude, then
+    /// stretched to fill the whole height. The frequency is the number of features across the grid.
+    Noise {
+        resolution: usize,
+        frequency: f32,
+        #[serde(default = "default_octaves")]
+        octaves: u32,
+        #[serde(default)]
+        seed: u64,
+    },
+    /// Explicit rows of samples, running along X.
+    Grid { rows: Vec<Vec<f32>> },
+}
+
+fn default_octaves() -> u32 {
+    4
+}
+
+/// Highest number of octaves, the later ones being finer than the samples of any practical grid.
+const MAX_OCTAVES: u32 = 16;
+
+/// Highest number of samples along each side of a noise grid.
+const MAX_RESOLUTION: usize = 4096;
+
+/// Grid of samples, stored row after row.
+struct Samples {
+    columns: usize,
+    rows: usize,
+    heights: Vec<f32>,
+}
+
+impl HeightfieldSource {
+    fn load(&self) -> Result<Samples, String> {
+        let samples = match self {
+            HeightfieldSource::Image { path, directory } => {
+                let path = directory.join(path);
+                Self::load_image(&path).map_err(|error| format!("{}: {}", path.display(), error))?
+            }
+            HeightfieldSource::Noise {
+                resolution,
+                frequency,
+                octaves,
+                seed,
+            } => {
+                if *octaves > MAX_OCTAVES {
+                    return Err(format!("the noise has at most {MAX_OCTAVES} octaves"));
+                }
+                if *resolution > MAX_RESOLUTION {
+                    return Err(format!(
+                        "the noise's resolution is at most {MAX_RESOLUTION}"
+                    ));
+                }
+                Self::generate_noise(*resolution, *frequency, *octaves, *seed)?
+            }
+            HeightfieldSource::Grid { rows } => {
+                let columns = rows.first().map_or(0, |x| x.len());
+                if rows.iter().any(|x| x.len() != columns) {
+                    return Err("the grid's rows must have the same length".to_string());
+                }
+                Samples {
+                    columns,
+                    rows: rows.len(),
+                    heights: rows.concat(),
+                }
+            }
+        };
+        match samples.columns < 2 || samples.rows < 2 {
+            true => Err("a heightfield needs at least 2 by 2 samples".to_string()),
+            false => Ok(samples),
+        }
+    }
+
+    /// Read the first channel of the image, scaled to [0, 1] whatever its bit depth.
+    fn load_image(path: &PathBuf) -> Result<Samples, String> {
+        let file = fs::File::open(path).map_err(|error| error.to_string())?;
+        let mut decoder = png::Decoder::new(BufReader::new(file));
+        decoder.set_transformations(png::Transformations::EXPAND);
+        let mut reader = decoder.read_info().map_err(|error| error.to_string())?;
+        let mut buffer = vec![0; reader.output_buffer_size().unwrap_or_default()];
+        let info = reader
+            .next_frame(&mut buffer)
+            .map_err(|error| error.to_string())?;
+
+        let channels = info.color_type.samples();
+        let heights = match info.bit_depth {
+            png::BitDepth::Sixteen => buffer
+                .chunks_exact(2 * channels)
+                .map(|x| u16::from_be_bytes([x[0], x[1]]) as f32 / u16::MAX as f32)
+                .collect(),
+            _ => buffer
+                .chunks_exact(channels)
+                .map(|x| x[0] as f32 / u8::MAX as f32)
+                .collect::<Vec<f32>>(),
+        };
+        let (columns, rows) = (info.width as usize, info.height as usize);
+        Ok(Samples {
+            columns,
+            rows,
+            heights: heights.into_iter().take(columns * rows).collect(),
+        })
+    }
+
+    fn generate_noise(
+        resolution: usize,
+        frequency: f32,
+        octaves: u32,
+        seed: u64,
+    ) -> Result<Samples, String> {
+        let count = resolution
+            .checked_mul(resolution)
+            .ok_or("the noise's resolution is too large")?;
+        let scale = frequency / resolution.saturating_sub(1).max(1) as f32;
+        let heights: Vec<f32> = (0..count)
+            .map(|index| {
+                let point = Vec2::new((index % resolution) as f32, (index / resolution) as f32);
+                (0..octaves).fold(0., |sum, octave| {
+                    let octave_scale = 2_f32.powi(octave as i32);
+                    sum + value_noise(point * scale * octave_scale, seed + octave as u64)
+                        / octave_scale
+                })
+            })
+            .collect();
+        let min = heights.iter().copied().fold(f32::INFINITY, f32::min);
+        let max = heights.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+        let range = (max - min).max(f32::EPSILON);
+        Ok(Samples {
+            columns: resolution,
+            rows: resolution,
+            heights: heights.iter().map(|x| (x - min) / range).collect(),
+        })
+    }
+}
+
+/// Smoothly interpolate pseudo-random values in [0, 1] given at the integer coordinates.
+fn value_noise(point: Vec2, seed: u64) -> f32 {
+    let lattice = |x: i64, y: i64| {
+        // SplitMix64's finalizer, mixing the coordinates and the seed.
+        let mut z = (x as u64)
+            .wrapping_mul(0x9e3779b97f4a7c15)
+            .wrapping_add((y as u64).wrapping_mul(0xc2b2ae3d27d4eb4f))
+            .wrapping_add(seed.wrapping_mul(0x165667b19e3779f9));
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        (z ^ (z >> 31)) as f32 / u64::MAX as f32
+    };
+    let cell = point.floor();
+    let (x, y) = (cell.x as i64, cell.y as i64);
+    let f = point - cell;
+    let f = f * f * (3. - 2. * f);
+    let bottom = lattice(x, y) + (lattice(x + 1, y) - lattice(x, y)) * f.x;
+    let top = lattice(x, y + 1) + (lattice(x + 1, y + 1) - lattice(x, y + 1)) * f.x;
+    bottom + (top - bottom) * f.y
+}
+
+/// Structure representing a terrain out of a grid of elevations, spanning the given size from its position, its
+/// lowest corner. Each cell of the grid is split in two triangles, whose normals are interpolated between the
+/// samples' ones. Rays walk through the cells they cross, skipping those they pass above.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(try_from = "HeightfieldData", into = "HeightfieldData")]
+pub struct Heightfield {
+    pub actor: Actor,
+    size: Vec3A,
+    source: HeightfieldSource,
+    columns: usize,
+    rows: usize,
+    heights: Vec<f32>,
+    normals: Vec<Vec3A>,
+    material: MaterialType,
+}
+
+/// Serialized fields of a heightfield, the samples being loaded out of their source.
+#[derive(Serialize, Deserialize)]
+struct HeightfieldData {
+    #[serde(flatten)]
+    actor: Actor,
+    size: Vec3A,
+    source: HeightfieldSource,
+    material: MaterialType,
+}
+
+impl TryFrom<HeightfieldData> for Heightfield {
+    type Error = String;
+
+    fn try_from(data: HeightfieldData) -> Result<Self, Self::Error> {
+        let mut heightfield = Heightfield::new(
+            &data.actor.position,
+            &data.size,
+            data.source,
+            &data.material,
+        )?;
+        heightfield.set_velocity(&data.actor.velocity);
+        Ok(heightfield)
+    }
+}
+
+impl From<Heightfield> for HeightfieldData {
+    fn from(heightfield: Heightfield) -> Self {
+        Self {
+            actor: heightfield.actor,
+            size: heightfield.size,
+            source: heightfield.source,
+            material: heightfield.material,
+        }
+    }
+}
+
+impl std::ops::Deref for Heightfield {
+    type Target = Actor;
+    fn deref(&self) -> &Self::Target {
+        &self.actor
+    }
+}
+
+impl MaterialBound for Heightfield {
+    fn get_material(&self) -> &MaterialType {
+        &self.material
+    }
+}
+
+impl Heightfield {
+    /// Load the samples out of the source, failing if they cannot be read or are too few, or if the size is invalid.
+    pub fn new(
+        position: &Vec3A,
+        size: &Vec3A,
+        source: HeightfieldSource,
+        material: &MaterialType,
+    ) -> Result<Self, String> {
+        Self::check_size(size)?;
+        let Samples {
+            columns,
+            rows,
+            heights,
+        } = source.load()?;
+        let mut heightfield = Self {
+            actor: Actor::new(position),
+            size: *size,
+            source,
+            columns,
+            rows,
+            heights,
+            normals: Vec::new(),
+            material: material.to_owned(),
+        };
+        heightfield.normals = (0..rows)
+            .flat_map(|z| (0..columns).map(move |x| (x, z)))
+            .map(|(x, z)| heightfield.get_sample_normal(x, z))
+            .collect();
+        Ok(heightfield)
+    }
+
+    /// Fail if the size is not finite and above zero along every axis, the cells then being degenerate.
+    pub fn check_size(size: &Vec3A) -> Result<(), String> {
+        match size.is_finite() && size.min_element() > 0. {
+            true => Ok(()),
+            false => Err("must be finite and above zero along every axis".to_string()),
+        }
+    }
+
+    pub fn get_size(&self) -> Vec3A {
+        self.size
+    }
+
+    /// Get the number of samples along X then Z.
+    pub fn get_resolution(&self) -> (usize, usize) {
+        (self.columns, self.rows)
+    }
+
+    pub fn get_source(&self) -> &HeightfieldSource {
+        &self.source
+    }
+
+    pub fn set_velocity(&mut self, velocity: &Vec3A) {
+        self.actor.velocity = *velocity;
+    }
+
+    fn get_height(&self, x: usize, z: usize) -> f32 {
+        self.heights[z * self.columns + x]
+    }
+
+    /// Get the size of a cell, from a sample to the next one.
+    fn get_cell_size(&self) -> Vec3A {
+        self.size / Vec3A::new((self.columns - 1) as f32, 1., (self.rows - 1) as f32)
+    }
+
+    /// Estimate the normal at a sample out of the slopes towards its neighbours.
+    fn get_sample_normal(&self, x: usize, z: usize) -> Vec3A {
+        let cell = self.get_cell_size();
+        let (left, right) = (x.saturating_sub(1), (x + 1).min(self.columns - 1));
+        let (back, front) = (z.saturating_sub(1), (z + 1).min(self.rows - 1));
+        let slope_x = (self.get_height(right, z) - self.get_height(left, z)) * cell.y
+            / ((right - left) as f32 * cell.x);
+        let slope_z = (self.get_height(x, front) - self.get_height(x, back)) * cell.y
+            / ((front - back) as f32 * cell.z);
+        Vec3A::new(-slope_x, 1., -slope_z).normalize()
+    }
+
+    /// Get the point in the grid's space, where the cells are unit squares and the height is a fraction of the
+    /// heightfield's one.
+    fn to_grid(&self, point: &Vec3A, time: f32) -> Vec3A {
+        (point - self.get_position_at(time)) / self.get_cell_size()
+    }
+
+    /// Get the cell containing the point of the grid's space, clamped within the grid.
+    fn get_cell(&self, grid: &Vec3A) -> (usize, usize) {
+        (
+            (grid.x.floor().max(0.) as usize).min(self.columns - 2),
+            (grid.z.floor().max(0.) as usize).min(self.rows - 2),
+        )
+    }
+
+    /// Get the cell's corners in the grid's space, ordered along X then Z.
+    fn get_corners(&self, x: usize, z: usize) -> [Vec3A; 4] {
+        [(0, 0), (1, 0), (0, 1), (1, 1)].map(|(i, j)| {
+            Vec3A::new(
+                (x + i) as f32,
+                self.get_height(x + i, z + j),
+                (z + j) as f32,
+            )
+        })
+    }
+
+    /// Intersect the cell's two triangles with the ray of the grid's space, returning the nearest accepted hit
+    /// within the given range of the ray's parameter.
+    fn intersect_cell(
+        &self,
+        (x, z): (usize, usize),
+        origin: &Vec3A,
+        direction: &Vec3A,
+        range: (f32, f32),
+        ray_type: &RayType,
+    ) -> Option<f32> {
+        let [c00, c10, c01, c11] = self.get_corners(x, z);
+        let tolerance = 1e-4 * (range.1 - range.0).abs().max(1.);
+        ray_type
+            .nearest(
+                [[c00, c10, c11], [c00, c11, c01]]
+                    .iter()
+                    .filter_map(|x| intersect_triangle(x, origin, direction)),
+            )
+            .filter(|t| (range.0 - tolerance..=range.1 + tolerance).contains(t))
+    }
+}
+
+/// Intersect a triangle with a ray, on both sides (Möller–Trumbore).
+fn intersect_triangle([a, b, c]: &[Vec3A; 3], origin: &Vec3A, direction: &Vec3A) -> Option<f32> {
+    let (edge_1, edge_2) = (b - a, c - a);
+    let p = direction.cross(edge_2);
+    let determinant = edge_1.dot(p);
+    if determinant.abs() < f32::EPSILON {
+        return None;
+    }
+    let s = (origin - a) / determinant;
+    let u = s.dot(p);
+    let q = s.cross(edge_1);
+    let v = direction.dot(q);
+    (u >= 0. && v >= 0. && u + v <= 1.).then(|| edge_2.dot(q))
+}
+
+impl ActorTrait for Heightfield {
+    fn get_position(&self) -> Vec3A {
+        self.actor.get_position()
+    }
+
+    fn get_velocity(&self) -> Vec3A {
+        self.actor.get_velocity()
+    }
+}
+
+impl Geometry for Heightfield {
+    /// Interpolate the samples' normals over the triangle containing the point, by its barycentric coordinates.
+    fn get_surface_normal(&self, point: &Vec3A, time: f32) -> Vec3A {
+        let grid = self.to_grid(point, time);
+        let (x, z) = self.get_cell(&grid);
+        let (u, v) = (
+            (grid.x - x as f32).clamp(0., 1.),
+            (grid.z - z as f32).clamp(0., 1.),
+        );
+        let normal = |i: usize, j: usize| self.normals[(z + j) * self.columns + x + i];
+        let normal = match u >= v {
+            true => normal(0, 0) * (1. - u) + normal(1, 0) * (u - v) + normal(1, 1) * v,
+            false => normal(0, 0) * (1. - v) + normal(1, 1) * u + normal(0, 1) * (v - u),
+        };
+        normal.normalize_or(Vec3A::Y)
+    }
+
+    /// Map the grid on the unit square, along X and Z.
+    fn get_uv(&self, point: &Vec3A, time: f32) -> Vec2 {
+        let local = (point - self.get_position_at(time)) / self.size;
+        Vec2::new(local.x, local.z)
+    }
+
+    fn get_bounds(&self, time_start: f32, time_end: f32) -> Aabb {
+        let lowest = self.heights.iter().copied().fold(0., f32::min);
+        let highest = self.heights.iter().copied().fold(0., f32::max);
+        let low = Vec3A::new(0., lowest * self.size.y, 0.);
+        let high = Vec3A::new(self.size.x, highest * self.size.y, self.size.z);
+        [time_start, time_end]
+            .iter()
+            .map(|x| self.get_position_at(*x))
+            .fold(Aabb::EMPTY, |bounds, corner| {
+                bounds.union(&Aabb::new(corner + low, corner + high))
+            })
+    }
+
+    /// Walk through the cells crossed by the ray within the heightfield's bounds (2D DDA), in the grid's space
+    /// where the ray's parameter is unchanged, and intersect the cells the ray does not pass above.
+    fn intersect(&self, ray: &Ray, ray_type: &RayType) -> Option<(f32, Vec3A)> {
+        let (near, far) = self
+            .get_bounds(ray.get_time(), ray.get_time())
+            .intersect(ray)?;
+        let (start, end) = ray_type.get_range();
+        let (start, end) = (start.max(near), end.min(far));
+        if start > end {
+            return None;
+        }
+        let cell_size = self.get_cell_size();
+        let origin = self.to_grid(&ray.get_position(), ray.get_time());
+        let direction = ray.get_direction() / cell_size;
+
+        let (mut x, mut z) = self.get_cell(&(origin + direction * start));
+        let step = |d: f32| match d {
+            0. => (0, f32::INFINITY),
+            d => (d.signum() as isize, d.recip().abs()),
+        };
+        let ((step_x, delta_x), (step_z, delta_z)) = (step(direction.x), step(direction.z));
+        let boundary = |cell: usize, o: f32, d: f32| match d {
+            0. => f32::INFINITY,
+            d if d > 0. => (cell as f32 + 1. - o) / d,
+            d => (cell as f32 - o) / d,
+        };
+        let mut next_x = boundary(x, origin.x, direction.x);
+        let mut next_z = boundary(z, origin.z, direction.z);
+
+        let mut enter = start;
+        while enter <= end {
+            let exit = next_x.min(next_z).min(end);
+            let lowest = (origin.y + direction.y * enter).min(origin.y + direction.y * exit);
+            let highest = self
+                .get_corners(x, z)
+                .iter()
+                .map(|x| x.y)
+                .fold(f32::MIN, f32::max);
+            if lowest <= highest
+                && let Some(t) =
+                    self.intersect_cell((x, z), &origin, &direction, (enter, exit), ray_type)
+            {
+                return Some((t, ray.get_position() + t * ray.get_direction()));
+            }
+
+            if exit >= end {
+                return None;
+            }
+            enter = exit;
+            if next_x < next_z {
+                match x.checked_add_signed(step_x) {
+                    Some(i) if i < self.columns - 1 => x = i,
+                    _ => return None,
+                }
+                next_x += delta_x;
+            } else {
+                match z.checked_add_signed(step_z) {
+                    Some(j) if j < self.rows - 1 => z = j,
+                    _ => return None,
+                }
+                next_z += delta_z;
+            }
+        }
+        None
+    }
+}
+
+// #####################################
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, io::BufWriter, path::PathBuf};
+
+    use glam::{Vec2, Vec3A, Vec4};
+
+    use crate::entity::{
+        geometry::{
+            Geometry, RayType,
+            aabb::Aabb,
+            heightfield::{Heightfield, HeightfieldSource},
+            ray::Ray,
+        },
+        rendering::material::{ColorMaterial, MaterialType},
+    };
+
+    fn material() -> MaterialType {
+        MaterialType::Color(ColorMaterial::new(Vec4::ONE))
+    }
+
+    /// A 4 by 4 units ramp, rising along X from 0 to 2.
+    fn ramp() -> Heightfield {
+        let source = HeightfieldSource::Grid {
+            rows: vec![vec![0., 0.5, 1.]; 3],
+        };
+        Heightfield::new(&Vec3A::ZERO, &Vec3A::new(4., 2., 4.), source, &material()).unwrap()
+    }
+
+    #[test]
+    fn test_success_intersect() {
+        let ramp = ramp();
+        assert_eq!(
+            ramp.get_bounds(0., 0.),
+            Aabb::new(Vec3A::ZERO, Vec3A::new(4., 2., 4.))
+        );
+
+        // Straight down onto the slope, where the height is 1.5.
+        let ray = Ray::new(&Vec3A::new(3., 10., 1.), &-Vec3A::Y);
+        let (distance, point) = ramp.intersect(&ray, &RayType::Camera).unwrap();
+        assert!((distance - 8.5).abs() < 1e-5);
+        let normal = ramp.get_surface_normal(&point, 0.);
+        assert!((normal - Vec3A::new(-0.5, 1., 0.).normalize()).length() < 1e-5);
+        assert!((ramp.get_uv(&point, 0.) - Vec2::new(0.75, 0.25)).length() < 1e-5);
+
+        // Grazing across the cells towards the slope.
+        let ray = Ray::new(&Vec3A::new(-2., 1., 3.5), &Vec3A::new(1., 0., -0.25));
+        let (distance, point) = ramp.intersect(&ray, &RayType::Camera).unwrap();
+        assert!((distance - 4.).abs() < 1e-5);
+        assert!((point - Vec3A::new(2., 1., 2.5)).length() < 1e-5);
+
+        let noise = HeightfieldSource::Noise {
+            resolution: 16,
+            frequency: 4.,
+            octaves: 3,
+            seed: 7,
+        };
+        let terrain =
+            Heightfield::new(&Vec3A::ZERO, &Vec3A::splat(10.), noise, &material()).unwrap();
+        assert_eq!(terrain.heights.len(), 256);
+        assert_eq!(terrain.heights.iter().copied().fold(0., f32::max), 1.);
+        let ray = Ray::new(&Vec3A::new(5., 20., 5.), &-Vec3A::Y);
+        assert!(terrain.intersect(&ray, &RayType::Camera).is_some());
+    }
+
+    #[test]
+    fn test_success_load_image() {
+        let path = std::env::temp_dir().join("tracer-core-test-heightfield.png");
+        let writer = BufWriter::new(fs::File::create(&path).unwrap());
+        let mut encoder = png::Encoder::new(writer, 3, 2);
+        encoder.set_color(png::ColorType::Grayscale);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().unwrap();
+        writer.write_image_data(&[0, 51, 255, 0, 0, 102]).unwrap();
+        writer.finish().unwrap();
+
+        // The image is read relative to its directory, while its path is serialized as written.
+        let source = HeightfieldSource::Image {
+            path: "tracer-core-test-heightfield.png".into(),
+            directory: std::env::temp_dir(),
+        };
+        let heightfield = Heightfield::new(&Vec3A::ZERO, &Vec3A::ONE, source, &material()).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!((heightfield.columns, heightfield.rows), (3, 2));
+        assert_eq!(heightfield.heights, [0., 0.2, 1., 0., 0., 0.4]);
+        assert_eq!(
+            toml::to_string(heightfield.get_source()).unwrap(),
+            "type = \"image\"\npath = \"tracer-core-test-heightfield.png\"\n"
+        );
+    }
+
+    #[test]
+    fn test_failure_intersect() {
+        let ramp = ramp();
+        // Above the slope, then beside the heightfield.
+        let ray = Ray::new(&Vec3A::new(-1., 2.5, 1.), &Vec3A::X);
+        assert_eq!(ramp.intersect(&ray, &RayType::Camera), None);
+        let ray = Ray::new(&Vec3A::new(5., 10., 1.), &-Vec3A::Y);
+        assert_eq!(ramp.intersect(&ray, &RayType::Camera), None);
+
+        let load = |source| Heightfield::new(&Vec3A::ZERO, &Vec3A::ONE, source, &material());
+        assert!(
+            load(HeightfieldSource::Grid {
+                rows: vec![vec![0., 1.], vec![0.]]
+            })
+            .is_err()
+        );
+        assert!(
+            load(HeightfieldSource::Grid {
+                rows: vec![vec![0.; 2]]
+            })
+            .is_err()
+        );
+        assert!(
+            load(HeightfieldSource::Image {
+                path: "missing.png".into(),
+                directory: PathBuf::new(),
+            })
+            .is_err()
+        );
+        assert!(
+            load(HeightfieldSource::Noise {
+                resolution: 4,
+                frequency: 1.,
+                octaves: 32,
+                seed: 0,
+            })
+            .is_err()
+        );
+        assert!(
+            load(HeightfieldSource::Noise {
+                resolution: usize::MAX,
+                frequency: 1.,
+                octaves: 1,
+                seed: 0,
+            })
+            .is_err()
+        );
+        let grid = HeightfieldSource::Grid {
+            rows: vec![vec![0.; 2]; 2],
+        };
+        [
+            Vec3A::new(1., 0., 1.),
+            Vec3A::new(-1., 1., 1.),
+            Vec3A::INFINITY,
+        ]
+        .iter()
+        .for_each(|size| {
+            assert!(Heightfield::new(&Vec3A::ZERO, size, grid.clone(), &material()).is_err());
+        });
+    }
+}
@@ -0,0 +1,321 @@
+use std::sync::Arc;
+
+use glam::{Affine3A, Vec2, Vec3A};
+use serde::{Deserialize, Serialize};
+
+use crate::entity::actor::{ActorTrait, DirectionalActorTrait};
+use crate::entity::geometry::transform::{Transform, TransformMatrices};
+use crate::entity::geometry::{Geometry, GeometryType, Solid, aabb::Aabb, ray::Ray, ray::RayType};
+use crate::entity::rendering::material::{MaterialBound, MaterialType};
+
+/// Placement of a shared geometry, defined in its own object space, with its own transform and optionally its own
+/// material. Any number of instances can share the same geometry without duplicating it.
+/// The transform is relative to the parent's space (the world's space by default), e.g. a scene graph's group.
+/// The whole instance moves at the given velocity, in the world's space.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(from = "InstanceData", into = "InstanceData")]
+pub struct Instance {
+    geometry: Arc<GeometryType>,
+    transform: Transform,
+    parent: Affine3A,
+    matrices: Box<TransformMatrices>,
+    material: Option<MaterialType>,
+    velocity: Vec3A,
+}
+
+/// Serialized fields of an instance, the matrices being rebuilt out of the transform.
+#[derive(Serialize, Deserialize)]
+struct InstanceData {
+    geometry: Arc<GeometryType>,
+    transform: Transform,
+    #[serde(default, skip_serializing_if = "is_identity")]
+    parent: Affine3A,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    material: Option<MaterialType>,
+    #[serde(default)]
+    velocity: Vec3A,
+}
+
+fn is_identity(affine: &Affine3A) -> bool {
+    *affine == Affine3A::IDENTITY
+}
+
+impl From<InstanceData> for Instance {
+    fn from(data: InstanceData) -> Self {
+        let mut instance = Instance::new(data.geometry, &data.transform, data.material);
+        instance.set_parent(&data.parent);
+        instance.set_velocity(&data.velocity);
+        instance
+    }
+}
+
+impl From<Instance> for InstanceData {
+    fn from(instance: Instance) -> Self {
+        Self {
+            geometry: instance.geometry,
+            transform: instance.transform,
+            parent: instance.parent,
+            material: instance.material,
+            velocity: instance.velocity,
+        }
+    }
+}
+
+impl Instance {
+    pub fn new(
+        geometry: Arc<GeometryType>,
+        transform: &Transform,
+        material: Option<MaterialType>,
+    ) -> Self {
+        Self {
+            geometry,
+            transform: *transform,
+            parent: Affine3A::IDENTITY,
+            matrices: Box::new(transform.into()),
+            material,
+            velocity: Vec3A::ZERO,
+        }
+    }
+
+    pub fn get_geometry(&self) -> &Arc<GeometryType> {
+        &self.geometry
+    }
+
+    pub fn get_transform(&self) -> &Transform {
+        &self.transform
+    }
+
+    pub fn get_parent(&self) -> &Affine3A {
+        &self.parent
+    }
+
+    /// Place the instance in the given parent's space, composing the parent's matrix with the instance's transform.
+    pub fn set_parent(&mut self, parent: &Affine3A) {
+        self.parent = *parent;
+        *self.matrices = (*parent * self.transform.to_affine()).into();
+    }
+
+    /// Get the material overriding the geometry's one, if any.
+    pub fn get_material_override(&self) -> Option<&MaterialType> {
+        self.material.as_ref()
+    }
+
+    pub fn set_velocity(&mut self, velocity: &Vec3A) {
+        self.velocity = *velocity;
+    }
+
+    /// Get the offset of the instance at the given time, due to its velocity.
+    fn get_offset(&self, time: f32) -> Vec3A {
+        self.velocity * time
+    }
+
+    /// Get the ray in the object's space, keeping the same ray's parameter.
+    fn get_object_ray(&self, ray: &Ray) -> Ray {
+        let offset = self.get_offset(ray.get_time());
+        let moved = Ray::new(&(ray.get_position() - offset), &ray.get_direction())
+            .with_time(ray.get_time());
+        self.matrices.ray_to_object(&moved)
+    }
+}
+
+impl ActorTrait for Instance {
+    fn get_position(&self) -> Vec3A {
+        self.matrices.point_to_world(&Vec3A::ZERO)
+    }
+
+    fn get_velocity(&self) -> Vec3A {
+        self.velocity
+    }
+}
+
+impl MaterialBound for Instance {
+    fn get_material(&self) -> &MaterialType {
+        self.material
+            .as_ref()
+            .unwrap_or_else(|| self.geometry.get_material())
+    }
+}
+
+impl Geometry for Instance {
+    fn get_surface_normal(&self, point: &Vec3A, time: f32) -> Vec3A {
+        let point = self
+            .matrices
+            .point_to_object(&(point - self.get_offset(time)));
+        self.matrices
+            .normal_to_world(&self.geometry.get_surface_normal(&point, time))
+    }
+
+    /// Get the geometry's texture coordinates, stretched along with the geometry.
+    fn get_uv(&self, point: &Vec3A, time: f32) -> Vec2 {
+        let point = self
+            .matrices
+            .point_to_object(&(point - self.get_offset(time)));
+        self.geometry.get_uv(&point, time)
+    }
+
+    fn get_bounds(&self, time_start: f32, time_end: f32) -> Aabb {
+        let bounds = self
+            .matrices
+            .bounds_to_world(&self.geometry.get_bounds(time_start, time_end));
+        [time_start, time_end]
+            .iter()
+            .map(|x| self.get_offset(*x))
+            .fold(Aabb::EMPTY, |moved, offset| {
+                moved.union(&Aabb::new(bounds.min + offset, bounds.max + offset))
+            })
+    }
+
+    /// Intersect the geometry with the ray moved into the object's space, keeping the same ray's parameter.
+    fn intersect(&self, ray: &Ray, ray_type: &RayType) -> Option<(f32, Vec3A)> {
+        self.geometry
+            .intersect(&self.get_object_ray(ray), ray_type)
+            .map(|(t, _)| (t, ray.get_position() + t * ray.get_direction()))
+    }
+
+    fn get_tangent(&self, point: &Vec3A, time: f32) -> Option<Vec3A> {
+        let point = self
+            .matrices
+            .point_to_object(&(point - self.get_offset(time)));
+        self.geometry
+            .get_tangent(&point, time)
+            .map(|x| self.matrices.direction_to_world(&x))
+    }
+}
+
+/// Instances of solids are solids, their distances being measured in the object's space.
+impl Solid for Instance {
+    fn get_intervals(&self, ray: &Ray) -> Vec<(f32, f32)> {
+        self.geometry
+            .as_solid()
+            .map(|x| x.get_intervals(&self.get_object_ray(ray)))
+            .unwrap_or_default()
+    }
+
+    fn get_surface_distance(&self, point: &Vec3A, time: f32) -> f32 {
+        let point = self
+            .matrices
+            .point_to_object(&(point - self.get_offset(time)));
+        self.geometry
+            .as_solid()
+            .map_or(f32::INFINITY, |x| x.get_surface_distance(&point, time))
+    }
+}
+
+// #####################################
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use glam::{Vec3A, Vec4};
+
+    use crate::entity::{
+        geometry::{
+            Geometry, GeometryType,
+            aabb::Aabb,
+            instance::Instance,
+            ray::{Ray, RayType},
+            sphere::Sphere,
+            transform::Transform,
+        },
+        rendering::material::{ColorMaterial, DiffuseMaterial, MaterialBound, MaterialType},
+    };
+
+    fn unit_sphere() -> Arc<GeometryType> {
+        Arc::new(GeometryType::Sphere(
+            Sphere::new(
+                &Vec3A::ZERO,
+                1.,
+                &MaterialType::Color(ColorMaterial::new(Vec4::ONE)),
+            )
+            .unwrap(),
+        ))
+    }
+
+    #[test]
+    fn test_success_intersect_ellipsoid() {
+        let ellipsoid = Instance::new(
+            unit_sphere(),
+            &Transform {
+                translation: Vec3A::new(0., 0., 10.),
+                scale: Vec3A::new(4., 1., 2.),
+                ..Default::default()
+            },
+            None,
+        );
+
+        let (distance, point) = ellipsoid
+            .intersect(&Ray::new(&Vec3A::ZERO, &Vec3A::Z), &RayType::Camera)
+            .unwrap();
+        assert!((distance - 8.).abs() < 1e-5);
+        assert!((ellipsoid.get_surface_normal(&point, 0.) + Vec3A::Z).length() < 1e-5);
+
+        // Past the sphere's radius, but within the stretched axis.
+        let ray = Ray::new(&Vec3A::new(3., 0., 0.), &Vec3A::Z);
+        let (_, point) = ellipsoid.intersect(&ray, &RayType::Camera).unwrap();
+        let normal = ellipsoid.get_surface_normal(&point, 0.);
+        assert!((normal.length() - 1.).abs() < 1e-5);
+        assert!(normal.x > 0. && normal.z < 0.);
+        // The normal follows the gradient of the ellipsoid's equation, x²/16 + y² + (z - 10)²/4 = 1.
+        let gradient = Vec3A::new(point.x / 16., point.y, (point.z - 10.) / 4.).normalize();
+        assert!((normal - gradient).length() < 1e-4);
+
+        assert_eq!(
+            ellipsoid.get_bounds(0., 1.),
+            Aabb::new(Vec3A::new(-4., -1., 8.), Vec3A::new(4., 1., 12.))
+        );
+    }
+
+    #[test]
+    fn test_success_instances_share_geometry() {
+        let sphere = unit_sphere();
+        let rotated = Instance::new(
+            sphere.clone(),
+            &Transform {
+                translation: Vec3A::new(5., 0., 0.),
+                rotation: Vec3A::new(0., 90., 0.),
+                scale: Vec3A::new(1., 1., 3.),
+            },
+            Some(MaterialType::Diffuse(DiffuseMaterial::new(0.5))),
+        );
+        let plain = Instance::new(sphere.clone(), &Transform::default(), None);
+        assert_eq!(Arc::strong_count(&sphere), 3);
+
+        // Rotated by 90 degrees around Y, the stretched Z axis lies along X.
+        let ray = Ray::new(&Vec3A::new(-10., 0., 0.), &Vec3A::X);
+        let (distance, _) = rotated.intersect(&ray, &RayType::Camera).unwrap();
+        assert!((distance - 12.).abs() < 1e-4);
+
+        assert_eq!(rotated.get_material().get_name(), "diffuse");
+        assert_eq!(plain.get_material().get_name(), "color");
+
+        let serialized = toml::to_string(&GeometryType::Instance(rotated)).unwrap();
+        let deserialized: GeometryType = toml::from_str(&serialized).unwrap();
+        assert!(
+            deserialized
+                .intersect(&ray, &RayType::Camera)
+                .is_some_and(|x| (x.0 - 12.).abs() < 1e-4)
+        );
+    }
+
+    #[test]
+    fn test_failure_intersect_outside_stretched_axis() {
+        let flattened = Instance::new(
+            unit_sphere(),
+            &Transform {
+                scale: Vec3A::new(1., 0.1, 1.),
+                ..Default::default()
+            },
+            None,
+        );
+        let ray = Ray::new(&Vec3A::new(-5., 0.5, 0.), &Vec3A::X);
+        assert_eq!(flattened.intersect(&ray, &RayType::Camera), None);
+
+        // A moving instance is missed where it was at the start.
+        let mut moving = Instance::new(unit_sphere(), &Transform::default(), None);
+        moving.set_velocity(&Vec3A::new(0., 10., 0.));
+        let ray = Ray::new(&Vec3A::new(-5., 0., 0.), &Vec3A::X);
+        assert!(moving.intersect(&ray, &RayType::Camera).is_some());
+        assert_eq!(moving.intersect(&ray.with_time(1.), &RayType::Camera), None);
+    }
+}
@@ -0,0 +1,288 @@
+use std::f32::consts::{PI, TAU};
+
+use glam::{Vec2, Vec3A};
+use serde::{Deserialize, Serialize};
+
+use crate::entity::actor::{Actor, ActorTrait, DirectionalActorTrait};
+use crate::entity::geometry::polynomial::{find_roots, solve_quadratic};
+use crate::entity::geometry::{Geometry, RayType, aabb::Aabb, ray::Ray};
+use crate::entity::rendering::material::{MaterialBound, MaterialType};
+
+/// Source of a field decreasing from 1 at its center to 0 at its radius, as (1 - d² / r²)².
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Metaball {
+    pub center: Vec3A,
+    pub radius: f32,
+}
+
+impl Metaball {
+    /// Get the coefficients of the quadratic 1 - d² / r² along the ray, by increasing degree.
+    fn get_falloff(&self, origin: &Vec3A, direction: &Vec3A) -> [f64; 3] {
+        let offset = (origin - self.center).as_dvec3();
+        let direction = direction.as_dvec3();
+        let scale = (self.radius as f64).powi(2);
+        [
+            1. - offset.length_squared() / scale,
+            -2. * offset.dot(direction) / scale,
+            -direction.length_squared() / scale,
+        ]
+    }
+}
+
+/// Structure representing a blobby object, relative to its position: its surface is where the summed fields of its
+/// balls reach the threshold, so that close balls melt together.
+/// Along a ray, the field is a quartic polynomial between consecutive ends of the balls' ranges, whose roots are
+/// found within each of these intervals.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(try_from = "MetaballsData", into = "MetaballsData")]
+pub struct Metaballs {
+    pub actor: Actor,
+    balls: Vec<Metaball>,
+    threshold: f32,
+    material: MaterialType,
+}
+
+/// Serialized fields of metaballs, checked while being deserialized.
+#[derive(Serialize, Deserialize)]
+struct MetaballsData {
+    #[serde(flatten)]
+    actor: Actor,
+    balls: Vec<Metaball>,
+    threshold: f32,
+    material: MaterialType,
+}
+
+impl TryFrom<MetaballsData> for Metaballs {
+    type Error = String;
+
+    fn try_from(data: MetaballsData) -> Result<Self, Self::Error> {
+        let mut metaballs = Metaballs::new(
+            &data.actor.position,
+            data.balls,
+            data.threshold,
+            &data.material,
+        )?;
+        metaballs.set_velocity(&data.actor.velocity);
+        Ok(metaballs)
+    }
+}
+
+impl From<Metaballs> for MetaballsData {
+    fn from(metaballs: Metaballs) -> Self {
+        Self {
+            actor: metaballs.actor,
+            balls: metaballs.balls,
+            threshold: metaballs.threshold,
+            material: metaballs.material,
+        }
+    }
+}
+
+impl std::ops::Deref for Metaballs {
+    type Target = Actor;
+    fn deref(&self) -> &Self::Target {
+        &self.actor
+    }
+}
+
+impl MaterialBound for Metaballs {
+    fn get_material(&self) -> &MaterialType {
+        &self.material
+    }
+}
+
+impl Metaballs {
+    /// Fail if the threshold or a radius is not above zero, the surface then being undefined.
+    pub fn new(
+        position: &Vec3A,
+        balls: Vec<Metaball>,
+        threshold: f32,
+        material: &MaterialType,
+    ) -> Result<Self, String> {
+        if threshold.is_nan() || threshold <= 0. {
+            return Err("the threshold must be above zero".to_string());
+        }
+        if balls.iter().any(|x| x.radius.is_nan() || x.radius <= 0.) {
+            return Err("the balls' radii must be above zero".to_string());
+        }
+        Ok(Self {
+            actor: Actor::new(position),
+            balls,
+            threshold,
+            material: material.to_owned(),
+        })
+    }
+
+    pub fn get_balls(&self) -> &Vec<Metaball> {
+        &self.balls
+    }
+
+    pub fn get_threshold(&self) -> f32 {
+        self.threshold
+    }
+
+    pub fn set_velocity(&mut self, velocity: &Vec3A) {
+        self.actor.velocity = *velocity;
+    }
+}
+
+impl ActorTrait for Metaballs {
+    fn get_position(&self) -> Vec3A {
+        self.actor.get_position()
+    }
+
+    fn get_velocity(&self) -> Vec3A {
+        self.actor.get_velocity()
+    }
+}
+
+impl Geometry for Metaballs {
+    /// Get the opposite of the field's gradient, the field growing towards the balls' centers.
+    fn get_surface_normal(&self, point: &Vec3A, time: f32) -> Vec3A {
+        let local = point - self.get_position_at(time);
+        let gradient = self.balls.iter().fold(Vec3A::ZERO, |gradient, ball| {
+            let offset = local - ball.center;
+            let scale = ball.radius * ball.radius;
+            let falloff = 1. - offset.length_squared() / scale;
+            match falloff > 0. {
+                true => gradient - 4. * falloff * offset / scale,
+                false => gradient,
+            }
+        });
+        (-gradient).normalize_or(Vec3A::Y)
+    }
+
+    /// Map the normal's direction, as on a sphere.
+    fn get_uv(&self, point: &Vec3A, time: f32) -> Vec2 {
+        let normal = self.get_surface_normal(point, time);
+        Vec2::new(
+            0.5 + normal.z.atan2(normal.x) / TAU,
+            0.5 + normal.y.clamp(-1., 1.).asin() / PI,
+        )
+    }
+
+    fn get_bounds(&self, time_start: f32, time_end: f32) -> Aabb {
+        let bounds = self.balls.iter().fold(Aabb::EMPTY, |bounds, x| {
+            let radius = Vec3A::splat(x.radius);
+            bounds.union(&Aabb::new(x.center - radius, x.center + radius))
+        });
+        [time_start, time_end]
+            .iter()
+            .map(|x| self.get_position_at(*x))
+            .fold(Aabb::EMPTY, |moved, position| {
+                moved.union(&Aabb::new(bounds.min + position, bounds.max + position))
+            })
+    }
+
+    /// Cut the ray at the ends of the balls' ranges, and find the threshold's crossings between them, the field
+    /// summing the squared falloffs of the balls covering each interval.
+    fn intersect(&self, ray: &Ray, ray_type: &RayType) -> Option<(f32, Vec3A)> {
+        let origin = ray.get_position() - self.get_position_at(ray.get_time());
+        let direction = ray.get_direction();
+        let (start, end) = ray_type.get_range();
+
+        let falloffs = self
+            .balls
+            .iter()
+            .filter_map(|x| {
+                let falloff = x.get_falloff(&origin, &direction);
+                match solve_quadratic(falloff[2], falloff[1], falloff[0])[..] {
+                    [near, far] if far >= start as f64 && near <= end as f64 => {
+                        Some((falloff, near, far))
+                    }
+                    _ => None,
+                }
+            })
+            .collect::<Vec<([f64; 3], f64, f64)>>();
+        let mut bounds = falloffs
+            .iter()
+            .flat_map(|x| [x.1, x.2])
+            .map(|x| x.clamp(start as f64, end as f64))
+            .collect::<Vec<f64>>();
+        bounds.sort_by(f64::total_cmp);
+        bounds.dedup();
+
+        bounds.windows(2).find_map(|x| {
+            let middle = (x[0] + x[1]) / 2.;
+            let mut field = [-self.threshold as f64, 0., 0., 0., 0.];
+            falloffs
+                .iter()
+                .filter(|(_, near, far)| (*near..=*far).contains(&middle))
+                .for_each(|([a, b, c], _, _)| {
+                    field[0] += a * a;
+                    field[1] += 2. * a * b;
+                    field[2] += b * b + 2. * a * c;
+                    field[3] += 2. * b * c;
+                    field[4] += c * c;
+                });
+            find_roots(&field, x[0], x[1])
+                .into_iter()
+                .map(|t| t as f32)
+                .find(|t| ray_type.accepts(*t))
+                .map(|t| (t, ray.get_position() + t * direction))
+        })
+    }
+}
+
+// #####################################
+
+#[cfg(test)]
+mod tests {
+    use glam::{Vec3A, Vec4};
+
+    use crate::entity::{
+        geometry::{
+            Geometry, RayType,
+            metaballs::{Metaball, Metaballs},
+            ray::Ray,
+        },
+        rendering::material::{ColorMaterial, MaterialType},
+    };
+
+    fn metaballs(centers: &[Vec3A], radius: f32, threshold: f32) -> Result<Metaballs, String> {
+        Metaballs::new(
+            &Vec3A::ZERO,
+            centers
+                .iter()
+                .map(|x| Metaball { center: *x, radius })
+                .collect(),
+            threshold,
+            &MaterialType::Color(ColorMaterial::new(Vec4::ONE)),
+        )
+    }
+
+    #[test]
+    fn test_success_intersect() {
+        // A lone ball's field reaches 1/4 where 1 - d² / 4 = 1/2, at the distance √2.
+        let ball = metaballs(&[Vec3A::ZERO], 2., 0.25).unwrap();
+        let ray = Ray::new(&Vec3A::new(0., 0., -10.), &Vec3A::Z);
+        let (distance, point) = ball.intersect(&ray, &RayType::Camera).unwrap();
+        assert!((distance - (10. - 2_f32.sqrt())).abs() < 1e-4);
+        assert!((ball.get_surface_normal(&point, 0.) - Vec3A::NEG_Z).length() < 1e-4);
+
+        // Two close balls melt together, their surface bulging between them beyond the lone ball's.
+        let pair = metaballs(&[Vec3A::NEG_X, Vec3A::X], 2., 0.25).unwrap();
+        let (distance, point) = pair.intersect(&ray, &RayType::Camera).unwrap();
+        let depth = (4. * (1. - 0.125_f32.sqrt()) - 1.).sqrt();
+        assert!((distance - (10. - depth)).abs() < 1e-4);
+        assert!((pair.get_surface_normal(&point, 0.) - Vec3A::NEG_Z).length() < 1e-4);
+        let ray = Ray::new(&Vec3A::new(-10., 0., 0.), &Vec3A::X);
+        let (distance, point) = pair.intersect(&ray, &RayType::Camera).unwrap();
+        assert!((distance - (9. - 2_f32.sqrt())).abs() < 1e-4);
+        assert!((pair.get_surface_normal(&point, 0.) - Vec3A::NEG_X).length() < 1e-4);
+    }
+
+    #[test]
+    fn test_failure_intersect() {
+        // Far apart balls do not melt, leaving a gap between them.
+        let pair = metaballs(&[Vec3A::new(-3., 0., 0.), Vec3A::new(3., 0., 0.)], 2., 0.25).unwrap();
+        let ray = Ray::new(&Vec3A::new(0., 0., -10.), &Vec3A::Z);
+        assert_eq!(pair.intersect(&ray, &RayType::Camera), None);
+        let ray = Ray::new(&Vec3A::new(-3., 0., -10.), &Vec3A::new(0., 0., 5.));
+        assert_eq!(pair.intersect(&ray, &RayType::Light), None);
+
+        assert!(metaballs(&[Vec3A::ZERO], 2., 0.).is_err());
+        assert!(metaballs(&[Vec3A::ZERO], 0., 0.5).is_err());
+    }
+}
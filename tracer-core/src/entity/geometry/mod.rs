@@ -1,13 +1,46 @@
+pub mod aabb;
+pub mod algebraic;
+pub mod cone;
+pub mod csg;
+pub mod cuboid;
+pub mod curve;
+pub mod cylinder;
+pub mod disk;
+pub mod heightfield;
+pub mod instance;
+pub mod metaballs;
+pub mod patch;
 pub mod plane;
+pub mod polynomial;
+pub mod quad;
 pub mod ray;
+pub mod sdf;
 pub mod sphere;
+pub mod torus;
+pub mod transform;
 
-use glam::Vec3A;
+use glam::{Vec2, Vec3A};
+use serde::{Deserialize, Serialize};
 
 use crate::entity::actor::ActorTrait;
+use crate::entity::geometry::aabb::Aabb;
+use crate::entity::geometry::algebraic::Algebraic;
+use crate::entity::geometry::cone::Cone;
+use crate::entity::geometry::csg::Csg;
+use crate::entity::geometry::cuboid::Cuboid;
+use crate::entity::geometry::curve::Curve;
+use crate::entity::geometry::cylinder::Cylinder;
+use crate::entity::geometry::disk::Disk;
+use crate::entity::geometry::heightfield::Heightfield;
+use crate::entity::geometry::instance::Instance;
+use crate::entity::geometry::metaballs::Metaballs;
+use crate::entity::geometry::patch::Patch;
 use crate::entity::geometry::plane::Plane;
+use crate::entity::geometry::quad::Quad;
 use crate::entity::geometry::ray::{Ray, RayType};
+use crate::entity::geometry::sdf::Sdf;
 use crate::entity::geometry::sphere::Sphere;
+use crate::entity::geometry::torus::Torus;
 use crate::entity::rendering::light::Light;
 use crate::entity::rendering::material::MaterialBound;
 
@@ -15,15 +48,110 @@ use crate::entity::rendering::material::MaterialBound;
 pub trait Geometry {
     //// Check collision with a given ray from the ray emitter, return the ray's color post-interaction with the geometry object.
     fn intersect(&self, ray: &Ray, ray_type: &RayType) -> Option<(f32, Vec3A)>;
-    fn get_surface_normal(&self, _point: &Vec3A) -> Vec3A;
+    fn get_surface_normal(&self, _point: &Vec3A, _time: f32) -> Vec3A;
+    /// Get the texture coordinates of a point of the surface, mostly within [0, 1].
+    fn get_uv(&self, point: &Vec3A, time: f32) -> Vec2;
+    /// Get a box containing the geometry at any time of the given interval.
+    fn get_bounds(&self, time_start: f32, time_end: f32) -> Aabb;
+    /// Get the direction of the fibers at a point of the surface, for the geometries made of fibers.
+    fn get_tangent(&self, _point: &Vec3A, _time: f32) -> Option<Vec3A> {
+        None
+    }
+}
+
+/// Closed geometries, whose inside is well defined, allowing to combine them with constructive solid geometry.
+pub trait Solid: Geometry {
+    /// Get the sorted and disjoint ranges of the ray's parameter inside the solid, along the ray's whole line.
+    fn get_intervals(&self, ray: &Ray) -> Vec<(f32, f32)>;
+    /// Get an estimate of the distance between the point and the solid's surface, zero on the surface.
+    fn get_surface_distance(&self, point: &Vec3A, time: f32) -> f32;
 }
 
 // #####################################
 
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum GeometryType {
     Plane(Plane),
     Sphere(Sphere),
     Light(Light),
+    Instance(Instance),
+    #[serde(rename = "box")]
+    Cuboid(Cuboid),
+    Cylinder(Cylinder),
+    Cone(Cone),
+    Disk(Disk),
+    Quad(Quad),
+    Torus(Torus),
+    Csg(Csg),
+    Sdf(Sdf),
+    Heightfield(Heightfield),
+    Patch(Patch),
+    Curve(Curve),
+    Metaballs(Metaballs),
+    Algebraic(Algebraic),
+}
+
+impl GeometryType {
+    /// Get the name of the geometry's variant, as written in the scene files.
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            GeometryType::Plane(_) => "plane",
+            GeometryType::Sphere(_) => "sphere",
+            GeometryType::Light(_) => "light",
+            GeometryType::Instance(_) => "instance",
+            GeometryType::Cuboid(_) => "box",
+            GeometryType::Cylinder(_) => "cylinder",
+            GeometryType::Cone(_) => "cone",
+            GeometryType::Disk(_) => "disk",
+            GeometryType::Quad(_) => "quad",
+            GeometryType::Torus(_) => "torus",
+            GeometryType::Csg(_) => "csg",
+            GeometryType::Sdf(_) => "sdf",
+            GeometryType::Heightfield(_) => "heightfield",
+            GeometryType::Patch(_) => "patch",
+            GeometryType::Curve(_) => "curve",
+            GeometryType::Metaballs(_) => "metaballs",
+            GeometryType::Algebraic(_) => "algebraic",
+        }
+    }
+
+    /// Get the geometry as a solid, if it is closed.
+    pub fn as_solid(&self) -> Option<&dyn Solid> {
+        match self {
+            GeometryType::Sphere(i) => Some(i),
+            GeometryType::Cuboid(i) => Some(i),
+            GeometryType::Cylinder(i) => Some(i),
+            GeometryType::Instance(i) if i.get_geometry().as_solid().is_some() => Some(i),
+            GeometryType::Csg(i) => Some(i),
+            _ => None,
+        }
+    }
+}
+
+impl GeometryType {
+    /// Set the velocity of the geometry, moving it while the shutter is open.
+    pub fn set_velocity(&mut self, velocity: &Vec3A) {
+        match self {
+            GeometryType::Plane(i) => i.set_velocity(velocity),
+            GeometryType::Sphere(i) => i.set_velocity(velocity),
+            GeometryType::Light(i) => i.set_velocity(velocity),
+            GeometryType::Instance(i) => i.set_velocity(velocity),
+            GeometryType::Cuboid(i) => i.set_velocity(velocity),
+            GeometryType::Cylinder(i) => i.set_velocity(velocity),
+            GeometryType::Cone(i) => i.set_velocity(velocity),
+            GeometryType::Disk(i) => i.set_velocity(velocity),
+            GeometryType::Quad(i) => i.set_velocity(velocity),
+            GeometryType::Torus(i) => i.set_velocity(velocity),
+            GeometryType::Csg(i) => i.set_velocity(velocity),
+            GeometryType::Sdf(i) => i.set_velocity(velocity),
+            GeometryType::Heightfield(i) => i.set_velocity(velocity),
+            GeometryType::Patch(i) => i.set_velocity(velocity),
+            GeometryType::Curve(i) => i.set_velocity(velocity),
+            GeometryType::Metaballs(i) => i.set_velocity(velocity),
+            GeometryType::Algebraic(i) => i.set_velocity(velocity),
+        }
+    }
 }
 
 impl ActorTrait for GeometryType {
@@ -32,16 +160,110 @@ impl ActorTrait for GeometryType {
             GeometryType::Plane(i) => i.get_position(),
             GeometryType::Sphere(i) => i.get_position(),
             GeometryType::Light(i) => i.get_position(),
+            GeometryType::Instance(i) => i.get_position(),
+            GeometryType::Cuboid(i) => i.get_position(),
+            GeometryType::Cylinder(i) => i.get_position(),
+            GeometryType::Cone(i) => i.get_position(),
+            GeometryType::Disk(i) => i.get_position(),
+            GeometryType::Quad(i) => i.get_position(),
+            GeometryType::Torus(i) => i.get_position(),
+            GeometryType::Csg(i) => i.get_position(),
+            GeometryType::Sdf(i) => i.get_position(),
+            GeometryType::Heightfield(i) => i.get_position(),
+            GeometryType::Patch(i) => i.get_position(),
+            GeometryType::Curve(i) => i.get_position(),
+            GeometryType::Metaballs(i) => i.get_position(),
+            GeometryType::Algebraic(i) => i.get_position(),
+        }
+    }
+
+    fn get_velocity(&self) -> Vec3A {
+        match self {
+            GeometryType::Plane(i) => i.get_velocity(),
+            GeometryType::Sphere(i) => i.get_velocity(),
+            GeometryType::Light(i) => i.get_velocity(),
+            GeometryType::Instance(i) => i.get_velocity(),
+            GeometryType::Cuboid(i) => i.get_velocity(),
+            GeometryType::Cylinder(i) => i.get_velocity(),
+            GeometryType::Cone(i) => i.get_velocity(),
+            GeometryType::Disk(i) => i.get_velocity(),
+            GeometryType::Quad(i) => i.get_velocity(),
+            GeometryType::Torus(i) => i.get_velocity(),
+            GeometryType::Csg(i) => i.get_velocity(),
+            GeometryType::Sdf(i) => i.get_velocity(),
+            GeometryType::Heightfield(i) => i.get_velocity(),
+            GeometryType::Patch(i) => i.get_velocity(),
+            GeometryType::Curve(i) => i.get_velocity(),
+            GeometryType::Metaballs(i) => i.get_velocity(),
+            GeometryType::Algebraic(i) => i.get_velocity(),
         }
     }
 }
 
 impl Geometry for GeometryType {
-    fn get_surface_normal(&self, point: &Vec3A) -> Vec3A {
+    fn get_surface_normal(&self, point: &Vec3A, time: f32) -> Vec3A {
+        match self {
+            GeometryType::Plane(i) => i.get_surface_normal(point, time),
+            GeometryType::Sphere(i) => i.get_surface_normal(point, time),
+            GeometryType::Light(i) => i.get_surface_normal(point, time),
+            GeometryType::Instance(i) => i.get_surface_normal(point, time),
+            GeometryType::Cuboid(i) => i.get_surface_normal(point, time),
+            GeometryType::Cylinder(i) => i.get_surface_normal(point, time),
+            GeometryType::Cone(i) => i.get_surface_normal(point, time),
+            GeometryType::Disk(i) => i.get_surface_normal(point, time),
+            GeometryType::Quad(i) => i.get_surface_normal(point, time),
+            GeometryType::Torus(i) => i.get_surface_normal(point, time),
+            GeometryType::Csg(i) => i.get_surface_normal(point, time),
+            GeometryType::Sdf(i) => i.get_surface_normal(point, time),
+            GeometryType::Heightfield(i) => i.get_surface_normal(point, time),
+            GeometryType::Patch(i) => i.get_surface_normal(point, time),
+            GeometryType::Curve(i) => i.get_surface_normal(point, time),
+            GeometryType::Metaballs(i) => i.get_surface_normal(point, time),
+            GeometryType::Algebraic(i) => i.get_surface_normal(point, time),
+        }
+    }
+
+    fn get_uv(&self, point: &Vec3A, time: f32) -> Vec2 {
         match self {
-            GeometryType::Plane(i) => i.get_surface_normal(point),
-            GeometryType::Sphere(i) => i.get_surface_normal(point),
-            GeometryType::Light(i) => i.get_surface_normal(point),
+            GeometryType::Plane(i) => i.get_uv(point, time),
+            GeometryType::Sphere(i) => i.get_uv(point, time),
+            GeometryType::Light(i) => i.get_uv(point, time),
+            GeometryType::Instance(i) => i.get_uv(point, time),
+            GeometryType::Cuboid(i) => i.get_uv(point, time),
+            GeometryType::Cylinder(i) => i.get_uv(point, time),
+            GeometryType::Cone(i) => i.get_uv(point, time),
+            GeometryType::Disk(i) => i.get_uv(point, time),
+            GeometryType::Quad(i) => i.get_uv(point, time),
+            GeometryType::Torus(i) => i.get_uv(point, time),
+            GeometryType::Csg(i) => i.get_uv(point, time),
+            GeometryType::Sdf(i) => i.get_uv(point, time),
+            GeometryType::Heightfield(i) => i.get_uv(point, time),
+            GeometryType::Patch(i) => i.get_uv(point, time),
+            GeometryType::Curve(i) => i.get_uv(point, time),
+            GeometryType::Metaballs(i) => i.get_uv(point, time),
+            GeometryType::Algebraic(i) => i.get_uv(point, time),
+        }
+    }
+
+    fn get_bounds(&self, time_start: f32, time_end: f32) -> Aabb {
+        match self {
+            GeometryType::Plane(i) => i.get_bounds(time_start, time_end),
+            GeometryType::Sphere(i) => i.get_bounds(time_start, time_end),
+            GeometryType::Light(i) => i.get_bounds(time_start, time_end),
+            GeometryType::Instance(i) => i.get_bounds(time_start, time_end),
+            GeometryType::Cuboid(i) => i.get_bounds(time_start, time_end),
+            GeometryType::Cylinder(i) => i.get_bounds(time_start, time_end),
+            GeometryType::Cone(i) => i.get_bounds(time_start, time_end),
+            GeometryType::Disk(i) => i.get_bounds(time_start, time_end),
+            GeometryType::Quad(i) => i.get_bounds(time_start, time_end),
+            GeometryType::Torus(i) => i.get_bounds(time_start, time_end),
+            GeometryType::Csg(i) => i.get_bounds(time_start, time_end),
+            GeometryType::Sdf(i) => i.get_bounds(time_start, time_end),
+            GeometryType::Heightfield(i) => i.get_bounds(time_start, time_end),
+            GeometryType::Patch(i) => i.get_bounds(time_start, time_end),
+            GeometryType::Curve(i) => i.get_bounds(time_start, time_end),
+            GeometryType::Metaballs(i) => i.get_bounds(time_start, time_end),
+            GeometryType::Algebraic(i) => i.get_bounds(time_start, time_end),
         }
     }
 
@@ -50,6 +272,28 @@ impl Geometry for GeometryType {
             GeometryType::Plane(i) => i.intersect(ray, ray_type),
             GeometryType::Sphere(i) => i.intersect(ray, ray_type),
             GeometryType::Light(i) => i.intersect(ray, ray_type),
+            GeometryType::Instance(i) => i.intersect(ray, ray_type),
+            GeometryType::Cuboid(i) => i.intersect(ray, ray_type),
+            GeometryType::Cylinder(i) => i.intersect(ray, ray_type),
+            GeometryType::Cone(i) => i.intersect(ray, ray_type),
+            GeometryType::Disk(i) => i.intersect(ray, ray_type),
+            GeometryType::Quad(i) => i.intersect(ray, ray_type),
+            GeometryType::Torus(i) => i.intersect(ray, ray_type),
+            GeometryType::Csg(i) => i.intersect(ray, ray_type),
+            GeometryType::Sdf(i) => i.intersect(ray, ray_type),
+            GeometryType::Heightfield(i) => i.intersect(ray, ray_type),
+            GeometryType::Patch(i) => i.intersect(ray, ray_type),
+            GeometryType::Curve(i) => i.intersect(ray, ray_type),
+            GeometryType::Metaballs(i) => i.intersect(ray, ray_type),
+            GeometryType::Algebraic(i) => i.intersect(ray, ray_type),
+        }
+    }
+
+    fn get_tangent(&self, point: &Vec3A, time: f32) -> Option<Vec3A> {
+        match self {
+            GeometryType::Instance(i) => i.get_tangent(point, time),
+            GeometryType::Curve(i) => i.get_tangent(point, time),
+            _ => None,
         }
     }
 }
@@ -60,6 +304,20 @@ impl MaterialBound for GeometryType {
             GeometryType::Plane(i) => i.get_material(),
             GeometryType::Sphere(i) => i.get_material(),
             GeometryType::Light(i) => i.get_material(),
+            GeometryType::Instance(i) => i.get_material(),
+            GeometryType::Cuboid(i) => i.get_material(),
+            GeometryType::Cylinder(i) => i.get_material(),
+            GeometryType::Cone(i) => i.get_material(),
+            GeometryType::Disk(i) => i.get_material(),
+            GeometryType::Quad(i) => i.get_material(),
+            GeometryType::Torus(i) => i.get_material(),
+            GeometryType::Csg(i) => i.get_material(),
+            GeometryType::Sdf(i) => i.get_material(),
+            GeometryType::Heightfield(i) => i.get_material(),
+            GeometryType::Patch(i) => i.get_material(),
+            GeometryType::Curve(i) => i.get_material(),
+            GeometryType::Metaballs(i) => i.get_material(),
+            GeometryType::Algebraic(i) => i.get_material(),
         }
     }
 }
@@ -0,0 +1,513 @@
+use glam::{Mat2, Mat3A, Vec2, Vec3A, Vec4};
+use serde::{Deserialize, Serialize};
+
+use crate::entity::actor::{Actor, ActorTrait, DirectionalActorTrait};
+use crate::entity::geometry::{Geometry, RayType, aabb::Aabb, ray::Ray};
+use crate::entity::rendering::material::{MaterialBound, MaterialType};
+
+/// Basis of the bicubic surface defined by a net of control points.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PatchBasis {
+    /// Patches of 4 by 4 control points, sharing their border rows and columns: the net has 3n + 1 rows and
+    /// columns, and the surface goes through its corners.
+    #[default]
+    Bezier,
+    /// Uniform cubic B-spline: each window of 4 by 4 control points defines a patch, smoothly joined with its
+    /// neighbours, the surface only approaching the control points.
+    BSpline,
+}
+
+/// Number of times the patches are split in four, to seed the intersections.
+const SUBDIVISIONS: usize = 3;
+const NEWTON_ITERATIONS: usize = 12;
+
+/// Get the cubic Bernstein polynomials, and their derivatives, at the given parameter.
+pub(super) fn bernstein(t: f32) -> (Vec4, Vec4) {
+    let s = 1. - t;
+    (
+        Vec4::new(s * s * s, 3. * t * s * s, 3. * t * t * s, t * t * t),
+        Vec4::new(
+            -3. * s * s,
+            3. * s * s - 6. * t * s,
+            6. * t * s - 3. * t * t,
+            3. * t * t,
+        ),
+    )
+}
+
+/// Split a cubic Bézier curve in halves (de Casteljau).
+pub(super) fn split_curve([p0, p1, p2, p3]: [Vec3A; 4]) -> ([Vec3A; 4], [Vec3A; 4]) {
+    let (a, b, c) = ((p0 + p1) / 2., (p1 + p2) / 2., (p2 + p3) / 2.);
+    let (d, e) = ((a + b) / 2., (b + c) / 2.);
+    let middle = (d + e) / 2.;
+    ([p0, a, d, middle], [middle, e, c, p3])
+}
+
+/// Bicubic Bézier patch, its control points being indexed by row (along V) then column (along U).
+#[derive(Debug, Clone, PartialEq)]
+struct BezierPatch {
+    points: [[Vec3A; 4]; 4],
+}
+
+impl BezierPatch {
+    /// Get the surface's point, and its partial derivatives along U and V.
+    fn evaluate(&self, uv: Vec2) -> (Vec3A, Vec3A, Vec3A) {
+        let ((bu, du), (bv, dv)) = (bernstein(uv.x), bernstein(uv.y));
+        let mut result = (Vec3A::ZERO, Vec3A::ZERO, Vec3A::ZERO);
+        for (row, points) in self.points.iter().enumerate() {
+            for (column, point) in points.iter().enumerate() {
+                result.0 += *point * bu[column] * bv[row];
+                result.1 += *point * du[column] * bv[row];
+                result.2 += *point * bu[column] * dv[row];
+            }
+        }
+        result
+    }
+
+    /// Get a box containing the patch, which lies within its control points' convex hull.
+    fn get_bounds(&self) -> Aabb {
+        self.points
+            .iter()
+            .flatten()
+            .fold(Aabb::EMPTY, |bounds, x| bounds.union(&Aabb::new(*x, *x)))
+    }
+
+    /// Split the patch in four, ordered along U then V.
+    fn split(&self) -> [BezierPatch; 4] {
+        let rows = self.points.map(split_curve);
+        let (left, right) = (rows.map(|x| x.0), rows.map(|x| x.1));
+        let split_columns = |points: [[Vec3A; 4]; 4]| {
+            let columns = [0, 1, 2, 3].map(|c| split_curve(points.map(|x| x[c])));
+            let rows = |half: [[Vec3A; 4]; 4]| [0, 1, 2, 3].map(|r| half.map(|x| x[r]));
+            (rows(columns.map(|x| x.0)), rows(columns.map(|x| x.1)))
+        };
+        let ((left_bottom, left_top), (right_bottom, right_top)) =
+            (split_columns(left), split_columns(right));
+        [left_bottom, right_bottom, left_top, right_top].map(|points| BezierPatch { points })
+    }
+}
+
+/// Node of a patch's subdivision, bounding the part of the patch within the parameters' range.
+#[derive(Debug, Clone, PartialEq)]
+struct PatchNode {
+    bounds: Aabb,
+    uv_min: Vec2,
+    uv_max: Vec2,
+    children: Vec<PatchNode>,
+}
+
+impl PatchNode {
+    fn new(patch: &BezierPatch, uv_min: Vec2, uv_max: Vec2, depth: usize) -> Self {
+        let middle = (uv_min + uv_max) / 2.;
+        let children = match depth {
+            0 => Vec::new(),
+            _ => patch
+                .split()
+                .iter()
+                .zip([
+                    (uv_min, middle),
+                    (Vec2::new(middle.x, uv_min.y), Vec2::new(uv_max.x, middle.y)),
+                    (Vec2::new(uv_min.x, middle.y), Vec2::new(middle.x, uv_max.y)),
+                    (middle, uv_max),
+                ])
+                .map(|(x, (min, max))| PatchNode::new(x, min, max, depth - 1))
+                .collect(),
+        };
+        Self {
+            bounds: patch.get_bounds(),
+            uv_min,
+            uv_max,
+            children,
+        }
+    }
+
+    /// Visit the leaves whose bounds pass the given test.
+    fn visit_leaves(&self, test: &dyn Fn(&Aabb) -> bool, visit: &mut dyn FnMut(&PatchNode)) {
+        if !test(&self.bounds) {
+            return;
+        }
+        match self.children.is_empty() {
+            true => visit(self),
+            false => self
+                .children
+                .iter()
+                .for_each(|x| x.visit_leaves(test, visit)),
+        }
+    }
+}
+
+/// Structure representing a bicubic Bézier or B-spline surface, out of a net of control points relative to its
+/// position. The net is cut in patches, each of them being intersected by Newton iterations seeded by the leaves
+/// of its subdivision crossed by the ray.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(try_from = "PatchData", into = "PatchData")]
+pub struct Patch {
+    pub actor: Actor,
+    basis: PatchBasis,
+    control_points: Vec<Vec<Vec3A>>,
+    patches: Vec<(BezierPatch, PatchNode)>,
+    /// Number of patches along U and V.
+    counts: (usize, usize),
+    material: MaterialType,
+}
+
+/// Serialized fields of a patch, the Bézier patches being rebuilt out of the control points.
+#[derive(Serialize, Deserialize)]
+struct PatchData {
+    #[serde(flatten)]
+    actor: Actor,
+    #[serde(default)]
+    basis: PatchBasis,
+    control_points: Vec<Vec<Vec3A>>,
+    material: MaterialType,
+}
+
+impl TryFrom<PatchData> for Patch {
+    type Error = String;
+
+    fn try_from(data: PatchData) -> Result<Self, Self::Error> {
+        let mut patch = Patch::new(
+            &data.actor.position,
+            data.basis,
+            data.control_points,
+            &data.material,
+        )?;
+        patch.set_velocity(&data.actor.velocity);
+        Ok(patch)
+    }
+}
+
+impl From<Patch> for PatchData {
+    fn from(patch: Patch) -> Self {
+        Self {
+            actor: patch.actor,
+            basis: patch.basis,
+            control_points: patch.control_points,
+            material: patch.material,
+        }
+    }
+}
+
+impl std::ops::Deref for Patch {
+    type Target = Actor;
+    fn deref(&self) -> &Self::Target {
+        &self.actor
+    }
+}
+
+impl MaterialBound for Patch {
+    fn get_material(&self) -> &MaterialType {
+        &self.material
+    }
+}
+
+impl Patch {
+    /// Cut the net of control points in patches, failing if its rows' lengths differ or do not fit the basis.
+    pub fn new(
+        position: &Vec3A,
+        basis: PatchBasis,
+        control_points: Vec<Vec<Vec3A>>,
+        material: &MaterialType,
+    ) -> Result<Self, String> {
+        let (rows, columns) = (
+            control_points.len(),
+            control_points.first().map_or(0, |x| x.len()),
+        );
+        if control_points.iter().any(|x| x.len() != columns) {
+            return Err("the control points' rows must have the same length".to_string());
+        }
+        // Consecutive patches are apart by the step, overlapping by 4 - step rows and columns.
+        let step = match basis {
+            PatchBasis::Bezier if rows % 3 == 1 && columns % 3 == 1 && rows > 1 && columns > 1 => 3,
+            PatchBasis::Bezier => {
+                return Err(
+                    "a bezier net needs 3n + 1 rows and columns of control points".to_string(),
+                );
+            }
+            PatchBasis::BSpline if rows >= 4 && columns >= 4 => 1,
+            PatchBasis::BSpline => {
+                return Err(
+                    "a b-spline net needs at least 4 rows and columns of control points"
+                        .to_string(),
+                );
+            }
+        };
+
+        let count = |x: usize| (x - (4 - step)) / step;
+        let counts = (count(columns), count(rows));
+        let patches = (0..counts.1)
+            .flat_map(|v| (0..counts.0).map(move |u| (u, v)))
+            .map(|(u, v)| {
+                let points = [0, 1, 2, 3]
+                    .map(|r| [0, 1, 2, 3].map(|c| control_points[v * step + r][u * step + c]));
+                let patch = BezierPatch {
+                    points: match basis {
+                        PatchBasis::Bezier => points,
+                        PatchBasis::BSpline => Self::to_bezier(&points),
+                    },
+                };
+                let node = PatchNode::new(&patch, Vec2::ZERO, Vec2::ONE, SUBDIVISIONS);
+                (patch, node)
+            })
+            .collect();
+
+        Ok(Self {
+            actor: Actor::new(position),
+            basis,
+            control_points,
+            patches,
+            counts,
+            material: material.to_owned(),
+        })
+    }
+
+    pub fn get_basis(&self) -> PatchBasis {
+        self.basis
+    }
+
+    pub fn get_control_points(&self) -> &Vec<Vec<Vec3A>> {
+        &self.control_points
+    }
+
+    pub fn set_velocity(&mut self, velocity: &Vec3A) {
+        self.actor.velocity = *velocity;
+    }
+
+    /// Change the basis of a uniform cubic B-spline patch's control points to Bézier's one, along both directions.
+    fn to_bezier(points: &[[Vec3A; 4]; 4]) -> [[Vec3A; 4]; 4] {
+        let curve = |[p0, p1, p2, p3]: [Vec3A; 4]| {
+            [
+                (p0 + 4. * p1 + p2) / 6.,
+                (4. * p1 + 2. * p2) / 6.,
+                (2. * p1 + 4. * p2) / 6.,
+                (p1 + 4. * p2 + p3) / 6.,
+            ]
+        };
+        let rows = points.map(curve);
+        let columns = [0, 1, 2, 3].map(|c| curve(rows.map(|x| x[c])));
+        [0, 1, 2, 3].map(|r| columns.map(|x| x[r]))
+    }
+
+    /// Find the patch and parameters of the point of the surface, relative to the position, by Gauss-Newton
+    /// iterations from the leaves containing it.
+    fn find_parameters(&self, local: &Vec3A) -> (usize, Vec2) {
+        let mut best = (0, Vec2::ZERO, f32::INFINITY);
+        for (index, (patch, node)) in self.patches.iter().enumerate() {
+            let tolerance = 1e-3 * node.bounds.max.distance(node.bounds.min);
+            let inflate = Vec3A::splat(tolerance);
+            let test = |bounds: &Aabb| {
+                Aabb::new(bounds.min - inflate, bounds.max + inflate).contains(local)
+            };
+            node.visit_leaves(&test, &mut |leaf| {
+                let mut uv = (leaf.uv_min + leaf.uv_max) / 2.;
+                for _ in 0..NEWTON_ITERATIONS {
+                    let (point, du, dv) = patch.evaluate(uv);
+                    let difference = local - point;
+                    let normal = Mat2::from_cols(
+                        Vec2::new(du.dot(du), du.dot(dv)),
+                        Vec2::new(du.dot(dv), dv.dot(dv)),
+                    );
+                    if normal.determinant() == 0. {
+                        break;
+                    }
+                    let gradient = Vec2::new(du.dot(difference), dv.dot(difference));
+                    uv = (uv + normal.inverse() * gradient).clamp(Vec2::ZERO, Vec2::ONE);
+                }
+                let distance = patch.evaluate(uv).0.distance(*local);
+                if distance < best.2 {
+                    best = (index, uv, distance);
+                }
+            });
+        }
+        (best.0, best.1)
+    }
+
+    /// Solve S(u, v) = origin + t direction by Newton iterations from the given guess, returning the ray's
+    /// parameter and the patch's parameters once converged within the patch.
+    fn solve(
+        patch: &BezierPatch,
+        origin: &Vec3A,
+        direction: &Vec3A,
+        mut uv: Vec2,
+        mut t: f32,
+        tolerance: f32,
+    ) -> Option<(f32, Vec2)> {
+        for _ in 0..NEWTON_ITERATIONS {
+            let (point, du, dv) = patch.evaluate(uv);
+            let error = point - (origin + t * direction);
+            if error.length() < tolerance {
+                let inside =
+                    uv.cmpge(Vec2::splat(-1e-4)).all() && uv.cmple(Vec2::splat(1. + 1e-4)).all();
+                return inside.then_some((t, uv));
+            }
+            let jacobian = Mat3A::from_cols(du, dv, -direction);
+            if jacobian.determinant() == 0. {
+                return None;
+            }
+            let delta = jacobian.inverse() * -error;
+            uv += Vec2::new(delta.x, delta.y);
+            t += delta.z;
+            if !uv.is_finite() || uv.abs().max_element() > 2. {
+                return None;
+            }
+        }
+        None
+    }
+}
+
+impl ActorTrait for Patch {
+    fn get_position(&self) -> Vec3A {
+        self.actor.get_position()
+    }
+
+    fn get_velocity(&self) -> Vec3A {
+        self.actor.get_velocity()
+    }
+}
+
+impl Geometry for Patch {
+    /// Get the cross product of the partial derivatives along U and V.
+    fn get_surface_normal(&self, point: &Vec3A, time: f32) -> Vec3A {
+        let (index, uv) = self.find_parameters(&(point - self.get_position_at(time)));
+        let (_, du, dv) = self.patches[index].0.evaluate(uv);
+        du.cross(dv).normalize_or(Vec3A::Y)
+    }
+
+    /// Map the whole net on the unit square, each patch getting an equal part of it.
+    fn get_uv(&self, point: &Vec3A, time: f32) -> Vec2 {
+        let (index, uv) = self.find_parameters(&(point - self.get_position_at(time)));
+        let cell = Vec2::new(
+            (index % self.counts.0) as f32,
+            (index / self.counts.0) as f32,
+        );
+        (cell + uv) / Vec2::new(self.counts.0 as f32, self.counts.1 as f32)
+    }
+
+    fn get_bounds(&self, time_start: f32, time_end: f32) -> Aabb {
+        let bounds = self
+            .patches
+            .iter()
+            .fold(Aabb::EMPTY, |bounds, x| bounds.union(&x.1.bounds));
+        [time_start, time_end]
+            .iter()
+            .map(|x| self.get_position_at(*x))
+            .fold(Aabb::EMPTY, |moved, position| {
+                moved.union(&Aabb::new(bounds.min + position, bounds.max + position))
+            })
+    }
+
+    /// Solve the intersection from the middle of each crossed leaf, keeping the nearest accepted hit.
+    fn intersect(&self, ray: &Ray, ray_type: &RayType) -> Option<(f32, Vec3A)> {
+        let origin = ray.get_position() - self.get_position_at(ray.get_time());
+        let local = Ray::new(&origin, &ray.get_direction());
+        let direction = ray.get_direction();
+        let (start, end) = ray_type.get_range();
+
+        let mut nearest: Option<f32> = None;
+        for (patch, node) in &self.patches {
+            let tolerance = 1e-5 * node.bounds.max.distance(node.bounds.min);
+            let test = |bounds: &Aabb| {
+                bounds
+                    .intersect(&local)
+                    .is_some_and(|(near, far)| near <= end && far >= start)
+            };
+            node.visit_leaves(&test, &mut |leaf| {
+                let Some((near, far)) = leaf.bounds.intersect(&local) else {
+                    return;
+                };
+                let uv = (leaf.uv_min + leaf.uv_max) / 2.;
+                if let Some((t, _)) =
+                    Self::solve(patch, &origin, &direction, uv, (near + far) / 2., tolerance)
+                    && ray_type.accepts(t)
+                    && nearest.is_none_or(|x| t < x)
+                {
+                    nearest = Some(t);
+                }
+            });
+        }
+        nearest.map(|t| (t, ray.get_position() + t * direction))
+    }
+}
+
+// #####################################
+
+#[cfg(test)]
+mod tests {
+    use glam::{Vec2, Vec3A, Vec4};
+
+    use crate::entity::{
+        geometry::{
+            Geometry, RayType,
+            patch::{Patch, PatchBasis},
+            ray::Ray,
+        },
+        rendering::material::{ColorMaterial, MaterialType},
+    };
+
+    /// Get a net of the given size, spaced by the step in the XY plane, its inner points' depths being changed.
+    fn net(size: usize, step: f32, depth: f32, inner_depth: f32) -> Vec<Vec<Vec3A>> {
+        (0..size)
+            .map(|r| {
+                (0..size)
+                    .map(|c| {
+                        let inner = (1..size - 1).contains(&r) && (1..size - 1).contains(&c);
+                        let z = if inner { inner_depth } else { depth };
+                        Vec3A::new(c as f32 * step, r as f32 * step, z)
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn patch(basis: PatchBasis, control_points: Vec<Vec<Vec3A>>) -> Result<Patch, String> {
+        Patch::new(
+            &Vec3A::ZERO,
+            basis,
+            control_points,
+            &MaterialType::Color(ColorMaterial::new(Vec4::ONE)),
+        )
+    }
+
+    #[test]
+    fn test_success_intersect() {
+        let flat = patch(PatchBasis::Bezier, net(4, 2., 10., 10.)).unwrap();
+        let ray = Ray::new(&Vec3A::new(3., 1.5, 0.), &Vec3A::Z);
+        let (distance, point) = flat.intersect(&ray, &RayType::Camera).unwrap();
+        assert!((distance - 10.).abs() < 1e-4);
+        assert!((flat.get_surface_normal(&point, 0.) - Vec3A::Z).length() < 1e-4);
+        assert!((flat.get_uv(&point, 0.) - Vec2::new(0.5, 0.25)).length() < 1e-4);
+
+        // The inner control points pull the patch's middle towards the ray's origin, by 2 * (3/4)².
+        let dome = patch(PatchBasis::Bezier, net(4, 2., 10., 8.)).unwrap();
+        let ray = Ray::new(&Vec3A::new(3., 3., 0.), &Vec3A::Z);
+        let (distance, point) = dome.intersect(&ray, &RayType::Camera).unwrap();
+        assert!((distance - 8.875).abs() < 1e-4);
+        assert!((dome.get_surface_normal(&point, 0.) - Vec3A::Z).length() < 1e-4);
+        let ray = Ray::new(&Vec3A::new(0.5, 3., 0.), &Vec3A::Z);
+        let (_, point) = dome.intersect(&ray, &RayType::Camera).unwrap();
+        assert!(dome.get_surface_normal(&point, 0.).x > 0.1);
+
+        // A flat B-spline net of 5 by 4 points makes 2 patches, spanning between the second and before-last points.
+        let spline = patch(PatchBasis::BSpline, net(5, 1., 5., 5.)[..4].to_vec()).unwrap();
+        let ray = Ray::new(&Vec3A::new(2.5, 1.5, 0.), &Vec3A::Z);
+        let (distance, point) = spline.intersect(&ray, &RayType::Camera).unwrap();
+        assert!((distance - 5.).abs() < 1e-4);
+        assert!((spline.get_uv(&point, 0.) - Vec2::new(0.75, 0.5)).length() < 1e-4);
+    }
+
+    #[test]
+    fn test_failure_intersect() {
+        let spline = patch(PatchBasis::BSpline, net(4, 1., 5., 5.)).unwrap();
+        let ray = Ray::new(&Vec3A::new(0.5, 1.5, 0.), &Vec3A::Z);
+        assert_eq!(spline.intersect(&ray, &RayType::Camera), None);
+        let ray = Ray::new(&Vec3A::new(1.5, 1.5, 0.), &Vec3A::Z);
+        assert_eq!(spline.intersect(&ray, &RayType::Light), None);
+
+        assert!(patch(PatchBasis::Bezier, net(5, 1., 0., 0.)).is_err());
+        assert!(patch(PatchBasis::BSpline, net(3, 1., 0., 0.)).is_err());
+        let mut uneven = net(4, 1., 0., 0.);
+        uneven[2].pop();
+        assert!(patch(PatchBasis::Bezier, uneven).is_err());
+    }
+}
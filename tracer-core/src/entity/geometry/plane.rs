@@ -1,23 +1,38 @@
-use glam::Vec3A;
+use glam::{Vec2, Vec3A};
+use serde::{Deserialize, Serialize};
 
 use crate::entity::{
     actor::{ActorTrait, DirectionalActor, DirectionalActorTrait},
-    geometry::{Geometry, RayType, ray::Ray},
+    geometry::{Geometry, RayType, aabb::Aabb, ray::Ray},
     rendering::material::{MaterialBound, MaterialType},
 };
 
 /// Structure representing a Planar surface.
+#[derive(Serialize, Deserialize)]
 pub struct Plane {
+    #[serde(flatten)]
     dir_actor: DirectionalActor,
     material: MaterialType,
 }
 
 impl Plane {
-    pub fn new(position: &Vec3A, direction: &Vec3A, material: &MaterialType) -> Self {
-        Self {
+    /// Fail if the normal is not finite and non-zero, the plane then having no orientation.
+    pub fn new(
+        position: &Vec3A,
+        direction: &Vec3A,
+        material: &MaterialType,
+    ) -> Result<Self, String> {
+        if !direction.is_finite() || *direction == Vec3A::ZERO {
+            return Err("the normal must be finite and non-zero".to_string());
+        }
+        Ok(Self {
             dir_actor: DirectionalActor::new(position, direction),
             material: material.to_owned(),
-        }
+        })
+    }
+
+    pub fn set_velocity(&mut self, velocity: &Vec3A) {
+        self.dir_actor.set_velocity(velocity);
     }
 }
 
@@ -38,21 +53,54 @@ impl ActorTrait for Plane {
     fn get_position(&self) -> Vec3A {
         self.dir_actor.get_position()
     }
+
+    fn get_velocity(&self) -> Vec3A {
+        self.dir_actor.get_velocity()
+    }
 }
 
 impl Geometry for Plane {
-    fn get_surface_normal(&self, _point: &Vec3A) -> Vec3A {
+    fn get_surface_normal(&self, _point: &Vec3A, _time: f32) -> Vec3A {
         self.dir_actor.get_direction()
     }
 
+    /// Map the plane along two axes perpendicular to its normal, one unit of the scene per unit of texture.
+    fn get_uv(&self, point: &Vec3A, time: f32) -> Vec2 {
+        let (tangent, bitangent) = self.get_direction().normalize().any_orthonormal_pair();
+        let local = point - self.get_position_at(time);
+        Vec2::new(local.dot(tangent), local.dot(bitangent))
+    }
+
+    /// Planes are unbounded, except along the axis they are perpendicular to, if any.
+    fn get_bounds(&self, time_start: f32, time_end: f32) -> Aabb {
+        let normal = self.get_direction().normalize();
+        match Vec3A::AXES
+            .iter()
+            .position(|x| x.dot(normal).abs() > 1. - 1e-6)
+        {
+            Some(axis) => {
+                let (start, end) = (
+                    self.get_position_at(time_start)[axis],
+                    self.get_position_at(time_end)[axis],
+                );
+                let (mut min, mut max) = (Vec3A::NEG_INFINITY, Vec3A::INFINITY);
+                min[axis] = start.min(end);
+                max[axis] = start.max(end);
+                Aabb::new(min, max)
+            }
+            _ => Aabb::INFINITE,
+        }
+    }
+
     //// check if the ray intersects with the current plane structure and return the ray's color post-interaction.
     fn intersect(&self, ray: &Ray, ray_type: &RayType) -> Option<(f32, Vec3A)> {
         let n_dot_l = ray.get_direction().dot(self.get_direction());
         match n_dot_l {
             x if x < 0.001 => None,
             _ => {
-                let t =
-                    (self.get_position() - ray.get_position()).dot(self.get_direction()) / n_dot_l;
+                let t = (self.get_position_at(ray.get_time()) - ray.get_position())
+                    .dot(self.get_direction())
+                    / n_dot_l;
 
                 match ray_type {
                     RayType::Camera => match t {
@@ -89,7 +137,15 @@ mod tests {
             &Vec3A::new(2., 1., 0.),
             &Vec3A::new(2., 1., 0.),
             &MaterialType::Color(ColorMaterial::new(Vec4::ONE)),
-        );
-        assert!(plane.intersect(&ray, &RayType::Camera) != None);
+        )
+        .unwrap();
+        assert!(plane.intersect(&ray, &RayType::Camera).is_some());
+    }
+
+    #[test]
+    fn test_failure_new() {
+        let material = MaterialType::Color(ColorMaterial::new(Vec4::ONE));
+        assert!(Plane::new(&Vec3A::ZERO, &Vec3A::ZERO, &material).is_err());
+        assert!(Plane::new(&Vec3A::ZERO, &Vec3A::new(f32::NAN, 1., 0.), &material).is_err());
     }
 }
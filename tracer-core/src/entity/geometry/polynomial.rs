@@ -0,0 +1,233 @@
+use std::f64::consts::PI;
+
+/// Solve `a x² + b x + c = 0`, falling back to the linear equation when `a` is zero.
+/// Like the other solvers, return the real roots sorted in increasing order.
+pub fn solve_quadratic(a: f64, b: f64, c: f64) -> Vec<f64> {
+    if a == 0. {
+        return match b {
+            0. => Vec::new(),
+            b => vec![-c / b],
+        };
+    }
+    let discriminant = b * b - 4. * a * c;
+    if discriminant < 0. {
+        return Vec::new();
+    }
+    // Avoid the cancellation between -b and the square root.
+    let q = -0.5 * (b + b.signum() * discriminant.sqrt());
+    let mut roots = match q {
+        0. => vec![0., 0.],
+        q => vec![q / a, c / q],
+    };
+    roots.sort_by(f64::total_cmp);
+    roots
+}
+
+/// Solve `a x³ + b x² + c x + d = 0`, falling back to the quadratic equation when `a` is zero.
+pub fn solve_cubic(a: f64, b: f64, c: f64, d: f64) -> Vec<f64> {
+    if a == 0. {
+        return solve_quadratic(b, c, d);
+    }
+    let (b, c, d) = (b / a, c / a, d / a);
+    let q = (b * b - 3. * c) / 9.;
+    let r = (2. * b * b * b - 9. * b * c + 27. * d) / 54.;
+    let offset = b / 3.;
+
+    let mut roots = match r * r < q * q * q {
+        true => {
+            let theta = (r / (q * q * q).sqrt()).clamp(-1., 1.).acos();
+            let scale = -2. * q.sqrt();
+            (0..3)
+                .map(|k| scale * ((theta + 2. * PI * k as f64) / 3.).cos() - offset)
+                .collect()
+        }
+        false => {
+            let s = -r.signum() * (r.abs() + (r * r - q * q * q).sqrt()).cbrt();
+            let t = if s == 0. { 0. } else { q / s };
+            vec![s + t - offset]
+        }
+    };
+    roots.sort_by(f64::total_cmp);
+    roots
+}
+
+/// Solve `a x⁴ + b x³ + c x² + d x + e = 0` with Ferrari's method, falling back to the cubic equation when `a` is
+/// zero. The roots are refined by a few Newton steps on the original polynomial.
+pub fn solve_quartic(a: f64, b: f64, c: f64, d: f64, e: f64) -> Vec<f64> {
+    if a == 0. {
+        return solve_cubic(b, c, d, e);
+    }
+    let (b, c, d, e) = (b / a, c / a, d / a, e / a);
+
+    // Depressed quartic y⁴ + p y² + q y + r = 0, with x = y - b / 4.
+    let offset = b / 4.;
+    let b2 = b * b;
+    let p = c - 3. * b2 / 8.;
+    let q = d - b * c / 2. + b2 * b / 8.;
+    let r = e - b * d / 4. + b2 * c / 16. - 3. * b2 * b2 / 256.;
+
+    let mut roots = match q.abs() < 1e-12 {
+        // Biquadratic equation, quadratic in y².
+        true => solve_quadratic(1., p, r)
+            .into_iter()
+            .filter(|x| *x >= 0.)
+            .flat_map(|x| [-x.sqrt(), x.sqrt()])
+            .collect::<Vec<f64>>(),
+        false => {
+            // Any positive root of the resolvent cubic splits the quartic into two quadratics.
+            let m = solve_cubic(1., p, p * p / 4. - r, -q * q / 8.)
+                .into_iter()
+                .fold(f64::NEG_INFINITY, f64::max);
+            if m <= 0. {
+                return Vec::new();
+            }
+            let s = (2. * m).sqrt();
+            let mut roots = solve_quadratic(1., s, p / 2. + m - q / (2. * s));
+            roots.extend(solve_quadratic(1., -s, p / 2. + m + q / (2. * s)));
+            roots
+        }
+    };
+
+    let value = |x: f64| (((x + b) * x + c) * x + d) * x + e;
+    let slope = |x: f64| ((4. * x + 3. * b) * x + 2. * c) * x + d;
+    roots.iter_mut().for_each(|root| {
+        *root -= offset;
+        (0..2).for_each(|_| {
+            let slope = slope(*root);
+            if slope != 0. {
+                *root -= value(*root) / slope;
+            }
+        });
+    });
+    roots.sort_by(f64::total_cmp);
+    roots
+}
+
+/// Evaluate the polynomial of the given coefficients, sorted by increasing degree, with Horner's method.
+pub fn evaluate(coefficients: &[f64], x: f64) -> f64 {
+    coefficients
+        .iter()
+        .rev()
+        .fold(0., |value, coefficient| value * x + coefficient)
+}
+
+/// Find the real roots of the polynomial of the given coefficients, sorted by increasing degree, within the finite
+/// interval [start, end]. The polynomial is monotonic between the roots of its derivative, found recursively, so
+/// each of these intervals holds at most one root, found by bisection. Any degree is handled.
+pub fn find_roots(coefficients: &[f64], start: f64, end: f64) -> Vec<f64> {
+    let Some(degree) = coefficients.iter().rposition(|x| *x != 0.) else {
+        return Vec::new();
+    };
+    if degree == 0 || start > end {
+        return Vec::new();
+    }
+    if degree == 1 {
+        let root = -coefficients[0] / coefficients[1];
+        return match (start..=end).contains(&root) {
+            true => vec![root],
+            false => Vec::new(),
+        };
+    }
+
+    let derivative = coefficients[1..=degree]
+        .iter()
+        .enumerate()
+        .map(|(power, coefficient)| coefficient * (power + 1) as f64)
+        .collect::<Vec<f64>>();
+    let mut bounds = vec![start];
+    bounds.extend(find_roots(&derivative, start, end));
+    bounds.push(end);
+
+    let mut roots = match evaluate(coefficients, start) {
+        0. => vec![start],
+        _ => Vec::new(),
+    };
+    roots.extend(
+        bounds
+            .windows(2)
+            .filter_map(|x| bisect(coefficients, x[0], x[1])),
+    );
+    roots
+}
+
+/// Find the root of a monotonic polynomial within ]low, high], if its values change of sign.
+fn bisect(coefficients: &[f64], mut low: f64, mut high: f64) -> Option<f64> {
+    let low_value = evaluate(coefficients, low);
+    let high_value = evaluate(coefficients, high);
+    if high_value == 0. {
+        return Some(high);
+    }
+    if low_value == 0. || low_value.signum() == high_value.signum() {
+        return None;
+    }
+    loop {
+        let middle = (low + high) / 2.;
+        if middle <= low || middle >= high {
+            return Some(middle);
+        }
+        match evaluate(coefficients, middle) {
+            0. => return Some(middle),
+            x if x.signum() == low_value.signum() => low = middle,
+            _ => high = middle,
+        }
+    }
+}
+
+// #####################################
+
+#[cfg(test)]
+mod tests {
+    use crate::entity::geometry::polynomial::{
+        find_roots, solve_cubic, solve_quadratic, solve_quartic,
+    };
+
+    fn assert_roots(roots: &[f64], expected: &[f64]) {
+        assert_eq!(roots.len(), expected.len(), "{:?}", roots);
+        roots
+            .iter()
+            .zip(expected)
+            .for_each(|(root, expected)| assert!((root - expected).abs() < 1e-6, "{:?}", roots));
+    }
+
+    #[test]
+    fn test_success_solve() {
+        assert_roots(&solve_quadratic(2., -2., -4.), &[-1., 2.]);
+        assert_roots(&solve_quadratic(0., 2., -4.), &[2.]);
+        // (x + 1)(x - 2)(x - 3)
+        assert_roots(&solve_cubic(1., -4., 1., 6.), &[-1., 2., 3.]);
+        // (x - 1)(x² + 1)
+        assert_roots(&solve_cubic(2., -2., 2., -2.), &[1.]);
+    }
+
+    #[test]
+    fn test_success_solve_quartic() {
+        // (x - 1)(x - 2)(x - 3)(x - 4)
+        assert_roots(&solve_quartic(1., -10., 35., -50., 24.), &[1., 2., 3., 4.]);
+        // (x² - 1)(x² - 9), biquadratic.
+        assert_roots(&solve_quartic(3., 0., -30., 0., 27.), &[-3., -1., 1., 3.]);
+        // (x - 2)(x + 5)(x² + 1)
+        assert_roots(&solve_quartic(1., 3., -9., 3., -10.), &[-5., 2.]);
+    }
+
+    #[test]
+    fn test_success_find_roots() {
+        // (x - 1)(x - 2)(x - 3)(x - 4)(x - 5)
+        let quintic = [-120., 274., -225., 85., -15., 1.];
+        assert_roots(&find_roots(&quintic, 0., 10.), &[1., 2., 3., 4., 5.]);
+        assert_roots(&find_roots(&quintic, 2.5, 10.), &[3., 4., 5.]);
+        // (x - 2)², touching zero without crossing it.
+        assert_roots(&find_roots(&[4., -4., 1.], 0., 10.), &[2.]);
+        assert_roots(&find_roots(&[-4., 2.], 0., 10.), &[2.]);
+    }
+
+    #[test]
+    fn test_failure_solve_without_real_roots() {
+        assert!(solve_quadratic(1., 0., 1.).is_empty());
+        assert!(solve_quadratic(0., 0., 1.).is_empty());
+        assert!(solve_quartic(1., 0., 0., 0., 1.).is_empty());
+        assert!(solve_quartic(1., 0., 2., 0., 1.).is_empty());
+        assert!(find_roots(&[1., 0., 1.], -10., 10.).is_empty());
+        assert!(find_roots(&[-4., 2.], 3., 10.).is_empty());
+        assert!(find_roots(&[5.], -10., 10.).is_empty());
+    }
+}
@@ -0,0 +1,186 @@
+use glam::{Vec2, Vec3A};
+use serde::{Deserialize, Serialize};
+
+use crate::entity::actor::{Actor, ActorTrait, DirectionalActorTrait};
+use crate::entity::geometry::{Geometry, RayType, aabb::Aabb, ray::Ray};
+use crate::entity::rendering::material::{MaterialBound, MaterialType};
+
+/// Structure representing a flat parallelogram, spanned by its two edges from its position, one of its corners.
+/// Its normal follows the right hand rule from the first edge to the second one, and it is hit from both sides.
+#[derive(Serialize, Deserialize)]
+pub struct Quad {
+    #[serde(flatten)]
+    pub actor: Actor,
+    pub edge_u: Vec3A,
+    pub edge_v: Vec3A,
+    material: MaterialType,
+}
+
+impl std::ops::Deref for Quad {
+    type Target = Actor;
+    fn deref(&self) -> &Self::Target {
+        &self.actor
+    }
+}
+
+impl MaterialBound for Quad {
+    fn get_material(&self) -> &MaterialType {
+        &self.material
+    }
+}
+
+impl Quad {
+    /// Fail if the edges are not finite, or if they are parallel or zero, the quad then being empty.
+    pub fn new(
+        position: &Vec3A,
+        edge_u: &Vec3A,
+        edge_v: &Vec3A,
+        material: &MaterialType,
+    ) -> Result<Self, String> {
+        if !edge_u.is_finite() || !edge_v.is_finite() {
+            return Err("the edges must be finite".to_string());
+        }
+        if edge_u.cross(*edge_v) == Vec3A::ZERO {
+            return Err("the edges must be non-zero and not parallel".to_string());
+        }
+        Ok(Self {
+            actor: Actor::new(position),
+            edge_u: *edge_u,
+            edge_v: *edge_v,
+            material: material.to_owned(),
+        })
+    }
+
+    pub fn set_velocity(&mut self, velocity: &Vec3A) {
+        self.actor.velocity = *velocity;
+    }
+
+    /// Get the coordinates of a point of the quad's plane along the edges, within [0, 1] inside the quad.
+    fn get_coordinates(&self, point: &Vec3A, time: f32) -> Vec2 {
+        let normal = self.edge_u.cross(self.edge_v);
+        let w = normal / normal.dot(normal);
+        let local = point - self.get_position_at(time);
+        Vec2::new(
+            w.dot(local.cross(self.edge_v)),
+            w.dot(self.edge_u.cross(local)),
+        )
+    }
+}
+
+impl ActorTrait for Quad {
+    fn get_position(&self) -> Vec3A {
+        self.actor.get_position()
+    }
+
+    fn get_velocity(&self) -> Vec3A {
+        self.actor.get_velocity()
+    }
+}
+
+impl Geometry for Quad {
+    fn get_surface_normal(&self, _point: &Vec3A, _time: f32) -> Vec3A {
+        self.edge_u.cross(self.edge_v).normalize()
+    }
+
+    fn get_uv(&self, point: &Vec3A, time: f32) -> Vec2 {
+        self.get_coordinates(point, time)
+    }
+
+    fn get_bounds(&self, time_start: f32, time_end: f32) -> Aabb {
+        [time_start, time_end]
+            .iter()
+            .map(|x| self.get_position_at(*x))
+            .flat_map(|corner| {
+                [
+                    corner,
+                    corner + self.edge_u,
+                    corner + self.edge_v,
+                    corner + self.edge_u + self.edge_v,
+                ]
+            })
+            .fold(Aabb::EMPTY, |bounds, corner| {
+                bounds.union(&Aabb::new(corner, corner))
+            })
+    }
+
+    fn intersect(&self, ray: &Ray, ray_type: &RayType) -> Option<(f32, Vec3A)> {
+        let normal = self.edge_u.cross(self.edge_v);
+        let n_dot_d = ray.get_direction().dot(normal);
+        if n_dot_d == 0. {
+            return None;
+        }
+        let t = (self.get_position_at(ray.get_time()) - ray.get_position()).dot(normal) / n_dot_d;
+        let point = ray.get_position() + t * ray.get_direction();
+        let coordinates = self.get_coordinates(&point, ray.get_time());
+        match coordinates.cmpge(Vec2::ZERO).all() && coordinates.cmple(Vec2::ONE).all() {
+            true => ray_type.nearest([t]).map(|t| (t, point)),
+            false => None,
+        }
+    }
+}
+
+// #####################################
+
+#[cfg(test)]
+mod tests {
+    use glam::{Vec2, Vec3A, Vec4};
+
+    use crate::entity::{
+        geometry::{Geometry, RayType, aabb::Aabb, quad::Quad, ray::Ray},
+        rendering::material::{ColorMaterial, MaterialType},
+    };
+
+    fn quad() -> Quad {
+        Quad::new(
+            &Vec3A::new(-1., -1., 10.),
+            &Vec3A::new(4., 0., 0.),
+            &Vec3A::new(0., 2., 2.),
+            &MaterialType::Color(ColorMaterial::new(Vec4::ONE)),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_success_intersect() {
+        let quad = quad();
+        let ray = Ray::new(&Vec3A::new(0., 0., 0.), &Vec3A::Z);
+        let (distance, point) = quad.intersect(&ray, &RayType::Camera).unwrap();
+        assert!((distance - 11.).abs() < 1e-5);
+        let normal = quad.get_surface_normal(&point, 0.);
+        assert!((normal - Vec3A::new(0., -1., 1.).normalize()).length() < 1e-6);
+        assert!((quad.get_uv(&point, 0.) - Vec2::new(0.25, 0.5)).length() < 1e-6);
+
+        assert_eq!(
+            quad.get_bounds(0., 0.),
+            Aabb::new(Vec3A::new(-1., -1., 10.), Vec3A::new(3., 1., 12.))
+        );
+    }
+
+    #[test]
+    fn test_failure_intersect() {
+        let quad = quad();
+        let ray = Ray::new(&Vec3A::new(-1.5, 0., 0.), &Vec3A::Z);
+        assert_eq!(quad.intersect(&ray, &RayType::Camera), None);
+        let ray = Ray::new(&Vec3A::new(0., 1.5, 0.), &Vec3A::Z);
+        assert_eq!(quad.intersect(&ray, &RayType::Camera), None);
+
+        assert!(
+            Quad::new(
+                &Vec3A::ZERO,
+                &Vec3A::X,
+                &Vec3A::new(2., 0., 0.),
+                &MaterialType::Color(ColorMaterial::new(Vec4::ONE))
+            )
+            .is_err()
+        );
+        assert!(
+            Quad::new(
+                &Vec3A::ZERO,
+                &Vec3A::ZERO,
+                &Vec3A::Y,
+                &MaterialType::Color(ColorMaterial::new(Vec4::ONE))
+            )
+            .is_err()
+        );
+    }
+}
@@ -8,10 +8,39 @@ pub enum RayType {
     Light,
 }
 
-/// Structure holding a ray's geometric data.
+impl RayType {
+    /// Tell whether a hit at the given ray's parameter counts: past the screen for camera rays, and between the
+    /// surface and the light for light rays.
+    pub fn accepts(&self, t: f32) -> bool {
+        match self {
+            RayType::Camera => t > 1.,
+            RayType::Light => (0.0001..=1.).contains(&t),
+        }
+    }
+
+    /// Get the range of the ray's parameter where hits count, the camera rays' one being unbounded.
+    pub fn get_range(&self) -> (f32, f32) {
+        match self {
+            RayType::Camera => (1., f32::INFINITY),
+            RayType::Light => (0.0001, 1.),
+        }
+    }
+
+    /// Get the nearest of the given ray's parameters counting as a hit, if any.
+    pub fn nearest(&self, candidates: impl IntoIterator<Item = f32>) -> Option<f32> {
+        candidates
+            .into_iter()
+            .filter(|x| self.accepts(*x))
+            .min_by(f32::total_cmp)
+    }
+}
+
+/// Structure holding a ray's geometric data, along with the time it is cast at, relative to the frame's time.
+/// Moving actors are intersected where they are at the ray's time.
 #[derive(Debug)]
 pub struct Ray {
     dir_actor: DirectionalActor,
+    time: f32,
 }
 
 impl std::ops::Deref for Ray {
@@ -25,14 +54,26 @@ impl Ray {
     pub const fn new(position: &Vec3A, direction: &Vec3A) -> Self {
         Self {
             dir_actor: DirectionalActor::new(position, direction),
+            time: 0.,
         }
     }
+
+    /// Cast the ray at the given time instead.
+    pub const fn with_time(mut self, time: f32) -> Self {
+        self.time = time;
+        self
+    }
+
+    pub const fn get_time(&self) -> f32 {
+        self.time
+    }
 }
 
 impl PartialEq for Ray {
     fn eq(&self, ray: &Ray) -> bool {
         self.dir_actor.get_position() == ray.get_position()
             && self.get_direction() == ray.get_direction()
+            && self.time == ray.time
     }
 }
 
@@ -0,0 +1,626 @@
+use std::f32::consts::{PI, TAU};
+
+use glam::{Quat, Vec2, Vec3A};
+use serde::{Deserialize, Serialize};
+
+use crate::entity::actor::{Actor, ActorTrait, DirectionalActorTrait};
+use crate::entity::geometry::transform::{Transform, TransformMatrices};
+use crate::entity::geometry::{Geometry, RayType, aabb::Aabb, ray::Ray};
+use crate::entity::rendering::material::{MaterialBound, MaterialType};
+
+/// Composable signed distance function: primitives centered on the origin, and operators combining or deforming
+/// other shapes. The distance is negative inside the shape.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SdfShape {
+    Sphere {
+        radius: f32,
+    },
+    #[serde(rename = "box")]
+    Cuboid {
+        size: Vec3A,
+    },
+    /// Torus lying in the XZ plane.
+    Torus {
+        major_radius: f32,
+        minor_radius: f32,
+    },
+    /// Capped cylinder along the Y axis.
+    Cylinder {
+        radius: f32,
+        height: f32,
+    },
+    /// Segment between two points, thickened by the radius.
+    Capsule {
+        start: Vec3A,
+        end: Vec3A,
+        radius: f32,
+    },
+    /// Half space below the plane at the given offset along its normal.
+    Plane {
+        normal: Vec3A,
+        offset: f32,
+    },
+    Union {
+        shapes: Vec<SdfShape>,
+    },
+    Intersection {
+        shapes: Vec<SdfShape>,
+    },
+    /// The first shape, minus all the others.
+    Difference {
+        shapes: Vec<SdfShape>,
+    },
+    /// Union blending the shapes together over the given distance.
+    SmoothUnion {
+        shapes: Vec<SdfShape>,
+        smoothness: f32,
+    },
+    Translate {
+        offset: Vec3A,
+        shape: Box<SdfShape>,
+    },
+    /// Rotation by Euler angles in degrees, applied in the X, Y then Z order.
+    Rotate {
+        rotation: Vec3A,
+        shape: Box<SdfShape>,
+    },
+    Scale {
+        factor: f32,
+        shape: Box<SdfShape>,
+    },
+    /// Infinite repetition of the shape along the axes with a non-zero period.
+    Repeat {
+        period: Vec3A,
+        shape: Box<SdfShape>,
+    },
+    /// Rotation around the Y axis, proportional to the height, in degrees per unit.
+    Twist {
+        rate: f32,
+        shape: Box<SdfShape>,
+    },
+    /// Inflate the shape by the radius, rounding its edges.
+    Round {
+        radius: f32,
+        shape: Box<SdfShape>,
+    },
+}
+
+impl SdfShape {
+    /// Get the signed distance between the point and the shape's surface.
+    /// Deforming operators (smooth unions, twists) only give a bound of it, to be compensated while sphere tracing.
+    pub fn distance(&self, p: &Vec3A) -> f32 {
+        match self {
+            SdfShape::Sphere { radius } => p.length() - radius,
+            SdfShape::Cuboid { size } => {
+                let q = p.abs() - *size / 2.;
+                q.max(Vec3A::ZERO).length() + q.max_element().min(0.)
+            }
+            SdfShape::Torus {
+                major_radius,
+                minor_radius,
+            } => Vec2::new(p.x.hypot(p.z) - major_radius, p.y).length() - minor_radius,
+            SdfShape::Cylinder { radius, height } => {
+                let d = Vec2::new(p.x.hypot(p.z) - radius, p.y.abs() - height / 2.);
+                d.max_element().min(0.) + d.max(Vec2::ZERO).length()
+            }
+            SdfShape::Capsule { start, end, radius } => {
+                let (pa, ba) = (p - start, end - start);
+                let h = (pa.dot(ba) / ba.length_squared()).clamp(0., 1.);
+                (pa - ba * h).length() - radius
+            }
+            SdfShape::Plane { normal, offset } => p.dot(normal.normalize()) - offset,
+            SdfShape::Union { shapes } => Self::fold(shapes, p, f32::min),
+            SdfShape::Intersection { shapes } => Self::fold(shapes, p, f32::max),
+            SdfShape::Difference { shapes } => Self::fold(shapes, p, |a, b| a.max(-b)),
+            SdfShape::SmoothUnion { shapes, smoothness } => Self::fold(shapes, p, |a, b| {
+                let h = (smoothness - (a - b).abs()).max(0.) / smoothness;
+                a.min(b) - h * h * smoothness / 4.
+            }),
+            SdfShape::Translate { offset, shape } => shape.distance(&(p - offset)),
+            SdfShape::Rotate { rotation, shape } => {
+                shape.distance(&(Self::get_rotation(rotation).inverse() * *p))
+            }
+            SdfShape::Scale { factor, shape } => shape.distance(&(p / *factor)) * factor,
+            SdfShape::Repeat { period, shape } => {
+                let repeated = p - *period * (*p / *period).round();
+                let q = Vec3A::select(period.cmpne(Vec3A::ZERO), repeated, *p);
+                shape.distance(&q)
+            }
+            SdfShape::Twist { rate, shape } => {
+                let (sin, cos) = (-rate.to_radians() * p.y).sin_cos();
+                let q = Vec3A::new(cos * p.x - sin * p.z, p.y, sin * p.x + cos * p.z);
+                shape.distance(&q)
+            }
+            SdfShape::Round { radius, shape } => shape.distance(p) - radius,
+        }
+    }
+
+    /// Get a box containing the shape, infinite for unbounded shapes.
+    pub fn get_bounds(&self) -> Aabb {
+        let extent = |x: Vec3A| Aabb::new(-x, x);
+        match self {
+            SdfShape::Sphere { radius } => extent(Vec3A::splat(*radius)),
+            SdfShape::Cuboid { size } => extent(*size / 2.),
+            SdfShape::Torus {
+                major_radius,
+                minor_radius,
+            } => {
+                let outer = major_radius + minor_radius;
+                extent(Vec3A::new(outer, *minor_radius, outer))
+            }
+            SdfShape::Cylinder { radius, height } => {
+                extent(Vec3A::new(*radius, height / 2., *radius))
+            }
+            SdfShape::Capsule { start, end, radius } => Aabb::new(
+                start.min(*end) - Vec3A::splat(*radius),
+                start.max(*end) + Vec3A::splat(*radius),
+            ),
+            SdfShape::Plane { .. } => Aabb::INFINITE,
+            SdfShape::Union { shapes } => shapes
+                .iter()
+                .fold(Aabb::EMPTY, |bounds, x| bounds.union(&x.get_bounds())),
+            SdfShape::Intersection { shapes } => shapes.iter().fold(Aabb::INFINITE, |bounds, x| {
+                bounds.intersection(&x.get_bounds())
+            }),
+            SdfShape::Difference { shapes } => {
+                shapes.first().map_or(Aabb::EMPTY, |x| x.get_bounds())
+            }
+            SdfShape::SmoothUnion { shapes, smoothness } => {
+                let bounds = shapes
+                    .iter()
+                    .fold(Aabb::EMPTY, |bounds, x| bounds.union(&x.get_bounds()));
+                Self::inflate(&bounds, smoothness / 4.)
+            }
+            SdfShape::Translate { offset, shape } => {
+                let bounds = shape.get_bounds();
+                Aabb::new(bounds.min + *offset, bounds.max + *offset)
+            }
+            SdfShape::Rotate { rotation, shape } => {
+                let transform = Transform {
+                    rotation: *rotation,
+                    ..Default::default()
+                };
+                TransformMatrices::from(&transform).bounds_to_world(&shape.get_bounds())
+            }
+            SdfShape::Scale { factor, shape } => {
+                let bounds = shape.get_bounds();
+                let (a, b) = (bounds.min * factor.abs(), bounds.max * factor.abs());
+                Aabb::new(a.min(b), a.max(b))
+            }
+            SdfShape::Repeat { period, shape } => {
+                let bounds = shape.get_bounds();
+                let repeated = period.cmpne(Vec3A::ZERO);
+                Aabb::new(
+                    Vec3A::select(repeated, Vec3A::NEG_INFINITY, bounds.min),
+                    Vec3A::select(repeated, Vec3A::INFINITY, bounds.max),
+                )
+            }
+            SdfShape::Twist { shape, .. } => {
+                let bounds = shape.get_bounds();
+                let radius = bounds.min.abs().max(bounds.max.abs());
+                let radius = radius.x.hypot(radius.z);
+                Aabb::new(
+                    Vec3A::new(-radius, bounds.min.y, -radius),
+                    Vec3A::new(radius, bounds.max.y, radius),
+                )
+            }
+            SdfShape::Round { radius, shape } => Self::inflate(&shape.get_bounds(), *radius),
+        }
+    }
+
+    /// Fail if the shape or any of the shapes it combines is degenerate, its distance then being undefined.
+    pub fn check(&self) -> Result<(), String> {
+        match self {
+            SdfShape::Capsule { start, end, .. } if start == end => {
+                Err("the capsule's start and end must differ".to_string())
+            }
+            SdfShape::Plane { normal, .. } if !normal.is_finite() || *normal == Vec3A::ZERO => {
+                Err("the plane's normal must be finite and non-zero".to_string())
+            }
+            SdfShape::SmoothUnion { smoothness, .. }
+                if smoothness.is_nan() || *smoothness <= 0. =>
+            {
+                Err("the smoothness must be above zero".to_string())
+            }
+            SdfShape::Scale { factor, .. } if !factor.is_finite() || *factor == 0. => {
+                Err("the scale factor must be finite and non-zero".to_string())
+            }
+            SdfShape::Union { shapes }
+            | SdfShape::Intersection { shapes }
+            | SdfShape::Difference { shapes }
+            | SdfShape::SmoothUnion { shapes, .. } => shapes.iter().try_for_each(|x| x.check()),
+            SdfShape::Translate { shape, .. }
+            | SdfShape::Rotate { shape, .. }
+            | SdfShape::Scale { shape, .. }
+            | SdfShape::Repeat { shape, .. }
+            | SdfShape::Twist { shape, .. }
+            | SdfShape::Round { shape, .. } => shape.check(),
+            _ => Ok(()),
+        }
+    }
+
+    fn fold(shapes: &[SdfShape], p: &Vec3A, combine: impl Fn(f32, f32) -> f32) -> f32 {
+        shapes
+            .iter()
+            .map(|x| x.distance(p))
+            .reduce(combine)
+            .unwrap_or(f32::INFINITY)
+    }
+
+    fn get_rotation(rotation: &Vec3A) -> Quat {
+        Transform {
+            rotation: *rotation,
+            ..Default::default()
+        }
+        .get_rotation()
+    }
+
+    fn inflate(bounds: &Aabb, distance: f32) -> Aabb {
+        Aabb::new(
+            bounds.min - Vec3A::splat(distance),
+            bounds.max + Vec3A::splat(distance),
+        )
+    }
+}
+
+/// Parameters of the sphere tracing: the ray advances by the fraction of the distance to the surface given by the
+/// step factor, which must be lowered for the shapes whose distance is only a bound (e.g. twisted ones), until it
+/// gets closer than epsilon, or the steps or distance run out.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SphereTracing {
+    pub max_steps: u32,
+    pub max_distance: f32,
+    pub epsilon: f32,
+    pub step_factor: f32,
+}
+
+impl Default for SphereTracing {
+    fn default() -> Self {
+        Self {
+            max_steps: 256,
+            max_distance: 10000.,
+            epsilon: 0.001,
+            step_factor: 1.,
+        }
+    }
+}
+
+impl SphereTracing {
+    /// Fail if the ray cannot converge onto the surface.
+    pub fn check(&self) -> Result<(), String> {
+        if self.max_steps == 0 {
+            return Err("the tracing's max_steps must be above zero".to_string());
+        }
+        if self.epsilon.is_nan() || self.epsilon <= 0. {
+            return Err("the tracing's epsilon must be above zero".to_string());
+        }
+        if self.step_factor.is_nan() || self.step_factor <= 0. || self.step_factor > 1. {
+            return Err("the tracing's step_factor must be above zero and at most 1".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Structure representing a shape defined by a signed distance function, placed at its position.
+#[derive(Serialize, Deserialize)]
+#[serde(try_from = "SdfData")]
+pub struct Sdf {
+    #[serde(flatten)]
+    pub actor: Actor,
+    pub shape: SdfShape,
+    #[serde(default)]
+    pub tracing: SphereTracing,
+    material: MaterialType,
+}
+
+/// Serialized fields of a signed distance function, checked while being deserialized.
+#[derive(Deserialize)]
+struct SdfData {
+    #[serde(flatten)]
+    actor: Actor,
+    shape: SdfShape,
+    #[serde(default)]
+    tracing: SphereTracing,
+    material: MaterialType,
+}
+
+impl TryFrom<SdfData> for Sdf {
+    type Error = String;
+
+    fn try_from(data: SdfData) -> Result<Self, Self::Error> {
+        let mut sdf = Sdf::new(
+            &data.actor.position,
+            data.shape,
+            &data.tracing,
+            &data.material,
+        )?;
+        sdf.set_velocity(&data.actor.velocity);
+        Ok(sdf)
+    }
+}
+
+impl std::ops::Deref for Sdf {
+    type Target = Actor;
+    fn deref(&self) -> &Self::Target {
+        &self.actor
+    }
+}
+
+impl MaterialBound for Sdf {
+    fn get_material(&self) -> &MaterialType {
+        &self.material
+    }
+}
+
+impl Sdf {
+    /// Fail if the shape is degenerate or the tracing cannot converge, the surface then being undefined.
+    pub fn new(
+        position: &Vec3A,
+        shape: SdfShape,
+        tracing: &SphereTracing,
+        material: &MaterialType,
+    ) -> Result<Self, String> {
+        shape.check()?;
+        tracing.check()?;
+        Ok(Self {
+            actor: Actor::new(position),
+            shape,
+            tracing: *tracing,
+            material: material.to_owned(),
+        })
+    }
+
+    pub fn set_velocity(&mut self, velocity: &Vec3A) {
+        self.actor.velocity = *velocity;
+    }
+}
+
+impl ActorTrait for Sdf {
+    fn get_position(&self) -> Vec3A {
+        self.actor.get_position()
+    }
+
+    fn get_velocity(&self) -> Vec3A {
+        self.actor.get_velocity()
+    }
+}
+
+impl Geometry for Sdf {
+    /// Estimate the distance's gradient by central differences, on the four vertices of a tetrahedron.
+    fn get_surface_normal(&self, point: &Vec3A, time: f32) -> Vec3A {
+        let local = point - self.get_position_at(time);
+        let h = self.tracing.epsilon;
+        [
+            Vec3A::new(1., -1., -1.),
+            Vec3A::new(-1., -1., 1.),
+            Vec3A::new(-1., 1., -1.),
+            Vec3A::new(1., 1., 1.),
+        ]
+        .iter()
+        .map(|x| *x * self.shape.distance(&(local + *x * h)))
+        .sum::<Vec3A>()
+        .normalize_or(Vec3A::Y)
+    }
+
+    /// Map the normal's longitude around the Y axis along U, and its latitude along V.
+    fn get_uv(&self, point: &Vec3A, time: f32) -> Vec2 {
+        let normal = self.get_surface_normal(point, time);
+        Vec2::new(
+            0.5 + normal.z.atan2(normal.x) / TAU,
+            0.5 + normal.y.clamp(-1., 1.).asin() / PI,
+        )
+    }
+
+    fn get_bounds(&self, time_start: f32, time_end: f32) -> Aabb {
+        let bounds = self.shape.get_bounds();
+        [time_start, time_end]
+            .iter()
+            .map(|x| self.get_position_at(*x))
+            .fold(Aabb::EMPTY, |moved, position| {
+                moved.union(&Aabb::new(bounds.min + position, bounds.max + position))
+            })
+    }
+
+    /// March along the normalized ray within the shape's bounds, by steps as long as the distance to the surface.
+    fn intersect(&self, ray: &Ray, ray_type: &RayType) -> Option<(f32, Vec3A)> {
+        let length = ray.get_direction().length();
+        if length == 0. {
+            return None;
+        }
+        let (near, far) = self
+            .get_bounds(ray.get_time(), ray.get_time())
+            .intersect(ray)?;
+        let (start, end) = ray_type.get_range();
+        let mut distance = start.max(near) * length;
+        let end = (end.min(far) * length).min(self.tracing.max_distance);
+
+        let origin = ray.get_position() - self.get_position_at(ray.get_time());
+        let direction = ray.get_direction() / length;
+        for _ in 0..self.tracing.max_steps {
+            if distance > end {
+                return None;
+            }
+            let step = self.shape.distance(&(origin + direction * distance)).abs();
+            if step < self.tracing.epsilon {
+                let t = distance / length;
+                return ray_type
+                    .accepts(t)
+                    .then(|| (t, ray.get_position() + t * ray.get_direction()));
+            }
+            distance += step * self.tracing.step_factor;
+        }
+        None
+    }
+}
+
+// #####################################
+
+#[cfg(test)]
+mod tests {
+    use glam::{Vec3A, Vec4};
+
+    use crate::entity::{
+        geometry::{
+            Geometry, RayType,
+            aabb::Aabb,
+            ray::Ray,
+            sdf::{Sdf, SdfShape, SphereTracing},
+        },
+        rendering::material::{ColorMaterial, MaterialType},
+    };
+
+    fn sdf(shape: SdfShape) -> Sdf {
+        Sdf::new(
+            &Vec3A::new(0., 0., 10.),
+            shape,
+            &SphereTracing::default(),
+            &MaterialType::Color(ColorMaterial::new(Vec4::ONE)),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_success_intersect() {
+        let sphere = sdf(SdfShape::Sphere { radius: 2. });
+        let ray = Ray::new(&Vec3A::ZERO, &Vec3A::new(0., 0., 2.));
+        let (distance, point) = sphere.intersect(&ray, &RayType::Camera).unwrap();
+        assert!((distance - 4.).abs() < 1e-3);
+        assert!((sphere.get_surface_normal(&point, 0.) + Vec3A::Z).length() < 1e-2);
+
+        // Two spheres blended together fill the gap between them.
+        let blend = sdf(SdfShape::SmoothUnion {
+            shapes: vec![
+                SdfShape::Translate {
+                    offset: Vec3A::new(-1.5, 0., 0.),
+                    shape: Box::new(SdfShape::Sphere { radius: 1. }),
+                },
+                SdfShape::Translate {
+                    offset: Vec3A::new(1.5, 0., 0.),
+                    shape: Box::new(SdfShape::Sphere { radius: 1. }),
+                },
+            ],
+            smoothness: 2.,
+        });
+        let ray = Ray::new(&Vec3A::ZERO, &Vec3A::Z);
+        let (distance, _) = blend.intersect(&ray, &RayType::Camera).unwrap();
+        assert!(distance < 10.);
+
+        // A rounded box, twisted and repeated along the X axis.
+        let shape = SdfShape::Repeat {
+            period: Vec3A::new(4., 0., 0.),
+            shape: Box::new(SdfShape::Twist {
+                rate: 10.,
+                shape: Box::new(SdfShape::Round {
+                    radius: 0.2,
+                    shape: Box::new(SdfShape::Cuboid {
+                        size: Vec3A::splat(1.),
+                    }),
+                }),
+            }),
+        };
+        assert!((shape.distance(&Vec3A::new(8., 0., -1.)) - 0.3).abs() < 1e-6);
+        let bounds = shape.get_bounds();
+        assert_eq!(bounds.min.x, f32::NEG_INFINITY);
+        assert!((bounds.max.y - 0.7).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_failure_intersect() {
+        let sphere = sdf(SdfShape::Sphere { radius: 2. });
+        let ray = Ray::new(&Vec3A::new(0., 3., 0.), &Vec3A::Z);
+        assert_eq!(sphere.intersect(&ray, &RayType::Camera), None);
+
+        // The light ray stops before reaching the sphere.
+        let ray = Ray::new(&Vec3A::ZERO, &Vec3A::new(0., 0., 5.));
+        assert_eq!(sphere.intersect(&ray, &RayType::Light), None);
+
+        // Running out of steps before reaching the surface.
+        let mut sphere = sphere;
+        sphere.tracing.max_steps = 1;
+        let ray = Ray::new(&Vec3A::new(0., 1.9, 0.), &Vec3A::Z);
+        assert_eq!(sphere.intersect(&ray, &RayType::Camera), None);
+        assert_eq!(
+            SdfShape::Plane {
+                normal: Vec3A::Y,
+                offset: 0.
+            }
+            .get_bounds(),
+            Aabb::INFINITE
+        );
+
+        let material = MaterialType::Color(ColorMaterial::new(Vec4::ONE));
+        let new = |shape: SdfShape, tracing: &SphereTracing| {
+            Sdf::new(&Vec3A::ZERO, shape, tracing, &material).err()
+        };
+        let sphere = || Box::new(SdfShape::Sphere { radius: 1. });
+        let tracing = SphereTracing::default();
+        assert_eq!(
+            new(
+                SdfShape::Union {
+                    shapes: vec![SdfShape::Scale {
+                        factor: 0.,
+                        shape: sphere()
+                    }]
+                },
+                &tracing
+            ),
+            Some("the scale factor must be finite and non-zero".to_string())
+        );
+        assert!(
+            new(
+                SdfShape::SmoothUnion {
+                    shapes: vec![*sphere()],
+                    smoothness: 0.
+                },
+                &tracing
+            )
+            .is_some()
+        );
+        assert!(
+            new(
+                SdfShape::Plane {
+                    normal: Vec3A::ZERO,
+                    offset: 0.
+                },
+                &tracing
+            )
+            .is_some()
+        );
+        assert!(
+            new(
+                SdfShape::Round {
+                    radius: 0.1,
+                    shape: Box::new(SdfShape::Capsule {
+                        start: Vec3A::Y,
+                        end: Vec3A::Y,
+                        radius: 1.
+                    })
+                },
+                &tracing
+            )
+            .is_some()
+        );
+        for tracing in [
+            SphereTracing {
+                max_steps: 0,
+                ..tracing
+            },
+            SphereTracing {
+                epsilon: 0.,
+                ..tracing
+            },
+            SphereTracing {
+                step_factor: 0.,
+                ..tracing
+            },
+            SphereTracing {
+                step_factor: 1.5,
+                ..tracing
+            },
+        ] {
+            assert!(new(*sphere(), &tracing).is_some());
+        }
+    }
+}
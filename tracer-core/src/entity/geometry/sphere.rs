@@ -1,12 +1,17 @@
 use crate::entity::actor::{Actor, ActorTrait, DirectionalActorTrait};
 use crate::entity::geometry::RayType;
-use crate::entity::geometry::{Geometry, ray::Ray};
+use crate::entity::geometry::{Geometry, Solid, aabb::Aabb, ray::Ray};
 use crate::entity::rendering::material::{MaterialBound, MaterialType};
 
-use glam::Vec3A;
+use std::f32::consts::{PI, TAU};
+
+use glam::{Vec2, Vec3A};
+use serde::{Deserialize, Serialize};
 
 /// Structure used to represent a spherical renderable.
+#[derive(Serialize, Deserialize)]
 pub struct Sphere {
+    #[serde(flatten)]
     pub actor: Actor,
     pub radius: f32,
     material: MaterialType,
@@ -26,12 +31,20 @@ impl MaterialBound for Sphere {
 }
 
 impl Sphere {
-    pub fn new(position: &Vec3A, radius: f32, material: &MaterialType) -> Self {
-        Self {
+    /// Fail if the radius is not finite and above zero, the normals then pointing inwards or being undefined.
+    pub fn new(position: &Vec3A, radius: f32, material: &MaterialType) -> Result<Self, String> {
+        if !radius.is_finite() || radius <= 0. {
+            return Err("the radius must be finite and above zero".to_string());
+        }
+        Ok(Self {
             actor: Actor::new(position),
             radius,
             material: material.to_owned(),
-        }
+        })
+    }
+
+    pub fn set_velocity(&mut self, velocity: &Vec3A) {
+        self.actor.velocity = *velocity;
     }
 }
 
@@ -39,17 +52,41 @@ impl ActorTrait for Sphere {
     fn get_position(&self) -> Vec3A {
         self.actor.get_position()
     }
+
+    fn get_velocity(&self) -> Vec3A {
+        self.actor.get_velocity()
+    }
 }
 
 impl Geometry for Sphere {
-    fn get_surface_normal(&self, point: &Vec3A) -> Vec3A {
-        (point - self.get_position()) / self.radius
+    fn get_surface_normal(&self, point: &Vec3A, time: f32) -> Vec3A {
+        (point - self.get_position_at(time)) / self.radius
+    }
+
+    /// Map the longitude around the Y axis along U, and the latitude from the bottom pole to the top one along V.
+    fn get_uv(&self, point: &Vec3A, time: f32) -> Vec2 {
+        let normal = self.get_surface_normal(point, time);
+        Vec2::new(
+            0.5 + normal.z.atan2(normal.x) / TAU,
+            0.5 + normal.y.clamp(-1., 1.).asin() / PI,
+        )
+    }
+
+    /// Bound the sphere along its whole motion during the given time interval.
+    fn get_bounds(&self, time_start: f32, time_end: f32) -> Aabb {
+        let radius = Vec3A::splat(self.radius);
+        [time_start, time_end]
+            .iter()
+            .map(|x| self.get_position_at(*x))
+            .fold(Aabb::EMPTY, |bounds, center| {
+                bounds.union(&Aabb::new(center - radius, center + radius))
+            })
     }
 
     /// Check line-circle plain intersection and return the ray color post-interaction.
     fn intersect(&self, ray: &Ray, ray_type: &RayType) -> Option<(f32, Vec3A)> {
         let d = ray.get_direction();
-        let f = ray.get_position() - self.position;
+        let f = ray.get_position() - self.get_position_at(ray.get_time());
 
         let a = d.dot(d);
         let b = 2. * f.dot(d);
@@ -92,6 +129,26 @@ impl Geometry for Sphere {
     }
 }
 
+impl Solid for Sphere {
+    fn get_intervals(&self, ray: &Ray) -> Vec<(f32, f32)> {
+        let d = ray.get_direction();
+        let f = ray.get_position() - self.get_position_at(ray.get_time());
+        let (a, b, c) = (
+            d.dot(d),
+            2. * f.dot(d),
+            f.dot(f) - self.radius * self.radius,
+        );
+        match b * b - 4. * a * c {
+            x if x < 0. || a == 0. => Vec::new(),
+            x => vec![((-b - x.sqrt()) / (2. * a), (-b + x.sqrt()) / (2. * a))],
+        }
+    }
+
+    fn get_surface_distance(&self, point: &Vec3A, time: f32) -> f32 {
+        (point.distance(self.get_position_at(time)) - self.radius).abs()
+    }
+}
+
 // #####################################
 
 #[cfg(test)]
@@ -110,16 +167,37 @@ mod tests {
             &Vec3A::new(2., 1., 0.),
             1.,
             &MaterialType::Color(ColorMaterial::new(Vec4::ONE)),
-        );
-        assert!(sphere.intersect(&ray, &RayType::Camera) != None);
+        )
+        .unwrap();
+        assert!(sphere.intersect(&ray, &RayType::Camera).is_some());
 
         let ray = Ray::new(&Vec3A::new(0., 2., 0.), &Vec3A::new(-1., -1., 0.));
         let sphere = Sphere::new(
             &Vec3A::new(-2., 1., 0.),
             1.,
             &MaterialType::Color(ColorMaterial::new(Vec4::ONE)),
-        );
-        assert!(sphere.intersect(&ray, &RayType::Camera) != None);
+        )
+        .unwrap();
+        assert!(sphere.intersect(&ray, &RayType::Camera).is_some());
+    }
+
+    #[test]
+    fn test_success_intersect_moving() {
+        let mut sphere = Sphere::new(
+            &Vec3A::new(0., 0., 10.),
+            1.,
+            &MaterialType::Color(ColorMaterial::new(Vec4::ONE)),
+        )
+        .unwrap();
+        sphere.set_velocity(&Vec3A::new(4., 0., 0.));
+        let ray = Ray::new(&Vec3A::new(2., 0., 0.), &Vec3A::Z);
+
+        assert_eq!(sphere.intersect(&ray, &RayType::Camera), None);
+        let (distance, point) = sphere
+            .intersect(&ray.with_time(0.5), &RayType::Camera)
+            .unwrap();
+        assert_eq!(distance, 9.);
+        assert_eq!(sphere.get_surface_normal(&point, 0.5), -Vec3A::Z);
     }
 
     // #####################################
@@ -131,7 +209,8 @@ mod tests {
             &Vec3A::new(-2., 1., 0.),
             1.,
             &MaterialType::Color(ColorMaterial::new(Vec4::ONE)),
-        );
+        )
+        .unwrap();
         assert_eq!(sphere.intersect(&ray, &RayType::Camera), None);
 
         let ray = Ray::new(&Vec3A::new(0., 2., 0.), &Vec3A::new(-1., 1., 0.));
@@ -139,7 +218,33 @@ mod tests {
             &Vec3A::new(-2., 1., 0.),
             1.,
             &MaterialType::Color(ColorMaterial::new(Vec4::ONE)),
-        );
+        )
+        .unwrap();
         assert_eq!(sphere.intersect(&ray, &RayType::Camera), None);
+
+        assert!(
+            Sphere::new(
+                &Vec3A::ZERO,
+                0.,
+                &MaterialType::Color(ColorMaterial::new(Vec4::ONE))
+            )
+            .is_err()
+        );
+        assert!(
+            Sphere::new(
+                &Vec3A::ZERO,
+                -1.,
+                &MaterialType::Color(ColorMaterial::new(Vec4::ONE))
+            )
+            .is_err()
+        );
+        assert!(
+            Sphere::new(
+                &Vec3A::ZERO,
+                f32::NAN,
+                &MaterialType::Color(ColorMaterial::new(Vec4::ONE))
+            )
+            .is_err()
+        );
     }
 }
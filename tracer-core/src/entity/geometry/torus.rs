@@ -0,0 +1,209 @@
+use std::f32::consts::TAU;
+
+use glam::{Vec2, Vec3A};
+use serde::{Deserialize, Serialize};
+
+use crate::entity::actor::{Actor, ActorTrait, DirectionalActorTrait};
+use crate::entity::geometry::{Geometry, RayType, aabb::Aabb, polynomial::solve_quartic, ray::Ray};
+use crate::entity::rendering::material::{MaterialBound, MaterialType};
+
+/// Structure representing a torus centered on its position and lying in the XZ plane: a tube of the minor radius
+/// around a circle of the major radius.
+#[derive(Serialize, Deserialize)]
+pub struct Torus {
+    #[serde(flatten)]
+    pub actor: Actor,
+    pub major_radius: f32,
+    pub minor_radius: f32,
+    material: MaterialType,
+}
+
+impl std::ops::Deref for Torus {
+    type Target = Actor;
+    fn deref(&self) -> &Self::Target {
+        &self.actor
+    }
+}
+
+impl MaterialBound for Torus {
+    fn get_material(&self) -> &MaterialType {
+        &self.material
+    }
+}
+
+impl Torus {
+    /// Fail if a radius is not finite and above zero, the torus then being empty.
+    pub fn new(
+        position: &Vec3A,
+        major_radius: f32,
+        minor_radius: f32,
+        material: &MaterialType,
+    ) -> Result<Self, String> {
+        if [major_radius, minor_radius]
+            .iter()
+            .any(|x| !x.is_finite() || *x <= 0.)
+        {
+            return Err("the radii must be finite and above zero".to_string());
+        }
+        Ok(Self {
+            actor: Actor::new(position),
+            major_radius,
+            minor_radius,
+            material: material.to_owned(),
+        })
+    }
+
+    pub fn set_velocity(&mut self, velocity: &Vec3A) {
+        self.actor.velocity = *velocity;
+    }
+}
+
+impl ActorTrait for Torus {
+    fn get_position(&self) -> Vec3A {
+        self.actor.get_position()
+    }
+
+    fn get_velocity(&self) -> Vec3A {
+        self.actor.get_velocity()
+    }
+}
+
+impl Geometry for Torus {
+    /// The normal points away from the nearest point of the tube's center circle.
+    fn get_surface_normal(&self, point: &Vec3A, time: f32) -> Vec3A {
+        let local = point - self.get_position_at(time);
+        let ring = Vec3A::new(local.x, 0., local.z).normalize_or_zero() * self.major_radius;
+        (local - ring) / self.minor_radius
+    }
+
+    /// Map the angle around the Y axis along U, and the angle around the tube along V.
+    fn get_uv(&self, point: &Vec3A, time: f32) -> Vec2 {
+        let local = point - self.get_position_at(time);
+        let distance = local.x.hypot(local.z) - self.major_radius;
+        Vec2::new(
+            0.5 + local.z.atan2(local.x) / TAU,
+            0.5 + local.y.atan2(distance) / TAU,
+        )
+    }
+
+    fn get_bounds(&self, time_start: f32, time_end: f32) -> Aabb {
+        let outer = self.major_radius + self.minor_radius;
+        let extent = Vec3A::new(outer, self.minor_radius, outer);
+        [time_start, time_end]
+            .iter()
+            .map(|x| self.get_position_at(*x))
+            .fold(Aabb::EMPTY, |bounds, center| {
+                bounds.union(&Aabb::new(center - extent, center + extent))
+            })
+    }
+
+    /// Solve the torus' quartic equation (|p|² + R² - r²)² = 4R²(x² + z²) along the normalized ray, in double
+    /// precision. The ray's origin is first moved next to the torus, to keep the coefficients well conditioned.
+    fn intersect(&self, ray: &Ray, ray_type: &RayType) -> Option<(f32, Vec3A)> {
+        let length = ray.get_direction().length() as f64;
+        if length == 0. {
+            return None;
+        }
+        let d = ray.get_direction().as_dvec3() / length;
+        let origin = (ray.get_position() - self.get_position_at(ray.get_time())).as_dvec3();
+        let (major, minor) = (self.major_radius as f64, self.minor_radius as f64);
+
+        // Skip the rays missing the bounding sphere, and start the others next to it.
+        let outer = major + minor;
+        let closest = -origin.dot(d);
+        if origin.length_squared() - closest * closest > outer * outer {
+            return None;
+        }
+        let shift = (closest - outer).max(0.);
+        let o = origin + d * shift;
+
+        let g = o.dot(d);
+        let k = o.length_squared() + major * major - minor * minor;
+        let r2 = 4. * major * major;
+        let roots = solve_quartic(
+            1.,
+            4. * g,
+            4. * g * g + 2. * k - r2 * (d.x * d.x + d.z * d.z),
+            4. * g * k - 2. * r2 * (o.x * d.x + o.z * d.z),
+            k * k - r2 * (o.x * o.x + o.z * o.z),
+        );
+        ray_type
+            .nearest(roots.iter().map(|x| ((x + shift) / length) as f32))
+            .map(|t| (t, ray.get_position() + t * ray.get_direction()))
+    }
+}
+
+// #####################################
+
+#[cfg(test)]
+mod tests {
+    use glam::{Vec2, Vec3A, Vec4};
+
+    use crate::entity::{
+        geometry::{Geometry, RayType, aabb::Aabb, ray::Ray, torus::Torus},
+        rendering::material::{ColorMaterial, MaterialType},
+    };
+
+    fn torus() -> Torus {
+        Torus::new(
+            &Vec3A::new(0., 0., 100.),
+            3.,
+            1.,
+            &MaterialType::Color(ColorMaterial::new(Vec4::ONE)),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_success_intersect() {
+        let torus = torus();
+        // Through the tube, on the near side of the ring.
+        let ray = Ray::new(&Vec3A::ZERO, &Vec3A::new(0., 0., 2.));
+        let (distance, point) = torus.intersect(&ray, &RayType::Camera).unwrap();
+        assert!((distance - 48.).abs() < 1e-4);
+        assert!((torus.get_surface_normal(&point, 0.) + Vec3A::Z).length() < 1e-4);
+        assert!((torus.get_uv(&point, 0.) - Vec2::new(0.25, 0.5)).length() < 1e-4);
+
+        // From above, onto the top of the tube.
+        let ray = Ray::new(&Vec3A::new(3., 10., 100.), &-Vec3A::Y);
+        let (distance, point) = torus.intersect(&ray, &RayType::Camera).unwrap();
+        assert!((distance - 9.).abs() < 1e-4);
+        assert!((torus.get_surface_normal(&point, 0.) - Vec3A::Y).length() < 1e-4);
+        assert!((torus.get_uv(&point, 0.) - Vec2::new(0.5, 0.75)).length() < 1e-4);
+
+        assert_eq!(
+            torus.get_bounds(0., 0.),
+            Aabb::new(Vec3A::new(-4., -1., 96.), Vec3A::new(4., 1., 104.))
+        );
+    }
+
+    #[test]
+    fn test_failure_intersect() {
+        let torus = torus();
+        // Down through the hole.
+        let ray = Ray::new(&Vec3A::new(0., 10., 100.), &-Vec3A::Y);
+        assert_eq!(torus.intersect(&ray, &RayType::Camera), None);
+        // Above the torus.
+        let ray = Ray::new(&Vec3A::new(0., 1.5, 0.), &Vec3A::Z);
+        assert_eq!(torus.intersect(&ray, &RayType::Camera), None);
+
+        assert!(
+            Torus::new(
+                &Vec3A::ZERO,
+                2.,
+                0.,
+                &MaterialType::Color(ColorMaterial::new(Vec4::ONE))
+            )
+            .is_err()
+        );
+        assert!(
+            Torus::new(
+                &Vec3A::ZERO,
+                -2.,
+                1.,
+                &MaterialType::Color(ColorMaterial::new(Vec4::ONE))
+            )
+            .is_err()
+        );
+    }
+}
@@ -0,0 +1,170 @@
+use glam::{Affine3A, BVec3A, EulerRot, Mat3A, Quat, Vec3A};
+use serde::{Deserialize, Serialize};
+
+use crate::entity::{
+    actor::{ActorTrait, DirectionalActorTrait},
+    geometry::{aabb::Aabb, ray::Ray},
+};
+
+/// Placement of an instance's object, applied in order: scale, then rotation (Euler angles in degrees, around X, Y
+/// then Z), then translation. Non-uniform scales stretch the object, e.g. into an ellipsoid out of a sphere.
+/// Geometries themselves only have a position: any other geometry is transformed by an instance placing it.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "TransformData", into = "TransformData")]
+pub struct Transform {
+    pub translation: Vec3A,
+    pub rotation: Vec3A,
+    pub scale: Vec3A,
+}
+
+/// Serialized fields of a transform, checked while being deserialized.
+#[derive(Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct TransformData {
+    translation: Vec3A,
+    rotation: Vec3A,
+    scale: Vec3A,
+}
+
+impl Default for TransformData {
+    fn default() -> Self {
+        Transform::default().into()
+    }
+}
+
+impl TryFrom<TransformData> for Transform {
+    type Error = String;
+
+    fn try_from(data: TransformData) -> Result<Self, Self::Error> {
+        Transform::new(&data.translation, &data.rotation, &data.scale)
+    }
+}
+
+impl From<Transform> for TransformData {
+    fn from(transform: Transform) -> Self {
+        Self {
+            translation: transform.translation,
+            rotation: transform.rotation,
+            scale: transform.scale,
+        }
+    }
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self {
+            translation: Vec3A::ZERO,
+            rotation: Vec3A::ZERO,
+            scale: Vec3A::ONE,
+        }
+    }
+}
+
+impl Transform {
+    /// Fail if the scale is not finite and non-zero along every axis, the object then not being invertible.
+    pub fn new(translation: &Vec3A, rotation: &Vec3A, scale: &Vec3A) -> Result<Self, String> {
+        if !scale.is_finite() || scale.cmpeq(Vec3A::ZERO).any() {
+            return Err("the scale must be finite and non-zero along every axis".to_string());
+        }
+        Ok(Self {
+            translation: *translation,
+            rotation: *rotation,
+            scale: *scale,
+        })
+    }
+
+    pub fn get_rotation(&self) -> Quat {
+        let radians = self.rotation * std::f32::consts::PI / 180.;
+        Quat::from_euler(EulerRot::XYZ, radians.x, radians.y, radians.z)
+    }
+
+    /// Get the matrix moving points from the object's space to the world's space.
+    pub fn to_affine(&self) -> Affine3A {
+        Affine3A::from_scale_rotation_translation(
+            self.scale.into(),
+            self.get_rotation(),
+            self.translation.into(),
+        )
+    }
+}
+
+// #####################################
+
+/// Transform along with the matrices derived from it, computed once for all the rays crossing the object.
+#[derive(Debug, Clone)]
+pub struct TransformMatrices {
+    object_to_world: Affine3A,
+    world_to_object: Affine3A,
+    normal_matrix: Mat3A,
+}
+
+impl From<&Transform> for TransformMatrices {
+    fn from(transform: &Transform) -> Self {
+        transform.to_affine().into()
+    }
+}
+
+impl From<Affine3A> for TransformMatrices {
+    fn from(object_to_world: Affine3A) -> Self {
+        Self {
+            object_to_world,
+            world_to_object: object_to_world.inverse(),
+            normal_matrix: object_to_world.matrix3.inverse().transpose(),
+        }
+    }
+}
+
+impl TransformMatrices {
+    /// Move the ray into the object's space. The direction is not normalized, so that the ray's parameter of any
+    /// point is the same in both spaces.
+    pub fn ray_to_object(&self, ray: &Ray) -> Ray {
+        Ray::new(
+            &self.world_to_object.transform_point3a(ray.get_position()),
+            &self.world_to_object.transform_vector3a(ray.get_direction()),
+        )
+        .with_time(ray.get_time())
+    }
+
+    pub fn point_to_object(&self, point: &Vec3A) -> Vec3A {
+        self.world_to_object.transform_point3a(*point)
+    }
+
+    pub fn point_to_world(&self, point: &Vec3A) -> Vec3A {
+        self.object_to_world.transform_point3a(*point)
+    }
+
+    /// Move a direction along the surface into the world's space, normalized.
+    pub fn direction_to_world(&self, direction: &Vec3A) -> Vec3A {
+        self.object_to_world
+            .transform_vector3a(*direction)
+            .normalize_or_zero()
+    }
+
+    /// Move a surface normal into the world's space through the inverse transpose matrix, keeping it perpendicular
+    /// to the stretched surface. The normal keeps its length.
+    pub fn normal_to_world(&self, normal: &Vec3A) -> Vec3A {
+        (self.normal_matrix * *normal).normalize_or_zero() * normal.length()
+    }
+
+    /// Get the world's box containing the object's box, out of its transformed corners.
+    pub fn bounds_to_world(&self, bounds: &Aabb) -> Aabb {
+        if bounds.is_empty() {
+            return Aabb::EMPTY;
+        }
+        if !bounds.min.is_finite() || !bounds.max.is_finite() {
+            return Aabb::INFINITE;
+        }
+        (0..8)
+            .map(|corner| {
+                Vec3A::select(
+                    BVec3A::new(corner & 1 != 0, corner & 2 != 0, corner & 4 != 0),
+                    bounds.max,
+                    bounds.min,
+                )
+            })
+            .fold(Aabb::EMPTY, |world, corner| {
+                let corner = self.point_to_world(&corner);
+                world.union(&Aabb::new(corner, corner))
+            })
+    }
+}
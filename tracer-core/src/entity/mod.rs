@@ -2,3 +2,4 @@ pub mod actor;
 pub mod geometry;
 pub mod rendering;
 pub mod scene;
+pub mod scene_graph;
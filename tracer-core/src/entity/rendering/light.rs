@@ -1,13 +1,17 @@
-use glam::Vec3A;
+use glam::{Vec2, Vec3A};
+use serde::{Deserialize, Serialize};
 
 use crate::entity::actor::{ActorTrait, DirectionalActorTrait};
 use crate::entity::geometry::Geometry;
+use crate::entity::geometry::aabb::Aabb;
 use crate::entity::geometry::ray::{Ray, RayType};
 use crate::entity::geometry::sphere::Sphere;
 use crate::entity::rendering::material::MaterialType;
 
 /// Structure holding a given light's representation.
+#[derive(Serialize, Deserialize)]
 pub struct Light {
+    #[serde(flatten)]
     geometry: Sphere,
     direction: Vec3A,
 }
@@ -20,11 +24,21 @@ impl std::ops::Deref for Light {
 }
 
 impl Light {
-    pub fn new(position: &Vec3A, direction: &Vec3A, radius: f32, material: &MaterialType) -> Self {
-        Self {
-            geometry: Sphere::new(position, radius, material),
+    /// Fail if the radius of the sphere the light is drawn as is not finite and above zero.
+    pub fn new(
+        position: &Vec3A,
+        direction: &Vec3A,
+        radius: f32,
+        material: &MaterialType,
+    ) -> Result<Self, String> {
+        Ok(Self {
+            geometry: Sphere::new(position, radius, material)?,
             direction: *direction,
-        }
+        })
+    }
+
+    pub fn set_velocity(&mut self, velocity: &Vec3A) {
+        self.geometry.set_velocity(velocity);
     }
 }
 
@@ -32,6 +46,10 @@ impl ActorTrait for Light {
     fn get_position(&self) -> Vec3A {
         self.actor.get_position()
     }
+
+    fn get_velocity(&self) -> Vec3A {
+        self.actor.get_velocity()
+    }
 }
 
 impl DirectionalActorTrait for Light {
@@ -46,7 +64,15 @@ impl Geometry for Light {
         self.geometry.intersect(ray, ray_type)
     }
 
-    fn get_surface_normal(&self, point: &Vec3A) -> Vec3A {
-        self.geometry.get_surface_normal(point)
+    fn get_surface_normal(&self, point: &Vec3A, time: f32) -> Vec3A {
+        self.geometry.get_surface_normal(point, time)
+    }
+
+    fn get_uv(&self, point: &Vec3A, time: f32) -> Vec2 {
+        self.geometry.get_uv(point, time)
+    }
+
+    fn get_bounds(&self, time_start: f32, time_end: f32) -> Aabb {
+        self.geometry.get_bounds(time_start, time_end)
     }
 }
@@ -1,4 +1,5 @@
-use glam::{FloatExt, Vec3A, Vec4};
+use glam::{FloatExt, Vec3, Vec3A, Vec4};
+use serde::{Deserialize, Serialize};
 
 use crate::entity::{
     actor::{ActorTrait, DirectionalActorTrait},
@@ -13,6 +14,7 @@ pub trait MaterialTrait {
         &self,
         scene: &Scene,
         surface_normal: &Vec3A,
+        surface_tangent: Option<Vec3A>,
         ray: &Ray,
         light: &Light,
         light_ray: &Ray,
@@ -24,7 +26,8 @@ pub trait MaterialTrait {
 
 // ########################################
 
-#[derive(Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct ColorMaterial {
     color: Vec4,
 }
@@ -40,6 +43,7 @@ impl MaterialTrait for ColorMaterial {
         &self,
         _scene: &Scene,
         _surface_normal: &Vec3A,
+        _surface_tangent: Option<Vec3A>,
         _ray: &Ray,
         _light: &Light,
         _light_ray: &Ray,
@@ -53,7 +57,8 @@ impl MaterialTrait for ColorMaterial {
 
 // ########################################
 
-#[derive(Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct DiffuseMaterial {
     diffuse: f32,
 }
@@ -69,6 +74,7 @@ impl MaterialTrait for DiffuseMaterial {
         &self,
         _scene: &Scene,
         surface_normal: &Vec3A,
+        _surface_tangent: Option<Vec3A>,
         _ray: &Ray,
         _light: &Light,
         light_ray: &Ray,
@@ -84,7 +90,8 @@ impl MaterialTrait for DiffuseMaterial {
 
 // ########################################
 
-#[derive(Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct SpecularMaterial {
     specular_reflection_coef: f32,
     shininess: f32,
@@ -104,6 +111,7 @@ impl MaterialTrait for SpecularMaterial {
         &self,
         _scene: &Scene,
         surface_normal: &Vec3A,
+        _surface_tangent: Option<Vec3A>,
         ray: &Ray,
         _light: &Light,
         light_ray: &Ray,
@@ -124,7 +132,69 @@ impl MaterialTrait for SpecularMaterial {
 
 // ########################################
 
-#[derive(Clone)]
+/// Kajiya-Kay shading of thin fibers, lit along their tangent rather than their normal: the diffuse term follows
+/// the sine between the tangent and the light, and the highlight is brightest when the view lies on the cone
+/// reflecting the light around the fiber. Surfaces without tangent are lit along their normal, without highlight.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct KajiyaKayMaterial {
+    diffuse: f32,
+    specular: f32,
+    shininess: f32,
+}
+
+impl KajiyaKayMaterial {
+    pub fn new(diffuse: f32, specular: f32, shininess: f32) -> Self {
+        Self {
+            diffuse,
+            specular,
+            shininess,
+        }
+    }
+}
+
+impl MaterialTrait for KajiyaKayMaterial {
+    fn calculate_illumination(
+        &self,
+        _scene: &Scene,
+        surface_normal: &Vec3A,
+        surface_tangent: Option<Vec3A>,
+        ray: &Ray,
+        _light: &Light,
+        light_ray: &Ray,
+        shadow_coef: &f32,
+        start_color: &Vec4,
+        _current_depth: &usize,
+    ) -> Vec4 {
+        let light_dir = light_ray.get_direction().normalize_or_zero();
+        let view_dir = -ray.get_direction().normalize_or_zero();
+        let (diffuse, specular) = match surface_tangent {
+            None => (surface_normal.dot(light_dir).max(0.), 0.),
+            Some(tangent) => {
+                let (tdotl, tdotv) = (tangent.dot(light_dir), tangent.dot(view_dir));
+                let sin_tl = (1. - tdotl * tdotl).max(0.).sqrt();
+                let sin_tv = (1. - tdotv * tdotv).max(0.).sqrt();
+                (
+                    sin_tl,
+                    (sin_tl * sin_tv - tdotl * tdotv)
+                        .saturate()
+                        .powf(self.shininess),
+                )
+            }
+        };
+
+        let lit =
+            start_color.truncate() * diffuse * self.diffuse + Vec3::splat(specular * self.specular);
+        (lit * shadow_coef)
+            .extend(start_color.w)
+            .clamp(Vec4::ZERO, Vec4::ONE)
+    }
+}
+
+// ########################################
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct ReflectiveMaterial {
     reflect_coef: f32,
     max_depth: usize,
@@ -143,8 +213,9 @@ impl MaterialTrait for ReflectiveMaterial {
     fn calculate_illumination(
         &self,
         scene: &Scene,
-        surface_normal: &Vec3A,
-        ray: &Ray,
+        _surface_normal: &Vec3A,
+        _surface_tangent: Option<Vec3A>,
+        _ray: &Ray,
         light: &Light,
         light_ray: &Ray,
         _shadow_coef: &f32,
@@ -152,14 +223,15 @@ impl MaterialTrait for ReflectiveMaterial {
         current_depth: &usize,
     ) -> Vec4 {
         match *current_depth {
-            x if x >= self.max_depth => *start_color,
+            x if x >= self.max_depth.min(scene.max_depth) => *start_color,
             _ => {
                 // let direction = (ray.get_direction()
                 //     - surface_normal * 2. * surface_normal.dot(ray.get_direction()))
                 // .normalize();
 
                 let relfection_ray =
-                    Ray::new(&light_ray.get_position(), &-light_ray.get_direction());
+                    Ray::new(&light_ray.get_position(), &-light_ray.get_direction())
+                        .with_time(light_ray.get_time());
                 let color = scene.render(
                     &relfection_ray,
                     light,
@@ -180,7 +252,8 @@ impl MaterialTrait for ReflectiveMaterial {
 
 // ########################################
 
-#[derive(Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct MaterialMixer {
     pub materials: Vec<MaterialType>,
 }
@@ -198,6 +271,7 @@ impl MaterialTrait for MaterialMixer {
         &self,
         scene: &Scene,
         surface_normal: &Vec3A,
+        surface_tangent: Option<Vec3A>,
         ray: &Ray,
         light: &Light,
         light_ray: &Ray,
@@ -211,6 +285,7 @@ impl MaterialTrait for MaterialMixer {
             result_color = x.calculate_illumination(
                 scene,
                 surface_normal,
+                surface_tangent,
                 ray,
                 light,
                 light_ray,
@@ -225,11 +300,13 @@ impl MaterialTrait for MaterialMixer {
 
 // ########################################
 
-#[derive(Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum MaterialType {
     Color(ColorMaterial),
     Diffuse(DiffuseMaterial),
     Specular(SpecularMaterial),
+    KajiyaKay(KajiyaKayMaterial),
     Reflective(ReflectiveMaterial),
     Mixer(MaterialMixer),
 }
@@ -239,6 +316,7 @@ impl MaterialTrait for MaterialType {
         &self,
         scene: &Scene,
         surface_normal: &Vec3A,
+        surface_tangent: Option<Vec3A>,
         ray: &Ray,
         light: &Light,
         light_ray: &Ray,
@@ -250,6 +328,7 @@ impl MaterialTrait for MaterialType {
             MaterialType::Color(i) => i.calculate_illumination(
                 scene,
                 surface_normal,
+                surface_tangent,
                 ray,
                 light,
                 light_ray,
@@ -260,6 +339,7 @@ impl MaterialTrait for MaterialType {
             MaterialType::Diffuse(i) => i.calculate_illumination(
                 scene,
                 surface_normal,
+                surface_tangent,
                 ray,
                 light,
                 light_ray,
@@ -270,6 +350,18 @@ impl MaterialTrait for MaterialType {
             MaterialType::Specular(i) => i.calculate_illumination(
                 scene,
                 surface_normal,
+                surface_tangent,
+                ray,
+                light,
+                light_ray,
+                _shadow_coef,
+                start_color,
+                current_depth,
+            ),
+            MaterialType::KajiyaKay(i) => i.calculate_illumination(
+                scene,
+                surface_normal,
+                surface_tangent,
                 ray,
                 light,
                 light_ray,
@@ -280,6 +372,7 @@ impl MaterialTrait for MaterialType {
             MaterialType::Reflective(i) => i.calculate_illumination(
                 scene,
                 surface_normal,
+                surface_tangent,
                 ray,
                 light,
                 light_ray,
@@ -290,6 +383,7 @@ impl MaterialTrait for MaterialType {
             MaterialType::Mixer(i) => i.calculate_illumination(
                 scene,
                 surface_normal,
+                surface_tangent,
                 ray,
                 light,
                 light_ray,
@@ -301,6 +395,81 @@ impl MaterialTrait for MaterialType {
     }
 }
 
+impl MaterialType {
+    /// Get the material's unlit base color, looking through mixer layers if needed.
+    pub fn get_base_color(&self) -> Option<Vec4> {
+        match self {
+            MaterialType::Color(i) => Some(i.color),
+            MaterialType::Mixer(i) => i.materials.iter().find_map(|x| x.get_base_color()),
+            _ => None,
+        }
+    }
+
+    /// Get the name of the material's variant, as written in the scene files.
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            MaterialType::Color(_) => "color",
+            MaterialType::Diffuse(_) => "diffuse",
+            MaterialType::Specular(_) => "specular",
+            MaterialType::KajiyaKay(_) => "kajiya_kay",
+            MaterialType::Reflective(_) => "reflective",
+            MaterialType::Mixer(_) => "mixer",
+        }
+    }
+}
+
 pub trait MaterialBound {
     fn get_material(&self) -> &MaterialType;
 }
+
+// ########################################
+
+#[cfg(test)]
+mod tests {
+    use glam::{Vec3A, Vec4};
+
+    use crate::entity::{
+        geometry::ray::Ray,
+        rendering::{
+            light::Light,
+            material::{ColorMaterial, KajiyaKayMaterial, MaterialTrait, MaterialType},
+        },
+        scene::Scene,
+    };
+
+    #[test]
+    fn test_success_kajiya_kay() {
+        let scene = Scene::new(&Vec4::ZERO);
+        let light = Light::new(
+            &Vec3A::new(10., 10., 0.),
+            &Vec3A::new(0., -1., 0.),
+            1.,
+            &MaterialType::Color(ColorMaterial::new(Vec4::ONE)),
+        )
+        .unwrap();
+        let light_ray = Ray::new(&Vec3A::ZERO, &Vec3A::new(10., 10., 0.));
+        let material = KajiyaKayMaterial::new(1., 0.5, 10.);
+        let shade = |direction: Vec3A, tangent: Option<Vec3A>| {
+            material.calculate_illumination(
+                &scene,
+                &Vec3A::Y,
+                tangent,
+                &Ray::new(&-direction, &direction),
+                &light,
+                &light_ray,
+                &1.,
+                &Vec4::new(0.4, 0.2, 0., 1.),
+                &0,
+            )
+        };
+        let diffuse = Vec4::new(0.4, 0.2, 0., 0.) * std::f32::consts::FRAC_1_SQRT_2;
+
+        // The highlight peaks when the view lies on the cone reflecting the light around the fiber.
+        let highlight = shade(Vec3A::new(1., -1., 0.), Some(Vec3A::X));
+        assert!((highlight - diffuse - Vec4::new(0.5, 0.5, 0.5, 1.)).length() < 1e-4);
+        let dull = shade(Vec3A::new(-1., -1., 0.), Some(Vec3A::X));
+        assert!((dull - diffuse - Vec4::W).length() < 1e-4);
+        let surface = shade(Vec3A::new(1., -1., 0.), None);
+        assert!((surface - diffuse - Vec4::W).length() < 1e-4);
+    }
+}
@@ -1,5 +1,6 @@
-use core::f32;
-use glam::Vec4;
+use std::ops::Range;
+
+use glam::{Vec3A, Vec4};
 
 use crate::entity::{
     actor::{ActorTrait, DirectionalActorTrait},
@@ -12,12 +13,30 @@ use crate::entity::{
         light::Light,
         material::{MaterialBound, MaterialTrait},
     },
+    scene_graph::SceneGraph,
 };
 
+/// Structure describing the nearest intersection found along a ray, at the ray's time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SurfaceHit {
+    pub renderable_index: usize,
+    pub distance: f32,
+    pub point: Vec3A,
+    pub normal: Vec3A,
+    /// Direction of the fibers at the hit point, for the geometries made of fibers.
+    pub tangent: Option<Vec3A>,
+    pub time: f32,
+}
+
 /// Container structure representing the scene's composition.
+/// The max depth bounds the recursion of every material casting secondary rays.
+/// The graph's geometries are rendered through the renderables flattened out of it, kept within the graph range.
 pub struct Scene {
     pub renderables: Vec<GeometryType>,
+    pub graph: SceneGraph,
+    graph_range: Range<usize>,
     pub ambient: Vec4,
+    pub max_depth: usize,
 }
 
 impl Renderable for Scene {
@@ -27,8 +46,53 @@ impl Renderable for Scene {
         ray: &Ray,
         light: &Light,
         ray_type: &RayType,
-        _current_depth: &usize,
+        current_depth: &usize,
     ) -> Option<Vec4> {
+        match self.closest_hit(ray, ray_type) {
+            None => Some(self.ambient),
+            Some(hit) => {
+                let (light_ray, see_light) = self.light_visibility(&hit, light);
+                Some(self.shade(ray, light, &hit, &light_ray, see_light, current_depth))
+            }
+        }
+    }
+}
+
+impl Scene {
+    pub const DEFAULT_MAX_DEPTH: usize = 8;
+
+    pub const fn new(ambient: &Vec4) -> Self {
+        Self {
+            renderables: Vec::new(),
+            graph: SceneGraph::new(),
+            graph_range: 0..0,
+            ambient: *ambient,
+            max_depth: Self::DEFAULT_MAX_DEPTH,
+        }
+    }
+
+    pub fn get_renderables(&self) -> &Vec<GeometryType> {
+        &self.renderables
+    }
+
+    /// Get the range of the renderables flattened out of the graph.
+    pub fn get_graph_range(&self) -> Range<usize> {
+        self.graph_range.clone()
+    }
+
+    /// Replace the renderables flattened out of the graph by the graph's current state, appending them after the
+    /// other renderables. To be called whenever the graph changes.
+    pub fn flatten_graph(&mut self) {
+        let range = self.graph_range.start.min(self.renderables.len())
+            ..self.graph_range.end.min(self.renderables.len());
+        self.renderables.drain(range);
+        let start = self.renderables.len();
+        self.renderables.extend(self.graph.flatten());
+        self.graph_range = start..self.renderables.len();
+    }
+
+    /// Find the nearest renderable crossed by the given ray, if any.
+    pub fn closest_hit(&self, ray: &Ray, ray_type: &RayType) -> Option<SurfaceHit> {
         let mut t_min = f32::NAN;
         let mut renderable_index: usize = 0;
 
@@ -42,54 +106,65 @@ impl Renderable for Scene {
         });
 
         match t_min.is_nan() {
-            true => Some(self.ambient),
+            true => None,
             false => {
-                let intersection_point = ray.get_direction() * t_min + ray.get_position();
-                let light_ray = Ray::new(
-                    &intersection_point,
-                    &(light.get_position() - intersection_point),
-                );
-
-                let see_light = f32::min(
-                    self.renderables
-                        .iter()
-                        .enumerate()
-                        .filter(|x| x.0 != renderable_index)
-                        .filter(|x| match x.1.intersect(&light_ray, &RayType::Light) {
-                            Some(hit) => hit.0 >= t_min,
-                            None => true,
-                        })
-                        .count() as f32,
-                    1.,
-                );
+                let point = ray.get_direction() * t_min + ray.get_position();
                 let renderable = &self.renderables[renderable_index];
-                Some(
-                    self.ambient
-                        + renderable.get_material().calculate_illumination(
-                            self,
-                            &renderable.get_surface_normal(&intersection_point),
-                            ray,
-                            light,
-                            &light_ray,
-                            &see_light,
-                            &Vec4::ZERO,
-                            &0,
-                        ),
-                )
+                Some(SurfaceHit {
+                    renderable_index,
+                    distance: t_min,
+                    point,
+                    normal: renderable.get_surface_normal(&point, ray.get_time()),
+                    tangent: renderable.get_tangent(&point, ray.get_time()),
+                    time: ray.get_time(),
+                })
             }
         }
     }
-}
 
-impl Scene {
-    pub const fn new(ambient: &Vec4) -> Self {
-        Self {
-            renderables: Vec::new(),
-            ambient: *ambient,
-        }
+    /// Build the ray going from the hit point to the light, and tell whether the light can be seen from there.
+    pub fn light_visibility(&self, hit: &SurfaceHit, light: &Light) -> (Ray, f32) {
+        let light_ray = Ray::new(&hit.point, &(light.get_position_at(hit.time) - hit.point))
+            .with_time(hit.time);
+
+        let see_light = f32::min(
+            self.renderables
+                .iter()
+                .enumerate()
+                .filter(|x| x.0 != hit.renderable_index)
+                .filter(|x| match x.1.intersect(&light_ray, &RayType::Light) {
+                    Some(shadow_hit) => shadow_hit.0 >= hit.distance,
+                    None => true,
+                })
+                .count() as f32,
+            1.,
+        );
+
+        (light_ray, see_light)
     }
 
-    pub fn get_renderables(&self) -> &Vec<GeometryType> {
-        &self.renderables
+    /// Compute the color of a surface hit, using the hit renderable's material.
+    pub fn shade(
+        &self,
+        ray: &Ray,
+        light: &Light,
+        hit: &SurfaceHit,
+        light_ray: &Ray,
+        see_light: f32,
+        current_depth: &usize,
+    ) -> Vec4 {
+        let renderable = &self.renderables[hit.renderable_index];
+        self.ambient
+            + renderable.get_material().calculate_illumination(
+                self,
+                &hit.normal,
+                hit.tangent,
+                ray,
+                light,
+                light_ray,
+                &see_light,
+                &Vec4::ZERO,
+                current_depth,
+            )
     }
 }
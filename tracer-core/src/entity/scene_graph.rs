@@ -0,0 +1,257 @@
+use std::sync::Arc;
+
+use glam::Affine3A;
+
+use crate::entity::{
+    geometry::{GeometryType, instance::Instance, transform::Transform},
+    rendering::material::MaterialType,
+};
+
+/// Named element of a scene graph, placed relative to its parent by its transform.
+/// A node may hold a shared geometry, and may group children moving along with it. The material overrides the
+/// geometries' ones, for the node and all its children without a material of their own.
+/// Hiding a node hides its whole subtree.
+pub struct Node {
+    name: String,
+    pub transform: Transform,
+    pub visible: bool,
+    geometry: Option<Arc<GeometryType>>,
+    material: Option<MaterialType>,
+    children: Vec<Node>,
+}
+
+impl Node {
+    /// Create a visible node without geometry, to group other nodes.
+    pub fn group(name: &str, transform: &Transform) -> Self {
+        Self {
+            name: name.to_string(),
+            transform: *transform,
+            visible: true,
+            geometry: None,
+            material: None,
+            children: Vec::new(),
+        }
+    }
+
+    /// Create a visible node placing the given geometry.
+    pub fn leaf(
+        name: &str,
+        transform: &Transform,
+        geometry: Arc<GeometryType>,
+        material: Option<MaterialType>,
+    ) -> Self {
+        Self {
+            geometry: Some(geometry),
+            material,
+            ..Self::group(name, transform)
+        }
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_geometry(&self) -> Option<&Arc<GeometryType>> {
+        self.geometry.as_ref()
+    }
+
+    pub fn get_material_override(&self) -> Option<&MaterialType> {
+        self.material.as_ref()
+    }
+
+    pub fn set_material_override(&mut self, material: Option<MaterialType>) {
+        self.material = material;
+    }
+
+    pub fn get_children(&self) -> &[Node] {
+        &self.children
+    }
+
+    pub fn add_child(&mut self, child: Node) {
+        self.children.push(child);
+    }
+
+    /// Find the first node of the given name in the subtree, the node itself included, depth first.
+    pub fn find_by_name(&self, name: &str) -> Option<&Node> {
+        match self.name == name {
+            true => Some(self),
+            false => self.children.iter().find_map(|x| x.find_by_name(name)),
+        }
+    }
+
+    pub fn find_by_name_mut(&mut self, name: &str) -> Option<&mut Node> {
+        match self.name == name {
+            true => Some(self),
+            false => self
+                .children
+                .iter_mut()
+                .find_map(|x| x.find_by_name_mut(name)),
+        }
+    }
+
+    /// Append instances of the visible geometries of the subtree, placed in the parent's space.
+    fn flatten(
+        &self,
+        parent: &Affine3A,
+        material: Option<&MaterialType>,
+        renderables: &mut Vec<GeometryType>,
+    ) {
+        if !self.visible {
+            return;
+        }
+        let material = self.material.as_ref().or(material);
+        if let Some(geometry) = &self.geometry {
+            let mut instance = Instance::new(geometry.clone(), &self.transform, material.cloned());
+            instance.set_parent(parent);
+            renderables.push(GeometryType::Instance(instance));
+        }
+        let world = *parent * self.transform.to_affine();
+        self.children
+            .iter()
+            .for_each(|x| x.flatten(&world, material, renderables));
+    }
+}
+
+// #####################################
+
+/// Hierarchy of nodes, whose transforms compose from the roots down to the leaves.
+/// Nodes are found by name, or by path: the names from a root down to the node, separated by slashes.
+#[derive(Default)]
+pub struct SceneGraph {
+    pub nodes: Vec<Node>,
+}
+
+impl SceneGraph {
+    pub const fn new() -> Self {
+        Self { nodes: Vec::new() }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Find the node at the given path, e.g. `car/wheel_front`.
+    pub fn find(&self, path: &str) -> Option<&Node> {
+        let mut names = path.split('/');
+        let root = names.next()?;
+        names.try_fold(self.nodes.iter().find(|x| x.name == root)?, |node, name| {
+            node.children.iter().find(|x| x.name == name)
+        })
+    }
+
+    pub fn find_mut(&mut self, path: &str) -> Option<&mut Node> {
+        let mut names = path.split('/');
+        let root = names.next()?;
+        names.try_fold(
+            self.nodes.iter_mut().find(|x| x.name == root)?,
+            |node, name| node.children.iter_mut().find(|x| x.name == name),
+        )
+    }
+
+    /// Find the first node of the given name, depth first.
+    pub fn find_by_name(&self, name: &str) -> Option<&Node> {
+        self.nodes.iter().find_map(|x| x.find_by_name(name))
+    }
+
+    pub fn find_by_name_mut(&mut self, name: &str) -> Option<&mut Node> {
+        self.nodes.iter_mut().find_map(|x| x.find_by_name_mut(name))
+    }
+
+    /// Get an instance of every visible geometry, placed in the world's space by its node's and ancestors'
+    /// transforms, in depth first order.
+    pub fn flatten(&self) -> Vec<GeometryType> {
+        let mut renderables = Vec::new();
+        self.nodes
+            .iter()
+            .for_each(|x| x.flatten(&Affine3A::IDENTITY, None, &mut renderables));
+        renderables
+    }
+}
+
+// #####################################
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use glam::{Vec3A, Vec4};
+
+    use crate::entity::{
+        actor::ActorTrait,
+        geometry::{GeometryType, sphere::Sphere, transform::Transform},
+        rendering::material::{ColorMaterial, DiffuseMaterial, MaterialBound, MaterialType},
+        scene_graph::{Node, SceneGraph},
+    };
+
+    fn translation(x: f32) -> Transform {
+        Transform {
+            translation: Vec3A::new(x, 0., 0.),
+            ..Default::default()
+        }
+    }
+
+    /// A car made of a body and two wheels sharing the same geometry.
+    fn car() -> SceneGraph {
+        let wheel = Arc::new(GeometryType::Sphere(
+            Sphere::new(
+                &Vec3A::ZERO,
+                1.,
+                &MaterialType::Color(ColorMaterial::new(Vec4::ONE)),
+            )
+            .unwrap(),
+        ));
+        let mut car = Node::group(
+            "car",
+            &Transform {
+                translation: Vec3A::new(0., 0., 10.),
+                rotation: Vec3A::new(0., 0., 90.),
+                scale: Vec3A::splat(2.),
+            },
+        );
+        car.add_child(Node::leaf("front", &translation(1.), wheel.clone(), None));
+        car.add_child(Node::leaf("back", &translation(-1.), wheel, None));
+        SceneGraph { nodes: vec![car] }
+    }
+
+    #[test]
+    fn test_success_flatten() {
+        let mut graph = car();
+        let renderables = graph.flatten();
+        assert_eq!(renderables.len(), 2);
+        // Scaled twice then rotated by 90 degrees around Z, the wheel's offset along X lies along Y.
+        assert!((renderables[0].get_position() - Vec3A::new(0., 2., 10.)).length() < 1e-5);
+        assert!((renderables[1].get_position() - Vec3A::new(0., -2., 10.)).length() < 1e-5);
+
+        // Moving the group moves every wheel.
+        let car = graph.find_mut("car").unwrap();
+        car.transform.translation.z = 20.;
+        car.set_material_override(Some(MaterialType::Diffuse(DiffuseMaterial::new(1.))));
+        let renderables = graph.flatten();
+        assert!((renderables[1].get_position() - Vec3A::new(0., -2., 20.)).length() < 1e-5);
+        assert_eq!(renderables[1].get_material().get_name(), "diffuse");
+    }
+
+    #[test]
+    fn test_success_find() {
+        let mut graph = car();
+        assert_eq!(graph.find("car/back").unwrap().get_name(), "back");
+        assert_eq!(
+            graph.find_by_name("front").unwrap().transform,
+            translation(1.)
+        );
+
+        graph.find_by_name_mut("front").unwrap().visible = false;
+        assert_eq!(graph.flatten().len(), 1);
+        graph.find_mut("car").unwrap().visible = false;
+        assert!(graph.flatten().is_empty());
+    }
+
+    #[test]
+    fn test_failure_find() {
+        let graph = car();
+        assert!(graph.find("back").is_none());
+        assert!(graph.find("car/back/nothing").is_none());
+        assert!(graph.find("").is_none());
+        assert!(graph.find_by_name("truck").is_none());
+    }
+}
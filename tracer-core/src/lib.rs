@@ -1,2 +1,4 @@
+pub mod animation;
 pub mod entity;
 pub mod rendering;
+pub mod scene_file;
@@ -0,0 +1,324 @@
+use glam::Vec4;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::rendering::framebuffer::{AovSample, FrameBuffer};
+
+/// B3 spline coefficients of the à-trous wavelet kernel.
+const KERNEL: [f32; 5] = [1. / 16., 1. / 4., 3. / 8., 1. / 4., 1. / 16.];
+
+/// Highest number of passes, the kernel's step doubling at each of them.
+pub const MAX_ITERATIONS: u32 = 10;
+
+/// Parameters of the edge-aware denoiser.
+/// Lower sigmas preserve more edges, while the strength blends the result with the noisy image.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "DenoiseSettingsData", into = "DenoiseSettingsData")]
+pub struct DenoiseSettings {
+    iterations: u32,
+    strength: f32,
+    color_sigma: f32,
+    normal_sigma: f32,
+    depth_sigma: f32,
+    albedo_sigma: f32,
+}
+
+/// Serialized fields of the denoiser's parameters, checked while being deserialized.
+#[derive(Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct DenoiseSettingsData {
+    iterations: u32,
+    strength: f32,
+    color_sigma: f32,
+    normal_sigma: f32,
+    depth_sigma: f32,
+    albedo_sigma: f32,
+}
+
+impl Default for DenoiseSettingsData {
+    fn default() -> Self {
+        DenoiseSettings::default().into()
+    }
+}
+
+impl TryFrom<DenoiseSettingsData> for DenoiseSettings {
+    type Error = String;
+
+    fn try_from(data: DenoiseSettingsData) -> Result<Self, Self::Error> {
+        DenoiseSettings::new(
+            data.iterations,
+            data.strength,
+            data.color_sigma,
+            data.normal_sigma,
+            data.depth_sigma,
+            data.albedo_sigma,
+        )
+    }
+}
+
+impl From<DenoiseSettings> for DenoiseSettingsData {
+    fn from(settings: DenoiseSettings) -> Self {
+        Self {
+            iterations: settings.iterations,
+            strength: settings.strength,
+            color_sigma: settings.color_sigma,
+            normal_sigma: settings.normal_sigma,
+            depth_sigma: settings.depth_sigma,
+            albedo_sigma: settings.albedo_sigma,
+        }
+    }
+}
+
+impl Default for DenoiseSettings {
+    fn default() -> Self {
+        Self {
+            iterations: 4,
+            strength: 1.,
+            color_sigma: 0.5,
+            normal_sigma: 64.,
+            depth_sigma: 0.1,
+            albedo_sigma: 0.1,
+        }
+    }
+}
+
+impl DenoiseSettings {
+    /// Fail above the highest number of passes, if a sigma is not finite and above zero, or if the strength is not
+    /// between 0 and 1, the weights then being undefined.
+    pub fn new(
+        iterations: u32,
+        strength: f32,
+        color_sigma: f32,
+        normal_sigma: f32,
+        depth_sigma: f32,
+        albedo_sigma: f32,
+    ) -> Result<Self, String> {
+        if iterations > MAX_ITERATIONS {
+            return Err(format!("the iterations must be at most {MAX_ITERATIONS}"));
+        }
+        if !(0. ..=1.).contains(&strength) {
+            return Err("the strength must be between 0 and 1".to_string());
+        }
+        if [color_sigma, normal_sigma, depth_sigma, albedo_sigma]
+            .iter()
+            .any(|x| !x.is_finite() || *x <= 0.)
+        {
+            return Err("the sigmas must be finite and above zero".to_string());
+        }
+        Ok(Self {
+            iterations,
+            strength,
+            color_sigma,
+            normal_sigma,
+            depth_sigma,
+            albedo_sigma,
+        })
+    }
+
+    pub fn get_iterations(&self) -> u32 {
+        self.iterations
+    }
+
+    pub fn get_strength(&self) -> f32 {
+        self.strength
+    }
+
+    pub fn get_color_sigma(&self) -> f32 {
+        self.color_sigma
+    }
+
+    pub fn get_normal_sigma(&self) -> f32 {
+        self.normal_sigma
+    }
+
+    pub fn get_depth_sigma(&self) -> f32 {
+        self.depth_sigma
+    }
+
+    pub fn get_albedo_sigma(&self) -> f32 {
+        self.albedo_sigma
+    }
+}
+
+/// Filter the framebuffer's beauty image with an à-trous wavelet, using the normal, albedo and depth passes to stop at edges.
+/// Return None when the framebuffer does not hold the extra passes.
+pub fn denoise(framebuffer: &FrameBuffer, settings: &DenoiseSettings) -> Option<Vec<Vec4>> {
+    let aovs = framebuffer.get_aovs()?;
+    let width = framebuffer.get_width() as isize;
+    let height = framebuffer.get_height() as isize;
+
+    let noisy: Vec<Vec4> = (0..aovs.len()).map(|x| framebuffer.get_color(x)).collect();
+    let mut filtered = noisy.clone();
+
+    (0..settings.iterations).for_each(|iteration| {
+        let step = 1_isize << iteration;
+        let color_sigma = settings.color_sigma / (1 << iteration) as f32;
+
+        filtered = (0..filtered.len())
+            .into_par_iter()
+            .map(|index| {
+                let (x, y) = (index as isize % width, index as isize / width);
+                let center = &aovs[index];
+                let mut sum = Vec4::ZERO;
+                let mut weight_sum = 0.;
+
+                KERNEL.iter().enumerate().for_each(|(j, ky)| {
+                    KERNEL.iter().enumerate().for_each(|(i, kx)| {
+                        let qx = x + (i as isize - 2) * step;
+                        let qy = y + (j as isize - 2) * step;
+                        if !(0..width).contains(&qx) || !(0..height).contains(&qy) {
+                            return;
+                        }
+
+                        let neighbour = (qy * width + qx) as usize;
+                        let weight = kx
+                            * ky
+                            * edge_weight(center, &aovs[neighbour], settings, step)
+                            * (-(filtered[index] - filtered[neighbour]).length_squared()
+                                / (color_sigma * color_sigma).max(f32::EPSILON))
+                            .exp();
+                        sum += filtered[neighbour] * weight;
+                        weight_sum += weight;
+                    })
+                });
+
+                match weight_sum {
+                    x if x > f32::EPSILON => sum / weight_sum,
+                    _ => filtered[index],
+                }
+            })
+            .collect();
+    });
+
+    Some(
+        noisy
+            .iter()
+            .zip(filtered.iter())
+            .map(|(noisy, filtered)| noisy.lerp(*filtered, settings.strength))
+            .collect(),
+    )
+}
+
+/// Weight given to a neighbour pixel by the geometric passes, dropping to zero across object silhouettes.
+fn edge_weight(
+    center: &AovSample,
+    neighbour: &AovSample,
+    settings: &DenoiseSettings,
+    step: isize,
+) -> f32 {
+    match (center.object_id, neighbour.object_id) {
+        (None, None) => 1.,
+        (None, _) | (_, None) => 0.,
+        _ => {
+            let normal_weight = center
+                .normal
+                .dot(neighbour.normal)
+                .max(0.)
+                .powf(settings.normal_sigma);
+            let depth_weight = (-(center.depth - neighbour.depth).abs()
+                / (settings.depth_sigma * center.depth.abs() * step as f32).max(f32::EPSILON))
+            .exp();
+            let albedo_weight = (-(center.albedo - neighbour.albedo).length_squared()
+                / (settings.albedo_sigma * settings.albedo_sigma).max(f32::EPSILON))
+            .exp();
+            normal_weight * depth_weight * albedo_weight
+        }
+    }
+}
+
+// #####################################
+
+#[cfg(test)]
+mod tests {
+    use glam::{Vec3A, Vec4};
+
+    use crate::rendering::{
+        denoiser::{DenoiseSettings, MAX_ITERATIONS, denoise},
+        framebuffer::{AovSample, FrameBuffer},
+        pixel_filter::PixelFilter,
+        sampler::Sampler,
+    };
+
+    fn noisy_framebuffer() -> FrameBuffer {
+        let framebuffer = FrameBuffer::new(16, 16, true);
+        let mut sampler = Sampler::new(1, 0);
+
+        (0..256).for_each(|index| {
+            let left = index % 16 < 8;
+            let base = match left {
+                true => Vec4::new(0.8, 0.2, 0.2, 1.),
+                false => Vec4::new(0.2, 0.2, 0.8, 1.),
+            };
+            let noise = (sampler.next_f32() - 0.5) * 0.4;
+            framebuffer.splat(
+                (index % 16) as f32 + 0.5,
+                (index / 16) as f32 + 0.5,
+                base + Vec4::new(noise, noise, noise, 0.),
+                &PixelFilter::default(),
+            );
+            framebuffer.set_aovs([(
+                index,
+                AovSample {
+                    depth: 10.,
+                    normal: match left {
+                        true => Vec3A::X,
+                        false => Vec3A::Y,
+                    },
+                    albedo: base,
+                    position: Vec3A::ZERO,
+                    object_id: Some(left as usize),
+                    shadow: 1.,
+                },
+            )]);
+        });
+        framebuffer
+    }
+
+    #[test]
+    fn test_success_denoise() {
+        let framebuffer = noisy_framebuffer();
+        let denoised = denoise(&framebuffer, &DenoiseSettings::default()).unwrap();
+
+        let error = |colors: &Vec<Vec4>| {
+            colors
+                .iter()
+                .enumerate()
+                .map(|(index, color)| (color.x - [0.2, 0.8][(index % 16 < 8) as usize]).abs())
+                .sum::<f32>()
+        };
+        let noisy: Vec<Vec4> = (0..256).map(|x| framebuffer.get_color(x)).collect();
+
+        assert!(error(&denoised) < error(&noisy) * 0.5);
+        assert!(
+            denoised
+                .iter()
+                .enumerate()
+                .all(|(index, color)| match index % 16 < 8 {
+                    true => color.z < 0.5,
+                    false => color.x < 0.5,
+                })
+        );
+    }
+
+    #[test]
+    fn test_failure_denoise_without_aovs() {
+        let framebuffer = FrameBuffer::new(4, 4, false);
+        assert_eq!(denoise(&framebuffer, &DenoiseSettings::default()), None);
+    }
+
+    #[test]
+    fn test_failure_new() {
+        assert!(DenoiseSettings::new(MAX_ITERATIONS, 1., 0.5, 64., 0.1, 0.1).is_ok());
+        assert!(DenoiseSettings::new(MAX_ITERATIONS + 1, 1., 0.5, 64., 0.1, 0.1).is_err());
+        assert!(DenoiseSettings::new(4, 1.5, 0.5, 64., 0.1, 0.1).is_err());
+        assert!(DenoiseSettings::new(4, 1., 0., 64., 0.1, 0.1).is_err());
+        assert!(DenoiseSettings::new(4, 1., 0.5, -64., 0.1, 0.1).is_err());
+        assert!(DenoiseSettings::new(4, 1., 0.5, 64., f32::NAN, 0.1).is_err());
+        assert!(DenoiseSettings::new(4, 1., 0.5, 64., 0.1, f32::INFINITY).is_err());
+
+        assert!(toml::from_str::<DenoiseSettings>("iterations = 64").is_err());
+        let settings = toml::from_str::<DenoiseSettings>("iterations = 2").unwrap();
+        assert_eq!(settings.get_iterations(), 2);
+        assert_eq!(settings.get_strength(), 1.);
+    }
+}
@@ -0,0 +1,382 @@
+use std::sync::{
+    RwLock, RwLockReadGuard,
+    atomic::{AtomicU32, Ordering},
+};
+
+use glam::{Vec3A, Vec4};
+use range2d::Range2D;
+
+use crate::rendering::pixel_filter::PixelFilter;
+
+/// Passes which can be read back from a framebuffer, the beauty image being the regular render.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aov {
+    Beauty,
+    Depth,
+    Normal,
+    Albedo,
+    Position,
+    ObjectId,
+    Shadow,
+}
+
+impl Aov {
+    pub const ALL: [Aov; 7] = [
+        Aov::Beauty,
+        Aov::Depth,
+        Aov::Normal,
+        Aov::Albedo,
+        Aov::Position,
+        Aov::ObjectId,
+        Aov::Shadow,
+    ];
+
+    /// Get the pass following this one, wrapping back to the beauty image.
+    pub fn next(&self) -> Aov {
+        let index = Aov::ALL.iter().position(|x| x == self).unwrap_or(0);
+        Aov::ALL[(index + 1) % Aov::ALL.len()]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Aov::Beauty => "beauty",
+            Aov::Depth => "depth",
+            Aov::Normal => "normal",
+            Aov::Albedo => "albedo",
+            Aov::Position => "position",
+            Aov::ObjectId => "object id",
+            Aov::Shadow => "shadow",
+        }
+    }
+}
+
+// #####################################
+
+/// Values of every extra pass for a single pixel.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AovSample {
+    pub depth: f32,
+    pub normal: Vec3A,
+    pub albedo: Vec4,
+    pub position: Vec3A,
+    pub object_id: Option<usize>,
+    pub shadow: f32,
+}
+
+impl Default for AovSample {
+    /// Values used for pixels whose ray does not hit anything.
+    fn default() -> Self {
+        Self {
+            depth: f32::INFINITY,
+            normal: Vec3A::ZERO,
+            albedo: Vec4::ZERO,
+            position: Vec3A::ZERO,
+            object_id: None,
+            shadow: 1.,
+        }
+    }
+}
+
+// #####################################
+
+/// Weighted sum of every sample splatted into a pixel, stored as float bits so threads can add to it concurrently.
+#[derive(Default)]
+struct PixelAccumulator {
+    channels: [AtomicU32; 5],
+}
+
+impl PixelAccumulator {
+    fn add(&self, color: Vec4, weight: f32) {
+        [color.x, color.y, color.z, color.w, 1.]
+            .iter()
+            .zip(self.channels.iter())
+            .for_each(|(value, channel)| {
+                channel.update(Ordering::Relaxed, Ordering::Relaxed, |bits| {
+                    (f32::from_bits(bits) + value * weight).to_bits()
+                });
+            });
+    }
+
+    fn resolve(&self) -> Vec4 {
+        let [r, g, b, a, weight] = self
+            .channels
+            .each_ref()
+            .map(|x| f32::from_bits(x.load(Ordering::Relaxed)));
+        match weight.abs() {
+            x if x < f32::EPSILON => Vec4::ZERO,
+            _ => Vec4::new(r, g, b, a) / weight,
+        }
+    }
+
+    fn clear(&self) {
+        self.channels
+            .iter()
+            .for_each(|x| x.store(0, Ordering::Relaxed));
+    }
+}
+
+// #####################################
+
+/// Floating point render target, holding the beauty image and optionally the extra passes.
+/// Samples are accumulated across renders until the framebuffer is cleared, allowing progressive rendering.
+/// Every write goes through a shared reference, so rendering threads can fill it while it is being displayed.
+pub struct FrameBuffer {
+    width: usize,
+    height: usize,
+    accumulators: Vec<PixelAccumulator>,
+    sample_count: AtomicU32,
+    aovs: Option<RwLock<Vec<AovSample>>>,
+    denoised: RwLock<Option<Vec<Vec4>>>,
+}
+
+impl FrameBuffer {
+    pub fn new(width: usize, height: usize, with_aovs: bool) -> Self {
+        Self {
+            width,
+            height,
+            accumulators: (0..width * height)
+                .map(|_| PixelAccumulator::default())
+                .collect(),
+            sample_count: AtomicU32::new(0),
+            aovs: with_aovs.then(|| RwLock::new(vec![AovSample::default(); width * height])),
+            denoised: RwLock::new(None),
+        }
+    }
+
+    pub fn get_width(&self) -> usize {
+        self.width
+    }
+
+    pub fn get_height(&self) -> usize {
+        self.height
+    }
+
+    /// Get the number of samples per pixel accumulated since the last clear.
+    pub fn get_sample_count(&self) -> u32 {
+        self.sample_count.load(Ordering::Relaxed)
+    }
+
+    pub fn add_sample_count(&self, samples_per_pixel: u32) {
+        self.sample_count
+            .fetch_add(samples_per_pixel, Ordering::Relaxed);
+    }
+
+    /// Drop every accumulated sample, to restart the progressive rendering.
+    pub fn clear(&mut self) {
+        self.accumulators.iter().for_each(|x| x.clear());
+        self.sample_count = AtomicU32::new(0);
+        self.denoised = RwLock::new(None);
+    }
+
+    /// Weight a sample located at the given continuous pixel coordinates into every pixel covered by the filter.
+    pub fn splat(&self, x: f32, y: f32, color: Vec4, filter: &PixelFilter) {
+        let x_start = (x - 0.5 - filter.get_radius()).ceil().max(0.) as isize;
+        let x_end = (x - 0.5 + filter.get_radius())
+            .floor()
+            .min(self.width as f32 - 1.) as isize;
+        let y_start = (y - 0.5 - filter.get_radius()).ceil().max(0.) as isize;
+        let y_end = (y - 0.5 + filter.get_radius())
+            .floor()
+            .min(self.height as f32 - 1.) as isize;
+
+        Range2D::new(y_start..y_end + 1, x_start..x_end + 1).for_each(|(py, px)| {
+            let weight = filter.evaluate(px as f32 + 0.5 - x, py as f32 + 0.5 - y);
+            if weight != 0. {
+                self.accumulators[py as usize * self.width + px as usize].add(color, weight);
+            }
+        });
+    }
+
+    /// Get a pixel's reconstructed color.
+    pub fn get_color(&self, index: usize) -> Vec4 {
+        self.accumulators[index].resolve()
+    }
+
+    pub fn has_aovs(&self) -> bool {
+        self.aovs.is_some()
+    }
+
+    pub fn get_aov(&self, index: usize) -> Option<AovSample> {
+        self.get_aovs().map(|aovs| aovs[index])
+    }
+
+    /// Lock the extra passes for reading, if the framebuffer holds them.
+    pub fn get_aovs(&self) -> Option<RwLockReadGuard<'_, Vec<AovSample>>> {
+        self.aovs.as_ref().map(|aovs| aovs.read().unwrap())
+    }
+
+    /// Write the extra passes of a set of pixels, given by index.
+    pub fn set_aovs(&self, samples: impl IntoIterator<Item = (usize, AovSample)>) {
+        if let Some(aovs) = &self.aovs {
+            let mut aovs = aovs.write().unwrap();
+            samples
+                .into_iter()
+                .for_each(|(index, sample)| aovs[index] = sample);
+        }
+    }
+
+    pub fn get_denoised(&self) -> RwLockReadGuard<'_, Option<Vec<Vec4>>> {
+        self.denoised.read().unwrap()
+    }
+
+    pub fn set_denoised(&self, denoised: Option<Vec<Vec4>>) {
+        *self.denoised.write().unwrap() = denoised;
+    }
+
+    /// Convert the requested pass to 8 bits RGBA values, normalizing unbounded passes over the frame.
+    /// The beauty image is read from the denoised colors when available.
+    /// Extra passes are left black when the framebuffer was created without them.
+    pub fn write_rgba8(&self, aov: Aov, buffer: &mut [u8]) {
+        let aovs = self.get_aovs();
+        let colors: Vec<Vec4> = match (aov, aovs.as_deref()) {
+            (Aov::Beauty, _) => match self.get_denoised().as_ref() {
+                Some(denoised) => denoised.clone(),
+                None => self.accumulators.iter().map(|x| x.resolve()).collect(),
+            },
+            (_, None) => vec![Vec4::new(0., 0., 0., 1.); self.accumulators.len()],
+            (Aov::Depth, Some(aovs)) => {
+                let (near, far) = aovs
+                    .iter()
+                    .map(|x| x.depth)
+                    .filter(|x| x.is_finite())
+                    .fold((f32::INFINITY, f32::NEG_INFINITY), |a, b| {
+                        (a.0.min(b), a.1.max(b))
+                    });
+                let range = (far - near).max(f32::EPSILON);
+                aovs.iter()
+                    .map(|x| match x.depth.is_finite() {
+                        true => Vec3A::splat(1. - (x.depth - near) / range).extend(1.),
+                        false => Vec4::new(0., 0., 0., 1.),
+                    })
+                    .collect()
+            }
+            (Aov::Normal, Some(aovs)) => aovs
+                .iter()
+                .map(|x| match x.object_id {
+                    Some(_) => (x.normal * 0.5 + 0.5).extend(1.),
+                    None => Vec4::new(0., 0., 0., 1.),
+                })
+                .collect(),
+            (Aov::Albedo, Some(aovs)) => aovs.iter().map(|x| x.albedo).collect(),
+            (Aov::Position, Some(aovs)) => {
+                let (min, max) = aovs
+                    .iter()
+                    .filter(|x| x.object_id.is_some())
+                    .fold((Vec3A::INFINITY, Vec3A::NEG_INFINITY), |a, b| {
+                        (a.0.min(b.position), a.1.max(b.position))
+                    });
+                let range = (max - min).max(Vec3A::splat(f32::EPSILON));
+                aovs.iter()
+                    .map(|x| match x.object_id {
+                        Some(_) => ((x.position - min) / range).extend(1.),
+                        None => Vec4::new(0., 0., 0., 1.),
+                    })
+                    .collect()
+            }
+            (Aov::ObjectId, Some(aovs)) => aovs
+                .iter()
+                .map(|x| match x.object_id {
+                    Some(id) => {
+                        let hash = (id as u32 + 1).wrapping_mul(2654435761);
+                        Vec4::new(
+                            (hash >> 24) as f32 / 255.,
+                            ((hash >> 16) & 0xff) as f32 / 255.,
+                            ((hash >> 8) & 0xff) as f32 / 255.,
+                            1.,
+                        )
+                    }
+                    None => Vec4::new(0., 0., 0., 1.),
+                })
+                .collect(),
+            (Aov::Shadow, Some(aovs)) => aovs
+                .iter()
+                .map(|x| Vec3A::splat(x.shadow).extend(1.))
+                .collect(),
+        };
+
+        colors.iter().enumerate().for_each(|it| {
+            buffer[it.0 * 4..(it.0 * 4) + 4].copy_from_slice(&[
+                (it.1.x * 255.) as u8,
+                (it.1.y * 255.) as u8,
+                (it.1.z * 255.) as u8,
+                (it.1.w * 255.) as u8,
+            ]);
+        });
+    }
+}
+
+// #####################################
+
+#[cfg(test)]
+mod tests {
+    use glam::{Vec3A, Vec4};
+
+    use crate::rendering::{
+        framebuffer::{Aov, AovSample, FrameBuffer},
+        pixel_filter::{FilterKind, PixelFilter},
+    };
+
+    #[test]
+    fn test_success_aov_cycle() {
+        let mut aov = Aov::Beauty;
+        Aov::ALL.iter().skip(1).for_each(|x| {
+            aov = aov.next();
+            assert_eq!(aov, *x);
+        });
+        assert_eq!(aov.next(), Aov::Beauty);
+    }
+
+    #[test]
+    fn test_success_write_rgba8() {
+        let framebuffer = FrameBuffer::new(2, 1, true);
+        framebuffer.splat(0.5, 0.5, Vec4::new(1., 0., 0., 1.), &PixelFilter::default());
+        framebuffer.set_aovs([(
+            0,
+            AovSample {
+                depth: 2.,
+                normal: Vec3A::new(0., 1., 0.),
+                albedo: Vec4::ONE,
+                position: Vec3A::ZERO,
+                object_id: Some(0),
+                shadow: 0.,
+            },
+        )]);
+
+        let mut buffer = [0_u8; 8];
+        framebuffer.write_rgba8(Aov::Beauty, &mut buffer);
+        assert_eq!(buffer[0..4], [255, 0, 0, 255]);
+
+        framebuffer.write_rgba8(Aov::Normal, &mut buffer);
+        assert_eq!(buffer[0..4], [127, 255, 127, 255]);
+        assert_eq!(buffer[4..8], [0, 0, 0, 255]);
+
+        framebuffer.write_rgba8(Aov::Shadow, &mut buffer);
+        assert_eq!(buffer[0..4], [0, 0, 0, 255]);
+        assert_eq!(buffer[4..8], [255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn test_success_splat() {
+        let mut framebuffer = FrameBuffer::new(3, 3, false);
+        let filter = PixelFilter::new(FilterKind::Tent, 1.5).unwrap();
+        framebuffer.splat(1.5, 1.5, Vec4::ONE, &filter);
+        framebuffer.splat(1.2, 1.7, Vec4::new(0., 0., 0., 1.), &filter);
+
+        (0..9).for_each(|x| {
+            let color = framebuffer.get_color(x);
+            assert!(color.x > 0. && color.x < 1.);
+            assert!((color.w - 1.).abs() < 1e-5);
+        });
+
+        framebuffer.clear();
+        assert_eq!(framebuffer.get_color(4), Vec4::ZERO);
+    }
+
+    #[test]
+    fn test_failure_write_rgba8_without_aovs() {
+        let framebuffer = FrameBuffer::new(1, 1, false);
+        let mut buffer = [255_u8; 4];
+        framebuffer.write_rgba8(Aov::Depth, &mut buffer);
+        assert_eq!(buffer, [0, 0, 0, 255]);
+    }
+}
@@ -0,0 +1,191 @@
+use std::f32::consts::PI;
+
+use glam::{Vec3A, Vec4};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    entity::{
+        actor::DirectionalActorTrait,
+        geometry::{
+            Geometry,
+            ray::{Ray, RayType},
+        },
+        rendering::light::Light,
+        scene::Scene,
+    },
+    rendering::sampler::Sampler,
+};
+
+/// Strategy used to turn a camera ray into a color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Integrator {
+    /// Shade the hit surfaces with their materials, following the reflections up to the scene's max depth.
+    #[default]
+    Whitted,
+    /// Gray level telling how much of the hemisphere above the hit surfaces is left open.
+    AmbientOcclusion,
+    /// World space normals of the hit surfaces, remapped to colors.
+    Normals,
+}
+
+impl Integrator {
+    /// Compute the color carried by a camera ray, falling back to the scene's ambient color if nothing is hit.
+    /// Ambient occlusion casts a single occlusion ray per call, only looking for occluders closer than the given
+    /// distance.
+    pub fn radiance(
+        &self,
+        scene: &Scene,
+        light: &Light,
+        ray: &Ray,
+        sampler: &mut Sampler,
+        occlusion_distance: f32,
+    ) -> Vec4 {
+        let Some(hit) = scene.closest_hit(ray, &RayType::Camera) else {
+            return scene.ambient;
+        };
+
+        match self {
+            Integrator::Whitted => {
+                let (light_ray, see_light) = scene.light_visibility(&hit, light);
+                scene.shade(ray, light, &hit, &light_ray, see_light, &0)
+            }
+            Integrator::AmbientOcclusion => {
+                // Planes' normals point away from their visible side, so the hemisphere faces the incoming ray.
+                let normal = match hit.normal.dot(ray.get_direction()) {
+                    x if x > 0. => -hit.normal,
+                    _ => hit.normal,
+                };
+                let direction = cosine_hemisphere(normal, sampler.next_2d());
+                let occlusion_ray =
+                    Ray::new(&hit.point, &(direction * occlusion_distance)).with_time(hit.time);
+                let occluded = scene
+                    .renderables
+                    .iter()
+                    .enumerate()
+                    .filter(|x| x.0 != hit.renderable_index)
+                    .any(|x| x.1.intersect(&occlusion_ray, &RayType::Light).is_some());
+
+                match occluded {
+                    true => Vec4::new(0., 0., 0., 1.),
+                    false => Vec4::ONE,
+                }
+            }
+            Integrator::Normals => (hit.normal * 0.5 + 0.5).extend(1.),
+        }
+    }
+}
+
+/// Map a uniform 2D sample to a direction around the given normal, with a density proportional to its cosine.
+fn cosine_hemisphere(normal: Vec3A, (u, v): (f32, f32)) -> Vec3A {
+    let (tangent, bitangent) = normal.any_orthonormal_pair();
+    let radius = u.sqrt();
+    let angle = 2. * PI * v;
+
+    tangent * radius * angle.cos()
+        + bitangent * radius * angle.sin()
+        + normal * (1. - u).max(0.).sqrt()
+}
+
+// #####################################
+
+#[cfg(test)]
+mod tests {
+    use glam::{Vec3A, Vec4};
+
+    use crate::{
+        entity::{
+            geometry::{GeometryType, plane::Plane, ray::Ray, sphere::Sphere},
+            rendering::{
+                light::Light,
+                material::{ColorMaterial, MaterialType},
+            },
+            scene::Scene,
+        },
+        rendering::{
+            integrator::{Integrator, cosine_hemisphere},
+            sampler::Sampler,
+        },
+    };
+
+    fn scene() -> (Scene, Light) {
+        let material = MaterialType::Color(ColorMaterial::new(Vec4::new(0., 0.5, 0., 0.)));
+        let mut scene = Scene::new(&Vec4::new(0.1, 0.1, 0.1, 1.));
+        scene.renderables.push(GeometryType::Plane(
+            Plane::new(&Vec3A::ZERO, &Vec3A::new(0., -1., 0.), &material).unwrap(),
+        ));
+        scene.renderables.push(GeometryType::Sphere(
+            Sphere::new(&Vec3A::new(0., 3., 0.), 4., &material).unwrap(),
+        ));
+        let light = Light::new(
+            &Vec3A::new(0., 100., 0.),
+            &Vec3A::new(0., -1., 0.),
+            1.,
+            &MaterialType::Color(ColorMaterial::new(Vec4::ONE)),
+        )
+        .unwrap();
+        (scene, light)
+    }
+
+    #[test]
+    fn test_success_radiance() {
+        let (scene, light) = scene();
+        let mut sampler = Sampler::new(0, 0);
+        let ray = Ray::new(&Vec3A::new(0., 20., 0.), &Vec3A::new(0., -1., 0.));
+
+        assert_eq!(
+            Integrator::Normals.radiance(&scene, &light, &ray, &mut sampler, 10.),
+            Vec4::new(0.5, 1., 0.5, 1.)
+        );
+        assert_eq!(
+            Integrator::Whitted.radiance(&scene, &light, &ray, &mut sampler, 10.),
+            Vec4::new(0.1, 0.6, 0.1, 1.)
+        );
+        assert_eq!(
+            Integrator::AmbientOcclusion.radiance(&scene, &light, &ray, &mut sampler, 10.),
+            Vec4::ONE
+        );
+
+        // The ground is inside the sphere, which occludes every direction.
+        let ray = Ray::new(&Vec3A::new(0., 1., 0.), &Vec3A::new(0., -0.5, 0.));
+        assert_eq!(
+            Integrator::AmbientOcclusion.radiance(&scene, &light, &ray, &mut sampler, 100.),
+            Vec4::new(0., 0., 0., 1.)
+        );
+        assert_eq!(
+            Integrator::Normals.radiance(&scene, &light, &ray, &mut sampler, 100.),
+            Vec4::new(0.5, 0., 0.5, 1.)
+        );
+    }
+
+    #[test]
+    fn test_failure_radiance_miss() {
+        let (scene, light) = scene();
+        let mut sampler = Sampler::new(0, 0);
+        let ray = Ray::new(&Vec3A::new(50., 20., 0.), &Vec3A::new(0., 1., 0.));
+
+        [
+            Integrator::Whitted,
+            Integrator::AmbientOcclusion,
+            Integrator::Normals,
+        ]
+        .iter()
+        .for_each(|integrator| {
+            assert_eq!(
+                integrator.radiance(&scene, &light, &ray, &mut sampler, 100.),
+                scene.ambient
+            );
+        });
+    }
+
+    #[test]
+    fn test_success_cosine_hemisphere() {
+        let mut sampler = Sampler::new(1, 2);
+        let normal = Vec3A::new(1., 2., 3.).normalize();
+        (0..100).for_each(|_| {
+            let direction = cosine_hemisphere(normal, sampler.next_2d());
+            assert!((direction.length() - 1.).abs() < 1e-4);
+            assert!(direction.dot(normal) >= 0.);
+        });
+    }
+}
@@ -1,2 +1,9 @@
-pub mod image_filter;
+pub mod denoiser;
+pub mod framebuffer;
+pub mod integrator;
+pub mod pixel_filter;
+pub mod progress;
 pub mod ray_emitter;
+pub mod renderer;
+pub mod sampler;
+pub mod tile;
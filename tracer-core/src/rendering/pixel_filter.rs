@@ -0,0 +1,177 @@
+use std::f32::consts::PI;
+
+use serde::{Deserialize, Serialize};
+
+/// Reconstruction kernels available to weight a sample into the pixels surrounding it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterKind {
+    Box,
+    Tent,
+    Gaussian,
+    MitchellNetravali,
+    Lanczos,
+    BlackmanHarris,
+}
+
+/// Separable pixel reconstruction filter, only covering samples closer than its radius (in pixels).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "PixelFilterData", into = "PixelFilterData")]
+pub struct PixelFilter {
+    kind: FilterKind,
+    radius: f32,
+}
+
+/// Serialized fields of a pixel filter, checked while being deserialized.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PixelFilterData {
+    kind: FilterKind,
+    radius: f32,
+}
+
+impl TryFrom<PixelFilterData> for PixelFilter {
+    type Error = String;
+
+    fn try_from(data: PixelFilterData) -> Result<Self, Self::Error> {
+        PixelFilter::new(data.kind, data.radius)
+    }
+}
+
+impl From<PixelFilter> for PixelFilterData {
+    fn from(filter: PixelFilter) -> Self {
+        Self {
+            kind: filter.kind,
+            radius: filter.radius,
+        }
+    }
+}
+
+impl Default for PixelFilter {
+    fn default() -> Self {
+        Self {
+            kind: FilterKind::Box,
+            radius: 0.5,
+        }
+    }
+}
+
+impl PixelFilter {
+    /// Fail if the radius is not finite and above zero, the kernels then being undefined.
+    pub fn new(kind: FilterKind, radius: f32) -> Result<Self, String> {
+        if !radius.is_finite() || radius <= 0. {
+            return Err("the filter's radius must be finite and above zero".to_string());
+        }
+        Ok(Self { kind, radius })
+    }
+
+    pub fn get_kind(&self) -> FilterKind {
+        self.kind
+    }
+
+    pub fn get_radius(&self) -> f32 {
+        self.radius
+    }
+
+    /// Get the weight of a sample located at the given offset from a pixel's center.
+    pub fn evaluate(&self, dx: f32, dy: f32) -> f32 {
+        self.evaluate_1d(dx) * self.evaluate_1d(dy)
+    }
+
+    fn evaluate_1d(&self, x: f32) -> f32 {
+        let x = x.abs();
+        if x > self.radius {
+            return 0.;
+        }
+
+        match self.kind {
+            FilterKind::Box => 1.,
+            FilterKind::Tent => 1. - x / self.radius,
+            FilterKind::Gaussian => {
+                const ALPHA: f32 = 2.;
+                f32::max(
+                    0.,
+                    (-ALPHA * x * x).exp() - (-ALPHA * self.radius * self.radius).exp(),
+                )
+            }
+            FilterKind::MitchellNetravali => {
+                const B: f32 = 1. / 3.;
+                const C: f32 = 1. / 3.;
+                let x = 2. * x / self.radius;
+                match x {
+                    x if x < 1. => {
+                        ((12. - 9. * B - 6. * C) * x * x * x
+                            + (-18. + 12. * B + 6. * C) * x * x
+                            + (6. - 2. * B))
+                            / 6.
+                    }
+                    x => {
+                        ((-B - 6. * C) * x * x * x
+                            + (6. * B + 30. * C) * x * x
+                            + (-12. * B - 48. * C) * x
+                            + (8. * B + 24. * C))
+                            / 6.
+                    }
+                }
+            }
+            FilterKind::Lanczos => sinc(x) * sinc(x / self.radius),
+            FilterKind::BlackmanHarris => {
+                let n = 2. * PI * (x + self.radius) / (2. * self.radius);
+                0.35875 - 0.48829 * n.cos() + 0.14128 * (2. * n).cos() - 0.01168 * (3. * n).cos()
+            }
+        }
+    }
+}
+
+fn sinc(x: f32) -> f32 {
+    match x.abs() {
+        x if x < 1e-5 => 1.,
+        x => (PI * x).sin() / (PI * x),
+    }
+}
+
+// #####################################
+
+#[cfg(test)]
+mod tests {
+    use crate::rendering::pixel_filter::{FilterKind, PixelFilter};
+
+    const KINDS: [FilterKind; 6] = [
+        FilterKind::Box,
+        FilterKind::Tent,
+        FilterKind::Gaussian,
+        FilterKind::MitchellNetravali,
+        FilterKind::Lanczos,
+        FilterKind::BlackmanHarris,
+    ];
+
+    #[test]
+    fn test_success_evaluate() {
+        KINDS.iter().for_each(|kind| {
+            let filter = PixelFilter::new(*kind, 2.).unwrap();
+            let center = filter.evaluate(0., 0.);
+            assert!(center > 0.);
+            assert!(filter.evaluate(0.5, 0.) <= center);
+            assert!((filter.evaluate(0.5, -1.) - filter.evaluate(-0.5, 1.)).abs() < 1e-6);
+        });
+    }
+
+    #[test]
+    fn test_failure_evaluate_outside_radius() {
+        KINDS.iter().for_each(|kind| {
+            let filter = PixelFilter::new(*kind, 1.5).unwrap();
+            assert_eq!(filter.evaluate(1.6, 0.), 0.);
+            assert_eq!(filter.evaluate(0., -2.), 0.);
+        });
+    }
+
+    #[test]
+    fn test_failure_new() {
+        KINDS.iter().for_each(|kind| {
+            assert!(PixelFilter::new(*kind, 0.).is_err());
+            assert!(PixelFilter::new(*kind, -1.).is_err());
+            assert!(PixelFilter::new(*kind, f32::NAN).is_err());
+            assert!(PixelFilter::new(*kind, f32::INFINITY).is_err());
+        });
+    }
+}
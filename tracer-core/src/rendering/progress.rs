@@ -0,0 +1,76 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+
+/// Snapshot of a render's advancement, reported each time a tile is completed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RenderProgress {
+    pub completed_tiles: usize,
+    pub total_tiles: usize,
+    pub samples_per_pixel: u32,
+    pub elapsed: Duration,
+    pub eta: Option<Duration>,
+}
+
+impl RenderProgress {
+    pub fn new(
+        completed_tiles: usize,
+        total_tiles: usize,
+        samples_per_pixel: u32,
+        elapsed: Duration,
+    ) -> Self {
+        Self {
+            completed_tiles,
+            total_tiles,
+            samples_per_pixel,
+            elapsed,
+            eta: (completed_tiles > 0).then(|| {
+                elapsed.mul_f64((total_tiles - completed_tiles) as f64 / completed_tiles as f64)
+            }),
+        }
+    }
+
+    /// Get the completed part of the render, between 0 and 1.
+    pub fn get_fraction(&self) -> f32 {
+        match self.total_tiles {
+            0 => 1.,
+            total => self.completed_tiles as f32 / total as f32,
+        }
+    }
+}
+
+// #####################################
+
+/// Outcome of a render which may have been stopped before completion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderStatus {
+    Completed,
+    Cancelled,
+}
+
+// #####################################
+
+/// Flag shared between a render and whoever may want to stop it.
+/// The renderer checks it between tiles, so the tiles in flight are always finished.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
@@ -1,18 +1,34 @@
 use glam::Vec3A;
-use range2d::Range2D;
+use serde::{Deserialize, Serialize};
 
 use crate::entity::{
     actor::{ActorTrait, DirectionalActor, DirectionalActorTrait},
     geometry::ray::Ray,
 };
 
-/// Structure containing and managing an array of rays.
+/// Way the camera rays are spread over the screen.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Projection {
+    /// Parallel rays, starting from a screen plane where each pixel covers the given size.
+    Orthographic { pixel_size: f32 },
+    /// Rays starting from the camera position, spread over the given vertical field of view (in degrees).
+    Perspective { fov: f32 },
+}
+
+impl Default for Projection {
+    fn default() -> Self {
+        Projection::Orthographic { pixel_size: 1. }
+    }
+}
+
+/// Structure building the camera rays crossing the screen, given its resolution.
 /// Each ray is then associated to a pixel of the render target in the renderer class, at the projection stage.
 pub struct RayEmitter {
     dir_actor: DirectionalActor,
     resolution_x: u32,
     resolution_y: u32,
-    pub rays: Vec<Ray>,
+    projection: Projection,
 }
 
 impl std::ops::Deref for RayEmitter {
@@ -23,47 +39,90 @@ impl std::ops::Deref for RayEmitter {
 }
 
 impl RayEmitter {
-    //// Declares and initializes the ray structures, given the screen's resolution.
-    fn calculate_rays(&mut self) {
-        let screen_bottom: Vec3A = Vec3A::new(
-            self.get_position().x - self.resolution_x as f32 / 2.,
-            self.get_position().y - self.resolution_y as f32 / 2.,
-            self.get_position().z,
-        );
-        let screen_top: Vec3A = Vec3A::new(
-            self.get_position().x + self.resolution_x as f32 / 2.,
-            self.get_position().y + self.resolution_y as f32 / 2.,
-            self.get_position().z,
-        );
-        let screen_unit: Vec3A = Vec3A::new(
-            (screen_top.x - screen_bottom.x) / self.resolution_x as f32,
-            (screen_top.y - screen_bottom.y) / self.resolution_y as f32,
-            0.,
-        );
+    pub fn new(position: Vec3A, direction: Vec3A, resolution_x: u32, resolution_y: u32) -> Self {
+        Self {
+            dir_actor: DirectionalActor::new(&position, &direction),
+            resolution_x,
+            resolution_y,
+            projection: Projection::default(),
+        }
+    }
 
-        self.rays = Range2D::new(0..self.resolution_y, 0..self.resolution_x)
-            .map(|i| {
-                Ray::new(
-                    &(screen_bottom
-                        + Vec3A::new(screen_unit.x * i.1 as f32, screen_unit.y * i.0 as f32, 0.)),
-                    &self.get_direction(),
-                )
-            })
-            .rev()
-            .collect();
+    pub fn get_resolution(&self) -> (u32, u32) {
+        (self.resolution_x, self.resolution_y)
     }
-}
 
-impl RayEmitter {
-    pub fn new(position: Vec3A, direction: Vec3A, resolution_x: u32, resolution_y: u32) -> Self {
-        let mut new_emitter = Self {
-            dir_actor: DirectionalActor::new(&position, &direction),
+    pub fn set_resolution(&mut self, resolution_x: u32, resolution_y: u32) {
+        self.resolution_x = resolution_x;
+        self.resolution_y = resolution_y;
+    }
+
+    /// Build a camera seeing the same view with a fraction of the resolution, at least one pixel wide and high.
+    pub fn downscaled(&self, fraction: f32) -> RayEmitter {
+        let resolution_x = ((self.resolution_x as f32 * fraction).round() as u32).max(1);
+        let resolution_y = ((self.resolution_y as f32 * fraction).round() as u32).max(1);
+
+        let mut emitter = RayEmitter::new(
+            self.get_position(),
+            self.get_direction(),
             resolution_x,
             resolution_y,
-            rays: Vec::new(),
+        );
+        emitter.set_projection(match self.projection {
+            Projection::Orthographic { pixel_size } => Projection::Orthographic {
+                pixel_size: pixel_size * self.resolution_y as f32 / resolution_y as f32,
+            },
+            projection => projection,
+        });
+        emitter
+    }
+
+    /// Move the camera to the given position and make it look in the given direction.
+    pub fn set_view(&mut self, position: Vec3A, direction: Vec3A) {
+        self.dir_actor = DirectionalActor::new(&position, &direction);
+    }
+
+    pub fn get_projection(&self) -> Projection {
+        self.projection
+    }
+
+    pub fn set_projection(&mut self, projection: Projection) {
+        self.projection = projection;
+    }
+
+    /// Get the screen's right and up axes along with the viewing axis, all normalized.
+    /// The up axis is kept as close as possible to the world's Y axis.
+    pub fn get_basis(&self) -> (Vec3A, Vec3A, Vec3A) {
+        let forward = self.get_direction().normalize();
+        let world_up = match forward.cross(Vec3A::Y).length_squared() {
+            x if x < 1e-6 => Vec3A::Z,
+            _ => Vec3A::Y,
         };
-        new_emitter.calculate_rays();
-        new_emitter
+        let right = forward.cross(world_up).normalize();
+        (right, right.cross(forward), forward)
+    }
+
+    /// Build the ray crossing the screen at the given continuous pixel coordinates, (0, 0) being the top left corner.
+    pub fn ray_at(&self, x: f32, y: f32) -> Ray {
+        let (right, up, forward) = self.get_basis();
+        let offset_x = x - self.resolution_x as f32 / 2.;
+        let offset_y = self.resolution_y as f32 / 2. - y;
+
+        match self.projection {
+            // The orthographic screen is shifted by half a pixel, as it always was.
+            Projection::Orthographic { pixel_size } => Ray::new(
+                &(self.get_position()
+                    + (right * (offset_x + 0.5) + up * (offset_y - 0.5)) * pixel_size),
+                &forward,
+            ),
+            Projection::Perspective { fov } => {
+                let pixel_size = 2. * (fov.to_radians() / 2.).tan() / self.resolution_y as f32;
+                Ray::new(
+                    &self.get_position(),
+                    &(forward + (right * offset_x + up * offset_y) * pixel_size).normalize(),
+                )
+            }
+        }
     }
 }
 
@@ -71,22 +130,99 @@ impl RayEmitter {
 
 #[cfg(test)]
 mod tests {
-    use crate::{entity::geometry::ray::Ray, rendering::ray_emitter::RayEmitter};
+    use crate::{
+        entity::{
+            actor::{ActorTrait, DirectionalActorTrait},
+            geometry::ray::Ray,
+        },
+        rendering::ray_emitter::{Projection, RayEmitter},
+    };
     use glam::Vec3A;
     use range2d::Range2D;
 
     #[test]
-    fn test_success_calculate_rays() {
+    fn test_success_ray_at() {
         let position = Vec3A::new(0., 0., 0.);
-        let direction = Vec3A::new(1., 0., 0.);
+        let direction = Vec3A::new(0., 0., 1.);
 
         let emitter = RayEmitter::new(position, direction, 2, 2);
 
-        assert_eq!(emitter.rays.len(), 4);
+        Range2D::new(0..2, 0..2).for_each(|(y, x)| {
+            let origin = Vec3A::new(-(x as f32), -(y as f32), 0.);
+            assert_eq!(
+                emitter.ray_at(x as f32 + 0.5, y as f32 + 0.5),
+                Ray::new(&origin, &direction)
+            );
+        });
+    }
 
-        Range2D::new(0..2, 0..2).rev().enumerate().for_each(|it| {
-            let origin = Vec3A::new(-1. + it.1.1 as f32, -1. + it.1.0 as f32, 0.);
-            assert_eq!(emitter.rays[it.0], Ray::new(&origin, &direction));
+    #[test]
+    fn test_success_perspective() {
+        let direction = Vec3A::new(1., 0., 0.);
+        let mut emitter = RayEmitter::new(Vec3A::ZERO, direction, 4, 2);
+        emitter.set_projection(Projection::Perspective { fov: 90. });
+
+        let (right, up, forward) = emitter.get_basis();
+        assert_eq!(forward, direction);
+        assert!(right.dot(up).abs() < 1e-6 && right.dot(forward).abs() < 1e-6);
+        assert_eq!(up, Vec3A::Y);
+
+        assert_eq!(emitter.ray_at(2., 1.).get_direction(), direction);
+        assert!(
+            (emitter
+                .ray_at(2., 0.)
+                .get_direction()
+                .angle_between(direction)
+                .to_degrees()
+                - 45.)
+                .abs()
+                < 1e-3
+        );
+        assert_eq!(emitter.ray_at(0.5, 0.5).get_position(), Vec3A::ZERO);
+    }
+
+    #[test]
+    fn test_failure_basis_looking_up() {
+        let emitter = RayEmitter::new(Vec3A::ZERO, Vec3A::new(0., 2., 0.), 2, 2);
+        let (right, up, forward) = emitter.get_basis();
+
+        assert_eq!(forward, Vec3A::Y);
+        assert!(right.is_normalized() && up.is_normalized());
+        assert!(right.dot(forward).abs() < 1e-6 && up.dot(forward).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_success_downscaled() {
+        let emitter = RayEmitter::new(Vec3A::new(1., 2., 3.), Vec3A::new(0., 0., 1.), 400, 300);
+        let preview = emitter.downscaled(0.25);
+
+        assert_eq!(preview.get_resolution(), (100, 75));
+        assert_eq!(
+            preview.get_projection(),
+            Projection::Orthographic { pixel_size: 4. }
+        );
+        // The screen corners match, up to the orthographic screen's half pixel shift.
+        let corners = [((0., 0.), (0., 0.)), ((100., 75.), (400., 300.))];
+        corners.iter().for_each(|(a, b)| {
+            let distance = preview
+                .ray_at(a.0, a.1)
+                .get_position()
+                .distance(emitter.ray_at(b.0, b.1).get_position());
+            assert!(distance < 4.);
         });
+
+        let mut emitter = emitter;
+        emitter.set_projection(Projection::Perspective { fov: 40. });
+        let preview = emitter.downscaled(0.5);
+        assert_eq!(
+            preview.ray_at(0., 0.).get_direction(),
+            emitter.ray_at(0., 0.).get_direction()
+        );
+    }
+
+    #[test]
+    fn test_failure_downscaled_to_nothing() {
+        let emitter = RayEmitter::new(Vec3A::ZERO, Vec3A::new(0., 0., 1.), 3, 2);
+        assert_eq!(emitter.downscaled(0.01).get_resolution(), (1, 1));
     }
 }
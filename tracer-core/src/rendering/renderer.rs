@@ -1,90 +1,358 @@
+use std::{
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Instant,
+};
+
 use glam::Vec4;
 use rayon::prelude::*;
-use sdl2::{Sdl, VideoSubsystem, event::Event, keyboard::Keycode, video::Window};
+use serde::{Deserialize, Serialize};
 
 use crate::{
     entity::{
-        geometry::RayType,
-        rendering::light::Light,
-        scene::{Renderable, Scene},
+        actor::{ActorTrait, DirectionalActorTrait},
+        geometry::ray::{Ray, RayType},
+        rendering::{light::Light, material::MaterialBound},
+        scene::Scene,
+    },
+    rendering::{
+        denoiser::{self, DenoiseSettings},
+        framebuffer::{AovSample, FrameBuffer},
+        integrator::Integrator,
+        pixel_filter::PixelFilter,
+        progress::{CancellationToken, RenderProgress, RenderStatus},
+        ray_emitter::RayEmitter,
+        sampler::Sampler,
+        tile::{TileOrder, generate_tiles},
     },
-    rendering::{image_filter, ray_emitter::RayEmitter},
 };
 
-/// Structure in charge of managing the window and the window's render target.
-pub struct Renderer<'a> {
-    window: Window,
-    sdl_context: &'a Sdl,
-    w: usize,
+/// Time interval the camera rays are cast during, in seconds relative to the frame's time.
+/// Moving actors are blurred along their motion while the shutter is open.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Shutter {
+    pub open: f32,
+    pub close: f32,
 }
 
-impl<'a> Renderer<'a> {
-    pub fn new(
-        video_subsystem: &'a VideoSubsystem,
-        sdl_context: &'a Sdl,
-        width: u32,
-        height: u32,
-    ) -> Self {
-        let window = video_subsystem
-            .window("raytracer", width, height)
-            .position_centered()
-            .build()
-            .unwrap();
-
-        let uswidth = width as usize;
+impl Shutter {
+    pub fn is_instant(&self) -> bool {
+        self.close <= self.open
+    }
 
+    /// Get the time at the given fraction of the interval.
+    pub fn sample(&self, u: f32) -> f32 {
+        self.open + (self.close - self.open) * u
+    }
+}
+
+/// Parameters driving how the pixels are sampled, reconstructed and scheduled across threads.
+/// The denoiser is only run when set, and needs a framebuffer holding the extra passes.
+/// The occlusion distance is only used by the ambient occlusion integrator.
+#[derive(Debug, Clone)]
+pub struct RenderSettings {
+    pub integrator: Integrator,
+    pub occlusion_distance: f32,
+    pub samples_per_pixel: u32,
+    pub filter: PixelFilter,
+    pub seed: u64,
+    pub denoise: Option<DenoiseSettings>,
+    pub tile_size: usize,
+    pub tile_order: TileOrder,
+    pub shutter: Shutter,
+}
+
+impl Default for RenderSettings {
+    fn default() -> Self {
         Self {
-            window,
-            sdl_context,
-            w: uswidth,
+            integrator: Integrator::Whitted,
+            occlusion_distance: 100.,
+            samples_per_pixel: 1,
+            filter: PixelFilter::default(),
+            seed: 0,
+            denoise: None,
+            tile_size: 32,
+            tile_order: TileOrder::Spiral,
+            shutter: Shutter::default(),
         }
     }
+}
 
-    /// Draw each object on the window surface, from the furthest to the nearest.
-    pub fn render(&self, ray_emitter: &RayEmitter, scene: &mut Scene, light: &Light) -> bool {
-        let mut event_pump = self.sdl_context.event_pump().unwrap();
-
-        {
-            let mut surface = self.window.surface(&event_pump).unwrap();
-            surface.enable_RLE();
-            surface.with_lock_mut(|buffer: &mut [u8]| {
-                ray_emitter
-                    .rays
-                    .par_iter()
-                    .map(|ray| scene.render(ray, light, &RayType::Camera, &0))
-                    .collect::<Vec<Option<Vec4>>>()
-                    .iter()
-                    .enumerate()
-                    .for_each(|it| match it.1 {
-                        None => {
-                            buffer[it.0 * 4] = 0;
-                            buffer[it.0 * 4 + 1] = 0;
-                            buffer[it.0 * 4 + 2] = 0;
-                            buffer[it.0 * 4 + 3] = 1;
-                        }
-                        Some(result) => {
-                            buffer[it.0 * 4] = result.x as u8;
-                            buffer[it.0 * 4 + 1] = result.y as u8;
-                            buffer[it.0 * 4 + 2] = result.z as u8;
-                            buffer[it.0 * 4 + 3] = result.w as u8;
-                        }
-                    });
-                image_filter::apply_msaa(self.w as isize, buffer, (1, 1));
+/// Structure in charge of computing the color of every ray emitted by the camera into a framebuffer.
+/// It does not depend on any window, so it can be used for headless renders as well.
+#[derive(Default)]
+pub struct Renderer {
+    pub settings: RenderSettings,
+}
+
+impl Renderer {
+    pub fn new(settings: RenderSettings) -> Self {
+        Self { settings }
+    }
+
+    /// Accumulate a new set of samples per pixel into the given framebuffer.
+    /// The extra passes are also rendered, from the pixels centers, if the framebuffer holds them.
+    pub fn render(
+        &self,
+        ray_emitter: &RayEmitter,
+        scene: &Scene,
+        light: &Light,
+        framebuffer: &FrameBuffer,
+    ) {
+        self.render_with_progress(
+            ray_emitter,
+            scene,
+            light,
+            framebuffer,
+            &|_| {},
+            &CancellationToken::new(),
+        );
+    }
+
+    /// Same as render, reporting the progress each time a tile is completed, and stopping early once cancelled.
+    /// The frame is split into tiles, which are picked up in order by the rendering threads and written
+    /// straight into the framebuffer.
+    /// A cancelled render leaves its completed tiles in the framebuffer, but is neither counted in the samples per pixel
+    /// nor denoised.
+    pub fn render_with_progress(
+        &self,
+        ray_emitter: &RayEmitter,
+        scene: &Scene,
+        light: &Light,
+        framebuffer: &FrameBuffer,
+        progress: &(dyn Fn(&RenderProgress) + Sync),
+        cancellation: &CancellationToken,
+    ) -> RenderStatus {
+        let start = Instant::now();
+        let width = framebuffer.get_width();
+        let seed = self
+            .settings
+            .seed
+            .wrapping_add((framebuffer.get_sample_count() as u64).wrapping_mul(0x9e3779b97f4a7c15));
+
+        let tiles = generate_tiles(
+            width,
+            framebuffer.get_height(),
+            self.settings.tile_size,
+            self.settings.tile_order,
+        );
+        let completed_tiles = AtomicUsize::new(0);
+
+        tiles.iter().par_bridge().for_each(|tile| {
+            if cancellation.is_cancelled() {
+                return;
+            }
+
+            tile.pixels().for_each(|(y, x)| {
+                let mut sampler = Sampler::new(seed, (y * width + x) as u64);
+                (0..self.settings.samples_per_pixel).for_each(|_| {
+                    let (dx, dy) = sampler.next_2d();
+                    let (sample_x, sample_y) = (x as f32 + dx, y as f32 + dy);
+                    let mut ray = ray_emitter.ray_at(sample_x, sample_y);
+                    // An instant shutter draws no time, keeping the samples of still renders unchanged.
+                    if !self.settings.shutter.is_instant() {
+                        ray = ray.with_time(self.settings.shutter.sample(sampler.next_f32()));
+                    }
+                    let color = self.settings.integrator.radiance(
+                        scene,
+                        light,
+                        &ray,
+                        &mut sampler,
+                        self.settings.occlusion_distance,
+                    );
+                    framebuffer.splat(sample_x, sample_y, color, &self.settings.filter);
+                });
             });
 
-            let _ = surface.finish();
+            if framebuffer.has_aovs() {
+                framebuffer.set_aovs(tile.pixels().map(|(y, x)| {
+                    let ray = ray_emitter.ray_at(x as f32 + 0.5, y as f32 + 0.5);
+                    (
+                        y * width + x,
+                        Self::render_aovs(ray_emitter, scene, light, &ray),
+                    )
+                }));
+            }
+
+            progress(&RenderProgress::new(
+                completed_tiles.fetch_add(1, Ordering::Relaxed) + 1,
+                tiles.len(),
+                framebuffer.get_sample_count(),
+                start.elapsed(),
+            ));
+        });
+
+        if cancellation.is_cancelled() {
+            return RenderStatus::Cancelled;
         }
 
-        for event in event_pump.poll_iter() {
-            match event {
-                Event::Quit { .. }
-                | Event::KeyDown {
-                    keycode: Some(Keycode::Escape),
-                    ..
-                } => return true,
-                _ => {}
+        framebuffer.add_sample_count(self.settings.samples_per_pixel);
+        framebuffer.set_denoised(
+            self.settings
+                .denoise
+                .and_then(|settings| denoiser::denoise(framebuffer, &settings)),
+        );
+        RenderStatus::Completed
+    }
+
+    /// Compute the extra passes of a camera ray, out of its intermediate shading values.
+    fn render_aovs(ray_emitter: &RayEmitter, scene: &Scene, light: &Light, ray: &Ray) -> AovSample {
+        match scene.closest_hit(ray, &RayType::Camera) {
+            None => AovSample::default(),
+            Some(hit) => {
+                let (_, see_light) = scene.light_visibility(&hit, light);
+                let material = scene.renderables[hit.renderable_index].get_material();
+                AovSample {
+                    depth: (hit.point - ray_emitter.get_position())
+                        .dot(ray_emitter.get_direction().normalize()),
+                    normal: hit.normal,
+                    albedo: material.get_base_color().unwrap_or(Vec4::ONE),
+                    position: hit.point,
+                    object_id: Some(hit.renderable_index),
+                    shadow: see_light,
+                }
             }
         }
-        false
+    }
+}
+
+// #####################################
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use glam::{Vec3A, Vec4};
+
+    use crate::{
+        entity::{
+            geometry::{GeometryType, sphere::Sphere},
+            rendering::{
+                light::Light,
+                material::{ColorMaterial, MaterialType},
+            },
+            scene::Scene,
+        },
+        rendering::{
+            framebuffer::FrameBuffer,
+            progress::{CancellationToken, RenderStatus},
+            ray_emitter::RayEmitter,
+            renderer::{RenderSettings, Renderer, Shutter},
+        },
+    };
+
+    #[test]
+    fn test_success_render() {
+        let mut scene = Scene::new(&Vec4::new(0.1, 0.1, 0.1, 1.));
+        scene.renderables.push(GeometryType::Sphere(
+            Sphere::new(
+                &Vec3A::ZERO,
+                10.,
+                &MaterialType::Color(ColorMaterial::new(Vec4::new(0., 0.5, 0., 0.))),
+            )
+            .unwrap(),
+        ));
+        let light = Light::new(
+            &Vec3A::new(0., 100., 0.),
+            &Vec3A::new(0., -1., 0.),
+            1.,
+            &MaterialType::Color(ColorMaterial::new(Vec4::ONE)),
+        )
+        .unwrap();
+        let emitter = RayEmitter::new(Vec3A::new(0., 0., -20.), Vec3A::new(0., 0., 1.), 70, 40);
+        let framebuffer = FrameBuffer::new(70, 40, true);
+
+        let renderer = Renderer::new(RenderSettings {
+            samples_per_pixel: 2,
+            ..RenderSettings::default()
+        });
+        renderer.render(&emitter, &scene, &light, &framebuffer);
+
+        assert_eq!(framebuffer.get_sample_count(), 2);
+        assert_eq!(
+            framebuffer.get_color(20 * 70 + 35),
+            Vec4::new(0.1, 0.6, 0.1, 1.)
+        );
+        assert_eq!(framebuffer.get_color(0), scene.ambient);
+        assert_eq!(
+            framebuffer.get_aov(20 * 70 + 35).unwrap().object_id,
+            Some(0)
+        );
+        assert_eq!(framebuffer.get_aov(0).unwrap().object_id, None);
+    }
+
+    #[test]
+    fn test_success_render_motion_blur() {
+        let mut sphere = Sphere::new(
+            &Vec3A::ZERO,
+            5.,
+            &MaterialType::Color(ColorMaterial::new(Vec4::new(0., 0.5, 0., 0.))),
+        )
+        .unwrap();
+        sphere.set_velocity(&Vec3A::new(20., 0., 0.));
+        let mut scene = Scene::new(&Vec4::new(0.1, 0.1, 0.1, 1.));
+        scene.renderables.push(GeometryType::Sphere(sphere));
+        let light = Light::new(
+            &Vec3A::new(0., 100., 0.),
+            &Vec3A::new(0., -1., 0.),
+            1.,
+            &MaterialType::Color(ColorMaterial::new(Vec4::ONE)),
+        )
+        .unwrap();
+        let emitter = RayEmitter::new(Vec3A::new(0., 0., -20.), Vec3A::new(0., 0., 1.), 60, 10);
+
+        // Count the pixels of the middle row partially covered by the sphere.
+        let blurred_pixels = |shutter: Shutter| {
+            let framebuffer = FrameBuffer::new(60, 10, false);
+            Renderer::new(RenderSettings {
+                samples_per_pixel: 32,
+                shutter,
+                ..RenderSettings::default()
+            })
+            .render(&emitter, &scene, &light, &framebuffer);
+            (5 * 60..6 * 60)
+                .filter(|x| (0.15..0.55).contains(&framebuffer.get_color(*x).y))
+                .count()
+        };
+
+        let still = blurred_pixels(Shutter::default());
+        let blurred = blurred_pixels(Shutter {
+            open: 0.,
+            close: 1.,
+        });
+        assert!(still <= 2);
+        assert!(blurred >= 15);
+    }
+
+    #[test]
+    fn test_failure_render_cancelled() {
+        let scene = Scene::new(&Vec4::ONE);
+        let light = Light::new(
+            &Vec3A::ZERO,
+            &Vec3A::new(0., -1., 0.),
+            1.,
+            &MaterialType::Color(ColorMaterial::new(Vec4::ONE)),
+        )
+        .unwrap();
+        let emitter = RayEmitter::new(Vec3A::ZERO, Vec3A::new(0., 0., 1.), 64, 64);
+        let framebuffer = FrameBuffer::new(64, 64, false);
+        let cancellation = CancellationToken::new();
+        let reports = AtomicUsize::new(0);
+
+        cancellation.cancel();
+        let status = Renderer::default().render_with_progress(
+            &emitter,
+            &scene,
+            &light,
+            &framebuffer,
+            &|_| {
+                reports.fetch_add(1, Ordering::Relaxed);
+            },
+            &cancellation,
+        );
+
+        assert_eq!(status, RenderStatus::Cancelled);
+        assert_eq!(reports.load(Ordering::Relaxed), 0);
+        assert_eq!(framebuffer.get_sample_count(), 0);
     }
 }
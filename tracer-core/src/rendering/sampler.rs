@@ -0,0 +1,40 @@
+/// Small PCG32 random number generator, used to place samples inside the pixels.
+/// Each pixel gets its own stream, so renders are reproducible whatever the threads scheduling is.
+#[derive(Debug, Clone)]
+pub struct Sampler {
+    state: u64,
+    increment: u64,
+}
+
+impl Sampler {
+    const MULTIPLIER: u64 = 6364136223846793005;
+
+    pub fn new(seed: u64, stream: u64) -> Self {
+        let mut sampler = Self {
+            state: 0,
+            increment: (stream << 1) | 1,
+        };
+        sampler.next_u32();
+        sampler.state = sampler.state.wrapping_add(seed);
+        sampler.next_u32();
+        sampler
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        let old_state = self.state;
+        self.state = old_state
+            .wrapping_mul(Self::MULTIPLIER)
+            .wrapping_add(self.increment);
+        let xorshifted = (((old_state >> 18) ^ old_state) >> 27) as u32;
+        xorshifted.rotate_right((old_state >> 59) as u32)
+    }
+
+    /// Get a uniformly distributed value in [0, 1).
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u32() >> 8) as f32 / (1 << 24) as f32
+    }
+
+    pub fn next_2d(&mut self) -> (f32, f32) {
+        (self.next_f32(), self.next_f32())
+    }
+}
@@ -0,0 +1,136 @@
+use range2d::Range2D;
+use serde::{Deserialize, Serialize};
+
+/// Order in which the tiles are handed to the rendering threads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TileOrder {
+    Scanline,
+    Spiral,
+    Hilbert,
+}
+
+/// Square area of the render target, rendered as a single unit of work.
+/// Tiles on the right and bottom edges are cropped to the render target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tile {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Tile {
+    pub const MIN_SIZE: usize = 16;
+    pub const MAX_SIZE: usize = 64;
+
+    /// Iterate through the tile's pixels, as (y, x) coordinates of the render target.
+    pub fn pixels(&self) -> Range2D {
+        Range2D::new(self.y..self.y + self.height, self.x..self.x + self.width)
+    }
+}
+
+/// Split a render target into tiles, sorted in the requested order.
+/// The tile size is clamped between Tile::MIN_SIZE and Tile::MAX_SIZE pixels.
+pub fn generate_tiles(
+    width: usize,
+    height: usize,
+    tile_size: usize,
+    order: TileOrder,
+) -> Vec<Tile> {
+    let size = tile_size.clamp(Tile::MIN_SIZE, Tile::MAX_SIZE);
+    let tiles_x = width.div_ceil(size);
+    let tiles_y = height.div_ceil(size);
+
+    let mut coordinates: Vec<(usize, usize)> = Range2D::new(0..tiles_y, 0..tiles_x).collect();
+
+    match order {
+        TileOrder::Scanline => {}
+        TileOrder::Spiral => {
+            let center_x = (tiles_x as f32 - 1.) / 2.;
+            let center_y = (tiles_y as f32 - 1.) / 2.;
+            coordinates.sort_by(|a, b| {
+                let key = |(y, x): &(usize, usize)| {
+                    let dx = *x as f32 - center_x;
+                    let dy = *y as f32 - center_y;
+                    (dx.abs().max(dy.abs()).round(), dy.atan2(dx))
+                };
+                key(a).partial_cmp(&key(b)).unwrap()
+            });
+        }
+        TileOrder::Hilbert => {
+            let n = tiles_x.max(tiles_y).next_power_of_two();
+            coordinates.sort_by_key(|(y, x)| hilbert_index(n, *x, *y));
+        }
+    }
+
+    coordinates
+        .iter()
+        .map(|(y, x)| Tile {
+            x: x * size,
+            y: y * size,
+            width: size.min(width - x * size),
+            height: size.min(height - y * size),
+        })
+        .collect()
+}
+
+/// Get the distance along the Hilbert curve covering a n*n grid (n being a power of two) of the given cell.
+fn hilbert_index(n: usize, mut x: usize, mut y: usize) -> usize {
+    let mut index = 0;
+    let mut s = n / 2;
+
+    while s > 0 {
+        let rx = (x & s > 0) as usize;
+        let ry = (y & s > 0) as usize;
+        index += s * s * ((3 * rx) ^ ry);
+
+        if ry == 0 {
+            if rx == 1 {
+                x = n - 1 - x;
+                y = n - 1 - y;
+            }
+            std::mem::swap(&mut x, &mut y);
+        }
+        s /= 2;
+    }
+    index
+}
+
+// #####################################
+
+#[cfg(test)]
+mod tests {
+    use crate::rendering::tile::{Tile, TileOrder, generate_tiles};
+
+    #[test]
+    fn test_success_generate_tiles() {
+        [TileOrder::Scanline, TileOrder::Spiral, TileOrder::Hilbert]
+            .iter()
+            .for_each(|order| {
+                let tiles = generate_tiles(100, 70, 32, *order);
+                assert_eq!(tiles.len(), 4 * 3);
+
+                let mut coverage = vec![0; 100 * 70];
+                tiles
+                    .iter()
+                    .flat_map(|x| x.pixels())
+                    .for_each(|(y, x)| coverage[y * 100 + x] += 1);
+                assert!(coverage.iter().all(|x| *x == 1));
+            });
+
+        let tiles = generate_tiles(128, 128, 32, TileOrder::Hilbert);
+        tiles.windows(2).for_each(|x| {
+            assert_eq!(x[0].x.abs_diff(x[1].x) + x[0].y.abs_diff(x[1].y), 32);
+        });
+    }
+
+    #[test]
+    fn test_failure_generate_tiles_size_out_of_range() {
+        let tiles = generate_tiles(256, 256, 4, TileOrder::Scanline);
+        assert_eq!(tiles[0].width, Tile::MIN_SIZE);
+
+        let tiles = generate_tiles(256, 256, 1000, TileOrder::Scanline);
+        assert_eq!(tiles[0].width, Tile::MAX_SIZE);
+    }
+}
@@ -0,0 +1,279 @@
+use std::collections::BTreeMap;
+
+use glam::{Vec3A, Vec4};
+use serde::{Deserialize, Serialize};
+use toml::Spanned;
+
+use crate::{
+    animation::{AnimatedValue, Interpolation, Keyframe},
+    entity::{
+        geometry::{
+            algebraic::Monomial,
+            csg::CsgOperation,
+            heightfield::HeightfieldSource,
+            metaballs::Metaball,
+            patch::PatchBasis,
+            sdf::{SdfShape, SphereTracing},
+            transform::Transform,
+        },
+        rendering::material::MaterialType,
+        scene::Scene,
+    },
+    rendering::{
+        denoiser::DenoiseSettings, integrator::Integrator, pixel_filter::PixelFilter,
+        ray_emitter::Projection, renderer::Shutter, tile::TileOrder,
+    },
+};
+
+/// Root of a scene file, describing everything needed to render an image.
+/// Materials are declared once by name, and referenced by the lights and geometries.
+/// Objects are geometries declared once by name, and placed any number of times by the instances and the scene graph's nodes.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SceneDescription {
+    #[serde(default = "default_ambient")]
+    pub ambient: Vec4,
+    pub camera: CameraDescription,
+    pub light: LightDescription,
+    #[serde(default)]
+    pub render: RenderDescription,
+    #[serde(default)]
+    pub materials: BTreeMap<String, MaterialType>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub objects: BTreeMap<String, Spanned<GeometryDescription>>,
+    #[serde(default)]
+    pub geometry: Vec<Spanned<GeometryDescription>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub nodes: Vec<NodeDescription>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub animation: Option<AnimationDescription>,
+}
+
+fn default_ambient() -> Vec4 {
+    Vec4::new(0., 0., 0., 1.)
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CameraDescription {
+    pub position: Vec3A,
+    pub direction: Spanned<Vec3A>,
+    #[serde(default)]
+    pub projection: Projection,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LightDescription {
+    pub position: Vec3A,
+    pub direction: Spanned<Vec3A>,
+    #[serde(default = "default_light_radius")]
+    pub radius: Spanned<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub material: Option<Spanned<String>>,
+}
+
+fn default_light_radius() -> Spanned<f32> {
+    Spanned::new(0..0, 1.)
+}
+
+/// Output resolution, integrator and sampling parameters.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RenderDescription {
+    pub width: Spanned<u32>,
+    pub height: Spanned<u32>,
+    pub integrator: Integrator,
+    pub max_depth: usize,
+    pub occlusion_distance: f32,
+    pub samples_per_pixel: Spanned<u32>,
+    pub seed: u64,
+    pub filter: PixelFilter,
+    pub tile_size: usize,
+    pub tile_order: TileOrder,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub denoise: Option<DenoiseSettings>,
+    pub shutter: Shutter,
+}
+
+impl Default for RenderDescription {
+    fn default() -> Self {
+        Self {
+            width: Spanned::new(0..0, 1000),
+            height: Spanned::new(0..0, 1000),
+            integrator: Integrator::Whitted,
+            max_depth: Scene::DEFAULT_MAX_DEPTH,
+            occlusion_distance: 100.,
+            samples_per_pixel: Spanned::new(0..0, 1),
+            seed: 0,
+            filter: PixelFilter::default(),
+            tile_size: 32,
+            tile_order: TileOrder::Spiral,
+            denoise: None,
+            shutter: Shutter::default(),
+        }
+    }
+}
+
+/// Renderable object, made of a shape and the name of its material, moving at the given velocity (in units per
+/// second) while the shutter is open. The optional transform scales, rotates then translates the shape, which is
+/// then placed by an instance of its own.
+/// An instance places the named object instead, its material overriding the object's one if given.
+/// A CSG combination combines its operands, which are closed solids defaulting to the combination's material and
+/// moving along with the combination on top of their own velocities.
+/// A heightfield's elevations are loaded out of its source, image paths being relative to the scene file.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct GeometryDescription {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub material: Option<Spanned<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub object: Option<Spanned<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<Spanned<HeightfieldSource>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transform: Option<Transform>,
+    #[serde(default)]
+    pub velocity: Vec3A,
+    #[serde(flatten)]
+    pub shape: ShapeDescription,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub operands: Vec<Spanned<GeometryDescription>>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum ShapeDescription {
+    Sphere {
+        position: Vec3A,
+        radius: f32,
+    },
+    Plane {
+        position: Vec3A,
+        normal: Vec3A,
+    },
+    Instance {},
+    #[serde(rename = "box")]
+    Cuboid {
+        position: Vec3A,
+        size: Vec3A,
+    },
+    Cylinder {
+        position: Vec3A,
+        radius: f32,
+        height: f32,
+    },
+    Cone {
+        position: Vec3A,
+        radius: f32,
+        height: f32,
+    },
+    Disk {
+        position: Vec3A,
+        normal: Vec3A,
+        radius: f32,
+    },
+    Quad {
+        position: Vec3A,
+        edge_u: Vec3A,
+        edge_v: Vec3A,
+    },
+    Torus {
+        position: Vec3A,
+        major_radius: f32,
+        minor_radius: f32,
+    },
+    Csg {
+        operation: CsgOperation,
+    },
+    /// Terrain spanning the size from its lowest corner.
+    Heightfield {
+        position: Vec3A,
+        size: Vec3A,
+    },
+    /// Bicubic surface out of rows of control points, relative to the position.
+    Patch {
+        position: Vec3A,
+        #[serde(default)]
+        basis: PatchBasis,
+        control_points: Vec<Vec<Vec3A>>,
+    },
+    /// Fiber along 3n + 1 control points, relative to the position, its widths being spread evenly along it.
+    Curve {
+        position: Vec3A,
+        control_points: Vec<Vec3A>,
+        widths: Vec<f32>,
+    },
+    /// Blobby object out of balls relative to the position, its surface being where their fields reach the threshold.
+    Metaballs {
+        position: Vec3A,
+        balls: Vec<Metaball>,
+        threshold: f32,
+    },
+    /// Surface where the sum of the terms, a polynomial in the coordinates relative to the position, is zero,
+    /// clipped to the box spanning the extent around the position.
+    Algebraic {
+        position: Vec3A,
+        terms: Vec<Monomial>,
+        extent: Vec3A,
+    },
+    Sdf {
+        position: Vec3A,
+        shape: SdfShape,
+        #[serde(default)]
+        tracing: SphereTracing,
+    },
+}
+
+/// Node of the scene graph, placing the named object (if any) and its children relative to its parent.
+/// The material overrides the objects' ones, for the node and its children without a material of their own.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NodeDescription {
+    pub name: Spanned<String>,
+    #[serde(default)]
+    pub transform: Transform,
+    #[serde(default = "default_visible")]
+    pub visible: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub object: Option<Spanned<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub material: Option<Spanned<String>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<NodeDescription>,
+}
+
+fn default_visible() -> bool {
+    true
+}
+
+/// Keyframed values overriding the scene's properties over time, sampled at the frame rate.
+/// The duration defaults to the time of the last keyframe.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AnimationDescription {
+    pub frame_rate: Spanned<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration: Option<f32>,
+    pub tracks: Vec<TrackDescription>,
+}
+
+impl Default for AnimationDescription {
+    fn default() -> Self {
+        Self {
+            frame_rate: Spanned::new(0..0, 24.),
+            duration: None,
+            tracks: Vec::new(),
+        }
+    }
+}
+
+/// Keyframes of a single number or vector of the scene file, designated by its path in the file,
+/// e.g. `camera.position`, `geometry.0.radius` or `materials.red.color`.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TrackDescription {
+    pub target: Spanned<String>,
+    #[serde(default)]
+    pub interpolation: Interpolation,
+    pub keyframes: Vec<Spanned<Keyframe<AnimatedValue>>>,
+}
@@ -0,0 +1,1342 @@
+pub mod description;
+pub mod timeline;
+
+use std::{
+    collections::BTreeMap,
+    fmt, fs, io,
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use glam::{Vec3A, Vec4};
+use toml::Spanned;
+
+use crate::{
+    entity::{
+        actor::{ActorTrait, DirectionalActorTrait},
+        geometry::{
+            GeometryType,
+            algebraic::Algebraic,
+            cone::Cone,
+            csg::{Csg, CsgOperation},
+            cuboid::Cuboid,
+            curve::Curve,
+            cylinder::Cylinder,
+            disk::Disk,
+            heightfield::{Heightfield, HeightfieldSource},
+            instance::Instance,
+            metaballs::Metaballs,
+            patch::Patch,
+            plane::Plane,
+            quad::Quad,
+            sdf::Sdf,
+            sphere::Sphere,
+            torus::Torus,
+        },
+        rendering::{
+            light::Light,
+            material::{ColorMaterial, MaterialBound, MaterialType},
+        },
+        scene::Scene,
+        scene_graph::{Node, SceneGraph},
+    },
+    rendering::{ray_emitter::RayEmitter, renderer::RenderSettings},
+    scene_file::{
+        description::{
+            CameraDescription, GeometryDescription, LightDescription, NodeDescription,
+            RenderDescription, SceneDescription, ShapeDescription,
+        },
+        timeline::Timeline,
+    },
+};
+
+/// Everything built out of a scene file, ready to be rendered.
+/// The scene of an animated file is built as it is at the animation's start.
+pub struct SceneFile {
+    pub scene: Scene,
+    pub light: Light,
+    pub camera: RayEmitter,
+    pub settings: RenderSettings,
+    pub timeline: Option<Timeline>,
+}
+
+/// Error raised while loading or saving a scene file, locating the faulty input whenever possible.
+#[derive(Debug)]
+pub enum SceneFileError {
+    Io {
+        path: PathBuf,
+        error: io::Error,
+    },
+    Serialization {
+        message: String,
+    },
+    /// A scene which can't be built out of its evaluated animation.
+    Build {
+        message: String,
+    },
+    Invalid {
+        path: PathBuf,
+        line: usize,
+        column: usize,
+        message: String,
+    },
+}
+
+impl fmt::Display for SceneFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SceneFileError::Io { path, error } => write!(f, "{}: {}", path.display(), error),
+            SceneFileError::Serialization { message } | SceneFileError::Build { message } => {
+                write!(f, "{}", message)
+            }
+            SceneFileError::Invalid {
+                path,
+                line,
+                column,
+                message,
+            } => write!(f, "{}:{}:{}: {}", path.display(), line, column, message),
+        }
+    }
+}
+
+impl std::error::Error for SceneFileError {}
+
+impl SceneFileError {
+    fn invalid(path: &Path, source: &str, span: Option<Range<usize>>, message: &str) -> Self {
+        let offset = span.map(|x| x.start.min(source.len())).unwrap_or(0);
+        let before = &source[..offset];
+        Self::Invalid {
+            path: path.to_path_buf(),
+            line: before.matches('\n').count() + 1,
+            column: before.chars().rev().take_while(|x| *x != '\n').count() + 1,
+            message: message.trim().to_string(),
+        }
+    }
+}
+
+// #####################################
+
+/// Read and build the scene file located at the given path.
+pub fn load(path: &Path) -> Result<SceneFile, SceneFileError> {
+    let source = fs::read_to_string(path).map_err(|error| SceneFileError::Io {
+        path: path.to_path_buf(),
+        error,
+    })?;
+    parse(&source, path)
+}
+
+/// Build a scene file out of its content, the path only being used to report errors.
+pub fn parse(source: &str, path: &Path) -> Result<SceneFile, SceneFileError> {
+    let mut description: SceneDescription = toml::from_str(source)
+        .map_err(|error| SceneFileError::invalid(path, source, error.span(), error.message()))?;
+    resolve_paths(&mut description, path.parent().unwrap_or(Path::new("")));
+
+    build(description)
+        .map_err(|(span, message)| SceneFileError::invalid(path, source, Some(span), &message))
+}
+
+/// Read the files of the described geometries relative to the scene file's directory, their paths being kept as
+/// written.
+fn resolve_paths(description: &mut SceneDescription, directory: &Path) {
+    fn resolve(geometry: &mut Spanned<GeometryDescription>, directory: &Path) {
+        if let Some(HeightfieldSource::Image { directory: x, .. }) =
+            geometry.get_mut().source.as_mut().map(|x| x.get_mut())
+        {
+            *x = directory.to_path_buf();
+        }
+        geometry
+            .get_mut()
+            .operands
+            .iter_mut()
+            .for_each(|x| resolve(x, directory));
+    }
+    description
+        .objects
+        .values_mut()
+        .chain(description.geometry.iter_mut())
+        .for_each(|x| resolve(x, directory));
+}
+
+/// Write the given scene file at the given path, in the format read by load.
+pub fn save(scene_file: &SceneFile, path: &Path) -> Result<(), SceneFileError> {
+    fs::write(path, to_string(scene_file)?).map_err(|error| SceneFileError::Io {
+        path: path.to_path_buf(),
+        error,
+    })
+}
+
+/// Serialize the given scene file, in the format read by parse.
+pub fn to_string(scene_file: &SceneFile) -> Result<String, SceneFileError> {
+    toml::to_string(&describe(scene_file)).map_err(|error| SceneFileError::Serialization {
+        message: error.to_string(),
+    })
+}
+
+/// Names given to the distinct materials and shared geometries while describing a scene file.
+#[derive(Default)]
+struct Names {
+    materials: BTreeMap<String, MaterialType>,
+    material_names: BTreeMap<String, String>,
+    objects: BTreeMap<String, Spanned<GeometryDescription>>,
+    shared: Vec<(Arc<GeometryType>, String)>,
+}
+
+impl Names {
+    fn material(&mut self, material: &MaterialType) -> Spanned<String> {
+        let key = toml::to_string(material).unwrap_or_default();
+        let name = self
+            .material_names
+            .entry(key)
+            .or_insert_with(|| format!("material_{}", self.materials.len()))
+            .clone();
+        self.materials.insert(name.clone(), material.clone());
+        Spanned::new(0..0, name)
+    }
+
+    /// Name the given shared geometry, describing it as an object the first time.
+    fn object(&mut self, geometry: &Arc<GeometryType>) -> Spanned<String> {
+        if let Some((_, name)) = self.shared.iter().find(|x| Arc::ptr_eq(&x.0, geometry)) {
+            return Spanned::new(0..0, name.clone());
+        }
+        let name = format!("object_{}", self.shared.len());
+        let description = GeometryDescription {
+            material: Some(self.material(geometry.get_material())),
+            object: None,
+            source: describe_source(geometry),
+            transform: None,
+            velocity: geometry.get_velocity(),
+            shape: describe_shape(geometry),
+            operands: self.operands(geometry),
+        };
+        self.objects
+            .insert(name.clone(), Spanned::new(0..0, description));
+        self.shared.push((geometry.clone(), name.clone()));
+        Spanned::new(0..0, name)
+    }
+
+    /// Describe a renderable, which is not located in any file.
+    fn geometry(&mut self, renderable: &GeometryType) -> Spanned<GeometryDescription> {
+        Spanned::new(0..0, self.describe(renderable))
+    }
+
+    /// Describe a renderable's table. Instances sharing a geometry refer to the same object, while a geometry
+    /// placed once keeps its shape inline.
+    fn describe(&mut self, renderable: &GeometryType) -> GeometryDescription {
+        let GeometryType::Instance(instance) = renderable else {
+            return GeometryDescription {
+                material: Some(self.material(renderable.get_material())),
+                object: None,
+                source: describe_source(renderable),
+                transform: None,
+                velocity: renderable.get_velocity(),
+                shape: describe_shape(renderable),
+                operands: self.operands(renderable),
+            };
+        };
+        let inner = instance.get_geometry();
+        if Arc::strong_count(inner) == 1
+            && instance.get_material_override().is_none()
+            && inner.get_velocity() == Vec3A::ZERO
+            && !matches!(**inner, GeometryType::Instance(_))
+        {
+            return GeometryDescription {
+                material: Some(self.material(inner.get_material())),
+                object: None,
+                source: describe_source(inner),
+                transform: Some(*instance.get_transform()),
+                velocity: instance.get_velocity(),
+                shape: describe_shape(inner),
+                operands: self.operands(inner),
+            };
+        }
+        GeometryDescription {
+            material: instance.get_material_override().map(|x| self.material(x)),
+            object: Some(self.object(inner)),
+            source: None,
+            transform: Some(*instance.get_transform()),
+            velocity: instance.get_velocity(),
+            shape: ShapeDescription::Instance {},
+            operands: Vec::new(),
+        }
+    }
+
+    /// Describe the operands of a CSG combination, other geometries having none.
+    fn operands(&mut self, geometry: &GeometryType) -> Vec<Spanned<GeometryDescription>> {
+        match geometry {
+            GeometryType::Csg(csg) => vec![
+                self.geometry(csg.get_left()),
+                self.geometry(csg.get_right()),
+            ],
+            _ => Vec::new(),
+        }
+    }
+
+    fn node(&mut self, node: &Node) -> NodeDescription {
+        NodeDescription {
+            name: Spanned::new(0..0, node.get_name().to_string()),
+            transform: node.transform,
+            visible: node.visible,
+            object: node.get_geometry().map(|x| self.object(x)),
+            material: node.get_material_override().map(|x| self.material(x)),
+            children: node.get_children().iter().map(|x| self.node(x)).collect(),
+        }
+    }
+}
+
+/// Build the description of instantiated objects, naming every distinct material.
+fn describe(scene_file: &SceneFile) -> SceneDescription {
+    let mut names = Names::default();
+    // The materials of an animated scene keep their names, as the tracks may refer to them.
+    if let Some(description) = scene_file
+        .timeline
+        .as_ref()
+        .and_then(|x| x.evaluate_description(0.).ok())
+    {
+        description.materials.iter().for_each(|(name, material)| {
+            names
+                .material_names
+                .insert(toml::to_string(material).unwrap_or_default(), name.clone());
+        });
+    }
+
+    let light = &scene_file.light;
+    let light = LightDescription {
+        position: light.get_position(),
+        direction: Spanned::new(0..0, light.get_direction()),
+        radius: Spanned::new(0..0, light.radius),
+        material: Some(names.material(light.get_material())),
+    };
+
+    // The graph's renderables are described by the graph's nodes.
+    let scene = &scene_file.scene;
+    let graph_range = scene.get_graph_range();
+    let geometry = scene
+        .renderables
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| !graph_range.contains(index))
+        .map(|(_, renderable)| names.geometry(renderable))
+        .collect();
+    let nodes = scene.graph.nodes.iter().map(|x| names.node(x)).collect();
+
+    let (width, height) = scene_file.camera.get_resolution();
+    let settings = &scene_file.settings;
+
+    SceneDescription {
+        ambient: scene_file.scene.ambient,
+        camera: CameraDescription {
+            position: scene_file.camera.get_position(),
+            direction: Spanned::new(0..0, scene_file.camera.get_direction()),
+            projection: scene_file.camera.get_projection(),
+        },
+        light,
+        render: RenderDescription {
+            width: Spanned::new(0..0, width),
+            height: Spanned::new(0..0, height),
+            integrator: settings.integrator,
+            max_depth: scene_file.scene.max_depth,
+            occlusion_distance: settings.occlusion_distance,
+            samples_per_pixel: Spanned::new(0..0, settings.samples_per_pixel),
+            seed: settings.seed,
+            filter: settings.filter,
+            tile_size: settings.tile_size,
+            tile_order: settings.tile_order,
+            denoise: settings.denoise,
+            shutter: settings.shutter,
+        },
+        materials: names.materials,
+        objects: names.objects,
+        geometry,
+        nodes,
+        animation: scene_file
+            .timeline
+            .as_ref()
+            .map(|x| x.get_animation().clone()),
+    }
+}
+
+/// Describe the source of a heightfield's elevations, other geometries having none.
+fn describe_source(geometry: &GeometryType) -> Option<Spanned<HeightfieldSource>> {
+    match geometry {
+        GeometryType::Heightfield(i) => Some(Spanned::new(0..0, i.get_source().clone())),
+        _ => None,
+    }
+}
+
+/// Describe the shape of a geometry, leaving out its material and velocity.
+fn describe_shape(geometry: &GeometryType) -> ShapeDescription {
+    match geometry {
+        GeometryType::Plane(i) => ShapeDescription::Plane {
+            position: i.get_position(),
+            normal: i.get_direction(),
+        },
+        GeometryType::Sphere(i) => ShapeDescription::Sphere {
+            position: i.get_position(),
+            radius: i.radius,
+        },
+        GeometryType::Light(i) => ShapeDescription::Sphere {
+            position: i.get_position(),
+            radius: i.radius,
+        },
+        GeometryType::Instance(_) => ShapeDescription::Instance {},
+        GeometryType::Cuboid(i) => ShapeDescription::Cuboid {
+            position: i.get_position(),
+            size: i.size,
+        },
+        GeometryType::Cylinder(i) => ShapeDescription::Cylinder {
+            position: i.get_position(),
+            radius: i.radius,
+            height: i.height,
+        },
+        GeometryType::Cone(i) => ShapeDescription::Cone {
+            position: i.get_position(),
+            radius: i.radius,
+            height: i.height,
+        },
+        GeometryType::Disk(i) => ShapeDescription::Disk {
+            position: i.get_position(),
+            normal: i.get_direction(),
+            radius: i.radius,
+        },
+        GeometryType::Quad(i) => ShapeDescription::Quad {
+            position: i.get_position(),
+            edge_u: i.edge_u,
+            edge_v: i.edge_v,
+        },
+        GeometryType::Torus(i) => ShapeDescription::Torus {
+            position: i.get_position(),
+            major_radius: i.major_radius,
+            minor_radius: i.minor_radius,
+        },
+        GeometryType::Csg(i) => ShapeDescription::Csg {
+            operation: i.operation,
+        },
+        GeometryType::Heightfield(i) => ShapeDescription::Heightfield {
+            position: i.get_position(),
+            size: i.get_size(),
+        },
+        GeometryType::Patch(i) => ShapeDescription::Patch {
+            position: i.get_position(),
+            basis: i.get_basis(),
+            control_points: i.get_control_points().clone(),
+        },
+        GeometryType::Curve(i) => ShapeDescription::Curve {
+            position: i.get_position(),
+            control_points: i.get_control_points().clone(),
+            widths: i.get_widths().clone(),
+        },
+        GeometryType::Metaballs(i) => ShapeDescription::Metaballs {
+            position: i.get_position(),
+            balls: i.get_balls().clone(),
+            threshold: i.get_threshold(),
+        },
+        GeometryType::Algebraic(i) => ShapeDescription::Algebraic {
+            position: i.get_position(),
+            terms: i.get_terms().clone(),
+            extent: i.get_extent(),
+        },
+        GeometryType::Sdf(i) => ShapeDescription::Sdf {
+            position: i.get_position(),
+            shape: i.shape.clone(),
+            tracing: i.tracing,
+        },
+    }
+}
+
+/// Instantiate the described objects, along with the timeline of an animated description.
+fn build(description: SceneDescription) -> Result<SceneFile, (Range<usize>, String)> {
+    let Some(timeline) = Timeline::new(description.clone())? else {
+        return build_static(description);
+    };
+    // The errors of the file's own values are located in the file, before the ones of the animated values.
+    let mut scene_file = timeline
+        .build(0.)
+        .or_else(|error| build_static(description).and(Err(error)))?;
+    scene_file.timeline = Some(timeline);
+    Ok(scene_file)
+}
+
+/// Instantiate the described objects, resolving the materials by name and ignoring any animation.
+pub(crate) fn build_static(
+    description: SceneDescription,
+) -> Result<SceneFile, (Range<usize>, String)> {
+    let material = |name: &Spanned<String>, field: &str| {
+        description
+            .materials
+            .get(name.get_ref())
+            .cloned()
+            .ok_or_else(|| {
+                (
+                    name.span(),
+                    format!("{}: unknown material `{}`", field, name.get_ref()),
+                )
+            })
+    };
+
+    let objects = description
+        .objects
+        .iter()
+        .map(|(name, object)| {
+            let field = format!("objects.{name}");
+            if let ShapeDescription::Instance {} = object.get_ref().shape {
+                let span = object
+                    .get_ref()
+                    .object
+                    .as_ref()
+                    .map_or(object.span(), |x| x.span());
+                return Err((span, format!("{field}: an object cannot be an instance")));
+            }
+            Ok((
+                name.clone(),
+                Arc::new(build_geometry(
+                    object,
+                    &field,
+                    &BTreeMap::new(),
+                    None,
+                    &material,
+                )?),
+            ))
+        })
+        .collect::<Result<BTreeMap<String, Arc<GeometryType>>, (Range<usize>, String)>>()?;
+
+    let mut scene = Scene::new(&description.ambient);
+    scene.renderables = description
+        .geometry
+        .iter()
+        .enumerate()
+        .map(|(index, x)| {
+            build_geometry(x, &format!("geometry[{index}]"), &objects, None, &material)
+        })
+        .collect::<Result<Vec<GeometryType>, (Range<usize>, String)>>()?;
+    scene.graph = SceneGraph {
+        nodes: build_nodes(&description.nodes, "nodes", &objects, &material)?,
+    };
+    scene.flatten_graph();
+
+    let light_material = match &description.light.material {
+        Some(name) => material(name, "light.material")?,
+        None => MaterialType::Color(ColorMaterial::new(Vec4::ONE)),
+    };
+    let render = &description.render;
+    // An empty image or a zero direction would leave nothing to render.
+    let counts = [
+        ("render.width", &render.width),
+        ("render.height", &render.height),
+        ("render.samples_per_pixel", &render.samples_per_pixel),
+    ];
+    if let Some((field, count)) = counts.iter().find(|(_, x)| *x.get_ref() == 0) {
+        return Err((count.span(), format!("{field}: must be above zero")));
+    }
+    let directions = [
+        ("camera.direction", &description.camera.direction),
+        ("light.direction", &description.light.direction),
+    ];
+    if let Some((field, direction)) = directions
+        .iter()
+        .find(|(_, x)| !x.get_ref().is_finite() || *x.get_ref() == Vec3A::ZERO)
+    {
+        return Err((
+            direction.span(),
+            format!("{field}: must be finite and non-zero"),
+        ));
+    }
+    scene.max_depth = render.max_depth;
+    let mut camera = RayEmitter::new(
+        description.camera.position,
+        *description.camera.direction.get_ref(),
+        *render.width.get_ref(),
+        *render.height.get_ref(),
+    );
+    camera.set_projection(description.camera.projection);
+    let radius = &description.light.radius;
+    let light = Light::new(
+        &description.light.position,
+        description.light.direction.get_ref(),
+        *radius.get_ref(),
+        &light_material,
+    )
+    .map_err(|error| (radius.span(), format!("light.radius: {error}")))?;
+
+    Ok(SceneFile {
+        scene,
+        light,
+        camera,
+        settings: RenderSettings {
+            integrator: render.integrator,
+            occlusion_distance: render.occlusion_distance,
+            samples_per_pixel: *render.samples_per_pixel.get_ref(),
+            filter: render.filter,
+            seed: render.seed,
+            denoise: render.denoise,
+            tile_size: render.tile_size,
+            tile_order: render.tile_order,
+            shutter: render.shutter,
+        },
+        timeline: None,
+    })
+}
+
+/// Instantiate a described geometry. Instances are built out of shared objects, which cannot be instances
+/// themselves. The operands of a CSG combination default to the combination's material.
+/// Errors without a more precise location are located at the geometry's table.
+fn build_geometry(
+    geometry: &Spanned<GeometryDescription>,
+    field: &str,
+    objects: &BTreeMap<String, Arc<GeometryType>>,
+    inherited: Option<&MaterialType>,
+    material: &dyn Fn(&Spanned<String>, &str) -> BuildResult<MaterialType>,
+) -> BuildResult<GeometryType> {
+    let description = geometry.get_ref();
+    let own_material = description
+        .material
+        .as_ref()
+        .map(|x| material(x, &format!("{field}.material")))
+        .transpose()?;
+    let is_instance = matches!(description.shape, ShapeDescription::Instance {});
+    if let Some(name) = &description.object
+        && !is_instance
+    {
+        return Err((
+            name.span(),
+            format!("{field}.object: only instances refer to an object"),
+        ));
+    }
+    if let Some(source) = &description.source
+        && !matches!(description.shape, ShapeDescription::Heightfield { .. })
+    {
+        return Err((
+            source.span(),
+            format!("{field}.source: only heightfields have a source"),
+        ));
+    }
+    if let Some(operand) = description.operands.first()
+        && !matches!(description.shape, ShapeDescription::Csg { .. })
+    {
+        return Err((
+            operand.span(),
+            format!("{field}.operands: only csg geometries have operands"),
+        ));
+    }
+    let shape = build_shape(geometry, field, objects, own_material, inherited, material)?;
+
+    // An instance carries its own transform, while any other transformed shape is placed by an instance of its own.
+    match (shape, description.transform.filter(|_| !is_instance)) {
+        (shape, Some(transform)) => {
+            let mut instance = Instance::new(Arc::new(shape), &transform, None);
+            instance.set_velocity(&description.velocity);
+            Ok(GeometryType::Instance(instance))
+        }
+        (mut shape, None) => {
+            shape.set_velocity(&description.velocity);
+            Ok(shape)
+        }
+    }
+}
+
+/// Combine the described operands from left to right, e.g. subtracting all the others from the first one.
+fn build_csg(
+    operation: CsgOperation,
+    geometry: &Spanned<GeometryDescription>,
+    field: &str,
+    objects: &BTreeMap<String, Arc<GeometryType>>,
+    csg_material: &MaterialType,
+    material: &dyn Fn(&Spanned<String>, &str) -> BuildResult<MaterialType>,
+) -> BuildResult<GeometryType> {
+    // A missing operand is located at the last one given, or at the combination itself.
+    let operands = &geometry.get_ref().operands;
+    let (first, rest) = match operands.split_first() {
+        Some((first, rest)) if !rest.is_empty() => (first, rest),
+        _ => {
+            return Err((
+                operands.last().unwrap_or(geometry).span(),
+                format!("{field}.operands: a csg combines at least two operands"),
+            ));
+        }
+    };
+    let build_operand = |index: usize, operand: &Spanned<GeometryDescription>| {
+        let field = format!("{field}.operands[{index}]");
+        let solid = build_geometry(operand, &field, objects, Some(csg_material), material)?;
+        match solid.as_solid() {
+            Some(_) => Ok(solid),
+            None => Err((
+                operand.span(),
+                format!("{field}: a {} is not a closed solid", solid.get_name()),
+            )),
+        }
+    };
+    let mut combination = build_operand(0, first)?;
+    for (index, operand) in rest.iter().enumerate() {
+        let right = build_operand(index + 1, operand)?;
+        combination = GeometryType::Csg(
+            Csg::new(operation, combination, right, csg_material)
+                .map_err(|error| (operand.span(), format!("{field}: {error}")))?,
+        );
+    }
+    Ok(combination)
+}
+
+/// Instantiate a described shape, out of its object for an instance and out of its operands for a CSG combination.
+/// Any other shape needs a material, either its own or the inherited one.
+fn build_shape(
+    geometry: &Spanned<GeometryDescription>,
+    field: &str,
+    objects: &BTreeMap<String, Arc<GeometryType>>,
+    own_material: Option<MaterialType>,
+    inherited: Option<&MaterialType>,
+    material: &dyn Fn(&Spanned<String>, &str) -> BuildResult<MaterialType>,
+) -> BuildResult<GeometryType> {
+    let span = geometry.span();
+    let description = geometry.get_ref();
+    let shape_material = || {
+        own_material
+            .clone()
+            .or_else(|| inherited.cloned())
+            .ok_or_else(|| (span.clone(), format!("{field}: missing material")))
+    };
+    // The shapes' own checks are located at their table.
+    let invalid = |error: String| (span.clone(), format!("{field}: {error}"));
+
+    Ok(match &description.shape {
+        ShapeDescription::Sphere { position, radius } => GeometryType::Sphere(
+            Sphere::new(position, *radius, &shape_material()?).map_err(invalid)?,
+        ),
+        ShapeDescription::Plane { position, normal } => {
+            GeometryType::Plane(Plane::new(position, normal, &shape_material()?).map_err(invalid)?)
+        }
+        ShapeDescription::Instance {} => {
+            let Some(name) = &description.object else {
+                return Err((span, format!("{field}: missing object")));
+            };
+            let object = objects.get(name.get_ref()).ok_or_else(|| {
+                (
+                    name.span(),
+                    format!("{field}.object: unknown object `{}`", name.get_ref()),
+                )
+            })?;
+            GeometryType::Instance(Instance::new(
+                object.clone(),
+                &description.transform.unwrap_or_default(),
+                own_material,
+            ))
+        }
+        ShapeDescription::Cuboid { position, size } => {
+            GeometryType::Cuboid(Cuboid::new(position, size, &shape_material()?).map_err(invalid)?)
+        }
+        ShapeDescription::Cylinder {
+            position,
+            radius,
+            height,
+        } => GeometryType::Cylinder(
+            Cylinder::new(position, *radius, *height, &shape_material()?).map_err(invalid)?,
+        ),
+        ShapeDescription::Cone {
+            position,
+            radius,
+            height,
+        } => GeometryType::Cone(
+            Cone::new(position, *radius, *height, &shape_material()?).map_err(invalid)?,
+        ),
+        ShapeDescription::Disk {
+            position,
+            normal,
+            radius,
+        } => GeometryType::Disk(
+            Disk::new(position, normal, *radius, &shape_material()?).map_err(invalid)?,
+        ),
+        ShapeDescription::Quad {
+            position,
+            edge_u,
+            edge_v,
+        } => GeometryType::Quad(
+            Quad::new(position, edge_u, edge_v, &shape_material()?).map_err(invalid)?,
+        ),
+        ShapeDescription::Torus {
+            position,
+            major_radius,
+            minor_radius,
+        } => GeometryType::Torus(
+            Torus::new(position, *major_radius, *minor_radius, &shape_material()?)
+                .map_err(invalid)?,
+        ),
+        ShapeDescription::Csg { operation } => build_csg(
+            *operation,
+            geometry,
+            field,
+            objects,
+            &shape_material()?,
+            material,
+        )?,
+        ShapeDescription::Heightfield { position, size } => {
+            let Some(source) = &description.source else {
+                return Err((span, format!("{field}: missing source")));
+            };
+            let shape_material = shape_material()?;
+            Heightfield::check_size(size)
+                .map_err(|error| (span.clone(), format!("{field}.size: {error}")))?;
+            GeometryType::Heightfield(
+                Heightfield::new(position, size, source.get_ref().clone(), &shape_material)
+                    .map_err(|error| (source.span(), format!("{field}.source: {error}")))?,
+            )
+        }
+        ShapeDescription::Patch {
+            position,
+            basis,
+            control_points,
+        } => GeometryType::Patch(
+            Patch::new(position, *basis, control_points.clone(), &shape_material()?)
+                .map_err(invalid)?,
+        ),
+        ShapeDescription::Curve {
+            position,
+            control_points,
+            widths,
+        } => GeometryType::Curve(
+            Curve::new(
+                position,
+                control_points.clone(),
+                widths.clone(),
+                &shape_material()?,
+            )
+            .map_err(invalid)?,
+        ),
+        ShapeDescription::Metaballs {
+            position,
+            balls,
+            threshold,
+        } => GeometryType::Metaballs(
+            Metaballs::new(position, balls.clone(), *threshold, &shape_material()?)
+                .map_err(invalid)?,
+        ),
+        ShapeDescription::Algebraic {
+            position,
+            terms,
+            extent,
+        } => GeometryType::Algebraic(
+            Algebraic::new(position, terms.clone(), extent, &shape_material()?).map_err(invalid)?,
+        ),
+        ShapeDescription::Sdf {
+            position,
+            shape,
+            tracing,
+        } => GeometryType::Sdf(
+            Sdf::new(position, shape.clone(), tracing, &shape_material()?).map_err(invalid)?,
+        ),
+    })
+}
+
+/// Result of building a part of a scene file, locating the faulty input on failure.
+type BuildResult<T> = Result<T, (Range<usize>, String)>;
+
+/// Instantiate the described nodes along with their children, whose names must be usable in paths.
+fn build_nodes(
+    nodes: &[NodeDescription],
+    field: &str,
+    objects: &BTreeMap<String, Arc<GeometryType>>,
+    material: &dyn Fn(&Spanned<String>, &str) -> BuildResult<MaterialType>,
+) -> BuildResult<Vec<Node>> {
+    nodes
+        .iter()
+        .enumerate()
+        .map(|(index, node)| {
+            let field = format!("{field}[{index}]");
+            let name = node.name.get_ref();
+            if name.is_empty() || name.contains('/') {
+                return Err((
+                    node.name.span(),
+                    format!("{field}.name: must not be empty nor contain `/`"),
+                ));
+            }
+            if nodes[..index].iter().any(|x| x.name.get_ref() == name) {
+                return Err((
+                    node.name.span(),
+                    format!("{field}.name: duplicate name `{name}` among the node's siblings"),
+                ));
+            }
+
+            let override_material = node
+                .material
+                .as_ref()
+                .map(|x| material(x, &format!("{field}.material")))
+                .transpose()?;
+            let mut built = match &node.object {
+                Some(object) => {
+                    let geometry = objects.get(object.get_ref()).ok_or_else(|| {
+                        (
+                            object.span(),
+                            format!("{field}.object: unknown object `{}`", object.get_ref()),
+                        )
+                    })?;
+                    Node::leaf(name, &node.transform, geometry.clone(), override_material)
+                }
+                None => {
+                    let mut group = Node::group(name, &node.transform);
+                    group.set_material_override(override_material);
+                    group
+                }
+            };
+            built.visible = node.visible;
+            build_nodes(
+                &node.children,
+                &format!("{field}.children"),
+                objects,
+                material,
+            )?
+            .into_iter()
+            .for_each(|x| built.add_child(x));
+            Ok(built)
+        })
+        .collect()
+}
+
+// #####################################
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        path::{Path, PathBuf},
+        sync::Arc,
+    };
+
+    use glam::{Vec3A, Vec4};
+
+    use crate::{
+        entity::{
+            actor::ActorTrait,
+            geometry::{Geometry, GeometryType, sphere::Sphere},
+            rendering::material::{DiffuseMaterial, MaterialBound, MaterialType},
+        },
+        rendering::{framebuffer::FrameBuffer, ray_emitter::Projection, renderer::Renderer},
+        scene_file::{SceneFile, SceneFileError, load, parse, to_string},
+    };
+
+    const SOURCE: &str = r#"
+[camera]
+position = [0.0, 0.0, -10.0]
+direction = [0.0, 0.0, 1.0]
+
+[light]
+position = [0.0, 10.0, 0.0]
+direction = [0.0, -1.0, 0.0]
+
+[render]
+width = 64
+height = 32
+
+[materials.red]
+type = "color"
+color = [1.0, 0.0, 0.0, 1.0]
+
+[[geometry]]
+type = "sphere"
+position = [0.0, 0.0, 0.0]
+radius = 5.0
+material = "red"
+"#;
+
+    fn print_error(source: &str) -> String {
+        match parse(source, Path::new("test.toml")) {
+            Err(error) => error.to_string(),
+            Ok(_) => panic!("the scene should not be valid"),
+        }
+    }
+
+    #[test]
+    fn test_success_parse() {
+        let scene_file = parse(SOURCE, Path::new("test.toml")).unwrap();
+        assert_eq!(scene_file.scene.renderables.len(), 1);
+        assert_eq!(scene_file.camera.get_resolution(), (64, 32));
+        assert_eq!(scene_file.camera.get_resolution(), (64, 32));
+        assert_eq!(scene_file.camera.get_projection(), Projection::default());
+
+        let source = SOURCE.replace(
+            "[light]",
+            "projection = { type = \"perspective\", fov = 45.0 }\n\n[light]",
+        );
+        let scene_file = parse(&source, Path::new("test.toml")).unwrap();
+        assert_eq!(
+            scene_file.camera.get_projection(),
+            Projection::Perspective { fov: 45. }
+        );
+    }
+
+    /// Get the names of the scene file's renderables.
+    fn names(scene_file: &SceneFile) -> Vec<&str> {
+        scene_file
+            .scene
+            .renderables
+            .iter()
+            .map(|x| x.get_name())
+            .collect()
+    }
+
+    /// Bundled scene, by file stem, with the checks specific to what it showcases.
+    type SceneCheck = (&'static str, fn(&SceneFile));
+
+    #[test]
+    fn test_success_load_scenes() {
+        let checks: &[SceneCheck] = &[
+            ("default", |x| assert_eq!(names(x), ["sphere", "plane"])),
+            ("turntable", |x| assert!(x.timeline.is_some())),
+            ("primitives", |x| {
+                assert_eq!(
+                    names(x),
+                    [
+                        "quad", "box", "cylinder", "cone", "disk", "instance", "instance"
+                    ]
+                )
+            }),
+            ("csg", |x| {
+                assert_eq!(names(x), ["quad", "csg", "instance", "csg"]);
+                assert!(matches!(
+                    &x.scene.renderables[1],
+                    GeometryType::Csg(csg) if csg.get_left().get_name() == "csg"
+                ));
+            }),
+            ("sdf", |x| {
+                assert_eq!(names(x), ["quad", "sdf", "sdf", "sdf"])
+            }),
+            ("terrain", |x| {
+                assert_eq!(names(x)[0], "heightfield");
+                assert!(matches!(
+                    &x.scene.renderables[0],
+                    GeometryType::Heightfield(heightfield)
+                        if heightfield.get_resolution() == (128, 128)
+                ));
+            }),
+            ("patches", |x| {
+                assert_eq!(names(x), ["quad", "patch", "patch"])
+            }),
+            ("curves", |x| {
+                assert_eq!(names(x).len(), 72);
+                assert!(names(x)[1..].iter().all(|x| *x == "curve"));
+            }),
+            ("implicit", |x| {
+                assert_eq!(names(x), ["quad", "metaballs", "algebraic", "algebraic"])
+            }),
+        ];
+        let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("../scenes");
+        let mut paths = std::fs::read_dir(&directory)
+            .unwrap()
+            .map(|x| x.unwrap().path())
+            .filter(|x| x.extension().is_some_and(|x| x == "toml"))
+            .collect::<Vec<PathBuf>>();
+        paths.sort();
+        assert!(
+            checks
+                .iter()
+                .all(|(stem, _)| paths.iter().any(|x| x.file_stem().unwrap() == *stem))
+        );
+
+        // Every bundled scene is saved as it is loaded, before the checks of its own.
+        paths.iter().for_each(|path| {
+            let scene_file = load(path).unwrap_or_else(|error| panic!("{error}"));
+            let saved = to_string(&scene_file).unwrap();
+            let reloaded = parse(&saved, Path::new("saved.toml")).unwrap();
+            assert_eq!(to_string(&reloaded).unwrap(), saved, "{}", path.display());
+            checks
+                .iter()
+                .filter(|(stem, _)| path.file_stem().unwrap() == *stem)
+                .for_each(|(_, check)| check(&scene_file));
+        });
+    }
+
+    #[test]
+    fn test_failure_load_terrain_scene() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../scenes/terrain.toml");
+
+        // Images are read relative to the scene file.
+        let source = std::fs::read_to_string(&path).unwrap().replace(
+            "{ type = \"noise\", resolution = 128, frequency = 5.0, octaves = 5, seed = 3 }",
+            "{ type = \"image\", path = \"heightmap.png\" }",
+        );
+        let error = parse(&source, Path::new("elsewhere/test.toml"))
+            .err()
+            .unwrap();
+        assert!(error.to_string().starts_with(
+            "elsewhere/test.toml:46:10: geometry[0].source: elsewhere/heightmap.png: "
+        ));
+        let source = std::fs::read_to_string(&path)
+            .unwrap()
+            .replace("size = [800.0, 120.0, 600.0]", "size = [800.0, 0.0, 600.0]");
+        assert_eq!(
+            parse(&source, Path::new("test.toml"))
+                .err()
+                .unwrap()
+                .to_string(),
+            "test.toml:41:1: geometry[0].size: must be finite and above zero along every axis"
+        );
+    }
+
+    #[test]
+    fn test_success_save_round_trip() {
+        let render = |scene_file: &SceneFile| {
+            let framebuffer = FrameBuffer::new(64, 32, false);
+            Renderer::new(scene_file.settings.clone()).render(
+                &scene_file.camera,
+                &scene_file.scene,
+                &scene_file.light,
+                &framebuffer,
+            );
+            (0..64 * 32)
+                .map(|x| framebuffer.get_color(x))
+                .collect::<Vec<Vec4>>()
+        };
+
+        let scene_file = parse(SOURCE, Path::new("test.toml")).unwrap();
+        let saved = to_string(&scene_file).unwrap();
+        let reloaded = parse(&saved, Path::new("saved.toml")).unwrap();
+
+        assert_eq!(to_string(&reloaded).unwrap(), saved);
+        assert_eq!(render(&reloaded), render(&scene_file));
+    }
+
+    #[test]
+    fn test_success_geometry_serde() {
+        let geometry = GeometryType::Sphere(
+            Sphere::new(
+                &Vec3A::new(1., 2., 3.),
+                4.,
+                &MaterialType::Diffuse(DiffuseMaterial::new(0.5)),
+            )
+            .unwrap(),
+        );
+        let serialized = toml::to_string(&geometry).unwrap();
+        let deserialized: GeometryType = toml::from_str(&serialized).unwrap();
+
+        assert_eq!(deserialized.get_position(), Vec3A::new(1., 2., 3.));
+        assert_eq!(toml::to_string(&deserialized).unwrap(), serialized);
+    }
+
+    #[test]
+    fn test_success_parse_instances() {
+        let source = format!(
+            "{}{}",
+            SOURCE,
+            r#"
+[materials.blue]
+type = "color"
+color = [0.0, 0.0, 1.0, 1.0]
+
+[objects.ball]
+type = "sphere"
+position = [0.0, 0.0, 0.0]
+radius = 1.0
+material = "red"
+
+[[geometry]]
+type = "instance"
+object = "ball"
+transform = { translation = [10.0, 0.0, 0.0], scale = [2.0, 1.0, 1.0] }
+
+[[geometry]]
+type = "instance"
+object = "ball"
+material = "blue"
+transform = { translation = [-10.0, 0.0, 0.0], rotation = [0.0, 0.0, 45.0] }
+
+[[geometry]]
+type = "sphere"
+position = [0.0, 0.0, 0.0]
+radius = 1.0
+material = "red"
+transform = { scale = [1.0, 3.0, 1.0] }
+"#
+        );
+        let scene_file = parse(&source, Path::new("test.toml")).unwrap();
+        let renderables = &scene_file.scene.renderables;
+        assert_eq!(renderables.len(), 4);
+        let [
+            _,
+            GeometryType::Instance(first),
+            GeometryType::Instance(second),
+            stretched,
+        ] = &renderables[..]
+        else {
+            panic!("the geometries should be instances");
+        };
+        assert!(Arc::ptr_eq(first.get_geometry(), second.get_geometry()));
+        assert_eq!(first.get_position(), Vec3A::new(10., 0., 0.));
+        assert_eq!(
+            second.get_material().get_base_color(),
+            Some(Vec4::new(0., 0., 1., 1.))
+        );
+        assert_eq!(stretched.get_bounds(0., 0.).max, Vec3A::new(1., 3., 1.));
+
+        let saved = to_string(&scene_file).unwrap();
+        let reloaded = parse(&saved, Path::new("saved.toml")).unwrap();
+        assert_eq!(to_string(&reloaded).unwrap(), saved);
+        assert!(saved.contains("[objects.object_0]"));
+    }
+
+    const NODES: &str = r#"
+[objects.wheel]
+type = "sphere"
+position = [0.0, 0.0, 0.0]
+radius = 1.0
+material = "red"
+
+[[nodes]]
+name = "car"
+transform = { translation = [0.0, 0.0, 10.0], scale = [2.0, 2.0, 2.0] }
+
+[[nodes.children]]
+name = "front"
+object = "wheel"
+transform = { translation = [1.0, 0.0, 0.0] }
+
+[[nodes.children]]
+name = "back"
+object = "wheel"
+visible = false
+transform = { translation = [-1.0, 0.0, 0.0] }
+"#;
+
+    #[test]
+    fn test_success_parse_nodes() {
+        let source = format!("{}{}", SOURCE, NODES);
+        let mut scene_file = parse(&source, Path::new("test.toml")).unwrap();
+        let scene = &mut scene_file.scene;
+        assert_eq!(scene.get_graph_range(), 1..2);
+        assert_eq!(scene.renderables[1].get_position(), Vec3A::new(2., 0., 10.));
+
+        scene.graph.find_mut("car/back").unwrap().visible = true;
+        scene.graph.find_mut("car").unwrap().transform.translation.z = 20.;
+        scene.flatten_graph();
+        assert_eq!(scene.get_graph_range(), 1..3);
+        assert_eq!(
+            scene.renderables[2].get_position(),
+            Vec3A::new(-2., 0., 20.)
+        );
+
+        let saved = to_string(&scene_file).unwrap();
+        let reloaded = parse(&saved, Path::new("saved.toml")).unwrap();
+        assert_eq!(reloaded.scene.renderables.len(), 3);
+        assert_eq!(to_string(&reloaded).unwrap(), saved);
+    }
+
+    #[test]
+    fn test_failure_parse() {
+        assert_eq!(
+            print_error(&SOURCE.replace("material = \"red\"", "material = \"blue\"")),
+            "test.toml:22:12: geometry[0].material: unknown material `blue`"
+        );
+        assert_eq!(
+            print_error(&SOURCE.replace("radius = 5.0", "")),
+            "test.toml:18:1: missing field `radius`"
+        );
+        assert_eq!(
+            print_error(&SOURCE.replace("[0.0, 0.0, -10.0]", "[0.0, -10.0]")),
+            "test.toml:3:12: invalid length 2, expected a sequence of 3 f32 values"
+        );
+        assert_eq!(
+            print_error(&SOURCE.replace(
+                "height = 32",
+                "height = 32\nfilter = { kind = \"tent\", radius = 0.0 }"
+            )),
+            "test.toml:13:10: the filter's radius must be finite and above zero"
+        );
+        assert_eq!(
+            print_error(&SOURCE.replace("width = 64", "width = 0")),
+            "test.toml:11:9: render.width: must be above zero"
+        );
+        assert_eq!(
+            print_error(&SOURCE.replace("height = 32", "height = 32\nsamples_per_pixel = 0")),
+            "test.toml:13:21: render.samples_per_pixel: must be above zero"
+        );
+        assert_eq!(
+            print_error(&SOURCE.replace("[0.0, 0.0, 1.0]", "[0.0, 0.0, 0.0]")),
+            "test.toml:4:13: camera.direction: must be finite and non-zero"
+        );
+        assert_eq!(
+            print_error(&SOURCE.replace("[0.0, -1.0, 0.0]", "[0.0, 0.0, 0.0]")),
+            "test.toml:8:13: light.direction: must be finite and non-zero"
+        );
+        assert_eq!(
+            print_error(&SOURCE.replace(
+                "height = 32",
+                "height = 32\ndenoise = { normal_sigma = -1.0 }"
+            )),
+            "test.toml:13:11: the sigmas must be finite and above zero"
+        );
+        assert_eq!(
+            print_error(&SOURCE.replace(
+                "type = \"sphere\"\nposition = [0.0, 0.0, 0.0]\nradius = 5.0",
+                "type = \"instance\"\nobject = \"ball\""
+            )),
+            "test.toml:20:10: geometry[0].object: unknown object `ball`"
+        );
+        assert_eq!(
+            print_error(&SOURCE.replace("material = \"red\"", "")),
+            "test.toml:18:1: geometry[0]: missing material"
+        );
+        assert_eq!(
+            print_error(&SOURCE.replace("radius = 5.0", "radius = -1.0")),
+            "test.toml:18:1: geometry[0]: the radius must be finite and above zero"
+        );
+        assert_eq!(
+            print_error(&SOURCE.replace(
+                "type = \"sphere\"\nposition = [0.0, 0.0, 0.0]\nradius = 5.0",
+                "type = \"quad\"\nposition = [0.0, 0.0, 0.0]\nedge_u = [1.0, 0.0, 0.0]\nedge_v = [2.0, 0.0, 0.0]"
+            )),
+            "test.toml:18:1: geometry[0]: the edges must be non-zero and not parallel"
+        );
+        assert_eq!(
+            print_error(&SOURCE.replace("[0.0, -1.0, 0.0]", "[0.0, -1.0, 0.0]\nradius = 0.0")),
+            "test.toml:9:10: light.radius: the radius must be finite and above zero"
+        );
+        assert_eq!(
+            print_error(&SOURCE.replace(
+                "type = \"sphere\"\nposition = [0.0, 0.0, 0.0]\nradius = 5.0",
+                "type = \"instance\""
+            )),
+            "test.toml:18:1: geometry[0]: missing object"
+        );
+        assert_eq!(
+            print_error(&SOURCE.replace(
+                "radius = 5.0",
+                "radius = 5.0\ntransform = { scale = [1.0, 0.0, 1.0] }"
+            )),
+            "test.toml:22:13: the scale must be finite and non-zero along every axis"
+        );
+        assert_eq!(
+            print_error(&format!(
+                "{}{}",
+                SOURCE,
+                NODES.replace("\"back\"", "\"front\"")
+            )),
+            "test.toml:40:8: nodes[0].children[1].name: duplicate name `front` among the node's siblings"
+        );
+        let csg = "[[geometry]]\ntype = \"csg\"\noperation = \"union\"\nmaterial = \"red\"\n\n\
+            [[geometry.operands]]\ntype = \"sphere\"\nposition = [0.0, 0.0, 0.0]\nradius = 1.0\n";
+        assert_eq!(
+            print_error(&format!("{SOURCE}{csg}")),
+            "test.toml:28:1: geometry[1].operands: a csg combines at least two operands"
+        );
+        assert_eq!(
+            print_error(&format!(
+                "{SOURCE}{csg}\n[[geometry.operands]]\ntype = \"plane\"\n\
+                position = [0.0, 0.0, 0.0]\nnormal = [0.0, 1.0, 0.0]\n"
+            )),
+            "test.toml:33:1: geometry[1].operands[1]: a plane is not a closed solid"
+        );
+        assert_eq!(
+            print_error(&format!(
+                "{SOURCE}\n[[geometry]]\ntype = \"metaballs\"\nposition = [0.0, 0.0, 0.0]\n\
+                balls = [{{ center = [0.0, 0.0, 0.0], radius = 1.0 }}]\nthreshold = 0.0\nmaterial = \"red\"\n"
+            )),
+            "test.toml:24:1: geometry[1]: the threshold must be above zero"
+        );
+        assert_eq!(
+            print_error(&format!(
+                "{SOURCE}\n[[geometry]]\ntype = \"sdf\"\nposition = [0.0, 0.0, 0.0]\nmaterial = \"red\"\n\
+                tracing = {{ step_factor = 2.0 }}\nshape = {{ type = \"sphere\", radius = 1.0 }}\n"
+            )),
+            "test.toml:24:1: geometry[1]: the tracing's step_factor must be above zero and at most 1"
+        );
+        assert!(matches!(
+            load(Path::new("missing.toml")),
+            Err(SceneFileError::Io { .. })
+        ));
+    }
+}
@@ -0,0 +1,397 @@
+use std::ops::Range;
+
+use toml::Value;
+
+use crate::{
+    animation::{AnimatedValue, Track},
+    scene_file::{
+        SceneFile, SceneFileError, build_static,
+        description::{AnimationDescription, SceneDescription},
+    },
+};
+
+/// Animation of a scene file, rebuilding the scene at any time out of its description, whose tracked values are
+/// replaced by the tracks' values at that time.
+#[derive(Clone)]
+pub struct Timeline {
+    description: SceneDescription,
+    animation: AnimationDescription,
+    tracks: Vec<(String, Track<AnimatedValue>)>,
+}
+
+impl Timeline {
+    /// Check the described animation against the rest of the description, locating the faulty track if any.
+    pub(crate) fn new(
+        mut description: SceneDescription,
+    ) -> Result<Option<Self>, (Range<usize>, String)> {
+        let Some(animation) = description.animation.take() else {
+            return Ok(None);
+        };
+        let frame_rate = *animation.frame_rate.get_ref();
+        if !frame_rate.is_finite() || frame_rate <= 0. {
+            return Err((
+                animation.frame_rate.span(),
+                "animation.frame_rate: must be finite and positive".to_string(),
+            ));
+        }
+
+        let tracks = animation
+            .tracks
+            .iter()
+            .enumerate()
+            .map(|(index, track)| {
+                let error = |message: &str| {
+                    (
+                        track.target.span(),
+                        format!("animation.tracks[{}]: {}", index, message),
+                    )
+                };
+                let Some(first) = track.keyframes.first() else {
+                    return Err(error("no keyframe"));
+                };
+                // The other errors are located at the first faulty keyframe.
+                let size = first.get_ref().value.get_components().len();
+                let faulty = track
+                    .keyframes
+                    .iter()
+                    .enumerate()
+                    .find_map(|(number, keyframe)| {
+                        let x = keyframe.get_ref();
+                        let previous = number.checked_sub(1).map(|y| track.keyframes[y].get_ref());
+                        let message = if !x.time.is_finite() {
+                            "must be at a finite time"
+                        } else if previous.is_some_and(|y| y.time >= x.time) {
+                            "the keyframes are not sorted by time"
+                        } else if x.value.get_components().len() != size {
+                            "the keyframes' values have different sizes"
+                        } else {
+                            return None;
+                        };
+                        Some((
+                            keyframe.span(),
+                            format!("animation.tracks[{index}].keyframes[{number}]: {message}"),
+                        ))
+                    });
+                if let Some(error) = faulty {
+                    return Err(error);
+                }
+                Ok((
+                    track.target.get_ref().clone(),
+                    Track::new(
+                        track.interpolation,
+                        track
+                            .keyframes
+                            .iter()
+                            .map(|x| x.get_ref().clone())
+                            .collect(),
+                    ),
+                ))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let timeline = Self {
+            description,
+            animation,
+            tracks,
+        };
+        let mut value = timeline.get_base_value();
+        timeline
+            .tracks
+            .iter()
+            .zip(timeline.animation.tracks.iter())
+            .enumerate()
+            .try_for_each(|(index, ((target, track), description))| {
+                set(&mut value, target, track.evaluate(0.).unwrap()).map_err(|message| {
+                    (
+                        description.target.span(),
+                        format!("animation.tracks[{}].target: {}", index, message),
+                    )
+                })
+            })?;
+        Ok(Some(timeline))
+    }
+
+    pub(crate) fn get_animation(&self) -> &AnimationDescription {
+        &self.animation
+    }
+
+    pub fn get_frame_rate(&self) -> f32 {
+        *self.animation.frame_rate.get_ref()
+    }
+
+    /// Get the animation's duration in seconds, the time of its last keyframe unless specified.
+    pub fn get_duration(&self) -> f32 {
+        self.animation.duration.unwrap_or_else(|| {
+            self.tracks
+                .iter()
+                .filter_map(|x| x.1.get_end())
+                .fold(0., f32::max)
+        })
+    }
+
+    /// Get the number of frames sampling the animation, one at the start of each frame interval, so a looping
+    /// animation does not show its first image twice. There is at least one frame.
+    pub fn get_frame_count(&self) -> u32 {
+        ((self.get_duration() * self.get_frame_rate()).ceil() as u32).max(1)
+    }
+
+    pub fn get_frame_time(&self, frame: u32) -> f32 {
+        frame as f32 / self.get_frame_rate()
+    }
+
+    /// Build the scene as it is at the given time, in seconds.
+    /// The built scene file is a still, without any timeline.
+    pub fn evaluate(&self, time: f32) -> Result<SceneFile, SceneFileError> {
+        self.build(time)
+            .map_err(|(_, message)| SceneFileError::Build { message })
+    }
+
+    /// Build the scene as it is at the given time, locating the errors at the target of the first track whose value
+    /// makes the scene invalid, the file's own values being assumed valid.
+    pub(crate) fn build(&self, time: f32) -> Result<SceneFile, (Range<usize>, String)> {
+        let build = |count: usize| {
+            self.evaluate_tracks(time, count)
+                .and_then(|x| build_static(x).map_err(|(_, message)| message))
+        };
+        build(self.tracks.len()).map_err(|message| {
+            let index = (1..self.tracks.len())
+                .find(|count| build(*count).is_err())
+                .unwrap_or(self.tracks.len())
+                .saturating_sub(1);
+            let span = self
+                .animation
+                .tracks
+                .get(index)
+                .map_or(0..0, |x| x.target.span());
+            (span, format!("animation.tracks[{}]: {}", index, message))
+        })
+    }
+
+    pub(crate) fn evaluate_description(&self, time: f32) -> Result<SceneDescription, String> {
+        self.evaluate_tracks(time, self.tracks.len())
+    }
+
+    /// Get the description at the given time, only applying the given number of tracks.
+    fn evaluate_tracks(&self, time: f32, count: usize) -> Result<SceneDescription, String> {
+        let mut value = self.get_base_value();
+        self.tracks[..count]
+            .iter()
+            .filter_map(|(target, track)| track.evaluate(time).map(|x| (target, x)))
+            .try_for_each(|(target, x)| set(&mut value, target, x))?;
+
+        // The round trip through a string keeps the materials' references deserializable.
+        let source = toml::to_string(&value).map_err(|error| error.to_string())?;
+        toml::from_str(&source).map_err(|error| error.message().to_string())
+    }
+
+    fn get_base_value(&self) -> Value {
+        Value::try_from(&self.description).unwrap()
+    }
+}
+
+/// Replace the number or vector found at the given path (made of table keys and array indices separated by dots)
+/// by the given value, which must have the same size.
+fn set(root: &mut Value, path: &str, value: AnimatedValue) -> Result<(), String> {
+    let target = path.split('.').try_fold(root, |value, key| match value {
+        Value::Table(table) => table.get_mut(key),
+        Value::Array(array) => key.parse::<usize>().ok().and_then(|x| array.get_mut(x)),
+        _ => None,
+    });
+    let Some(target) = target else {
+        return Err(format!("unknown target `{}`", path));
+    };
+
+    let size = match target {
+        Value::Float(_) => 1,
+        Value::Array(array) if array.iter().all(|x| x.is_float()) => array.len(),
+        _ => return Err(format!("`{}` is not a number or a vector", path)),
+    };
+    match (value, size) {
+        (AnimatedValue::Scalar(x), 1) => *target = Value::Float(x as f64),
+        (AnimatedValue::Vector(x), size)
+            if x.len() == size && !matches!(target, Value::Float(_)) =>
+        {
+            *target = Value::Array(x.into_iter().map(|x| Value::Float(x as f64)).collect())
+        }
+        _ => {
+            return Err(format!(
+                "`{}` expects {} value{}",
+                path,
+                size,
+                if size > 1 { "s" } else { "" }
+            ));
+        }
+    }
+    Ok(())
+}
+
+// #####################################
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use glam::{Vec3A, Vec4};
+
+    use crate::{
+        entity::{actor::ActorTrait, rendering::material::MaterialBound},
+        rendering::ray_emitter::Projection,
+        scene_file::{load, parse, to_string},
+    };
+
+    const SOURCE: &str = r#"
+[camera]
+position = [0.0, 0.0, -10.0]
+direction = [0.0, 0.0, 1.0]
+projection = { type = "perspective", fov = 40.0 }
+
+[light]
+position = [0.0, 10.0, 0.0]
+direction = [0.0, -1.0, 0.0]
+
+[materials.red]
+type = "color"
+color = [1.0, 0.0, 0.0, 1.0]
+
+[[geometry]]
+type = "sphere"
+position = [0.0, 0.0, 0.0]
+radius = 5.0
+material = "red"
+
+[animation]
+frame_rate = 10.0
+
+[[animation.tracks]]
+target = "geometry.0.position"
+keyframes = [
+    { time = 0.0, value = [0.0, 0.0, 0.0] },
+    { time = 1.0, value = [10.0, 0.0, 0.0] },
+]
+
+[[animation.tracks]]
+target = "camera.projection.fov"
+interpolation = "step"
+keyframes = [{ time = 0.0, value = 40.0 }, { time = 0.5, value = 80.0 }]
+
+[[animation.tracks]]
+target = "materials.red.color"
+interpolation = "cubic"
+keyframes = [{ time = 0.0, value = [1.0, 0.0, 0.0, 1.0] }, { time = 1.0, value = [0.0, 0.0, 1.0, 1.0] }]
+"#;
+
+    fn print_error(source: &str) -> String {
+        match parse(source, Path::new("test.toml")) {
+            Err(error) => error.to_string(),
+            Ok(_) => panic!("the scene should not be valid"),
+        }
+    }
+
+    #[test]
+    fn test_success_evaluate() {
+        let scene_file = parse(SOURCE, Path::new("test.toml")).unwrap();
+        let timeline = scene_file.timeline.as_ref().unwrap();
+        assert_eq!(timeline.get_duration(), 1.);
+        assert_eq!(timeline.get_frame_count(), 10);
+        assert_eq!(timeline.get_frame_time(5), 0.5);
+
+        let frame = timeline.evaluate(0.5).unwrap();
+        assert!(frame.timeline.is_none());
+        assert_eq!(
+            frame.scene.renderables[0].get_position(),
+            Vec3A::new(5., 0., 0.)
+        );
+        assert_eq!(
+            frame.camera.get_projection(),
+            Projection::Perspective { fov: 80. }
+        );
+        let frame = timeline.evaluate(2.).unwrap();
+        assert_eq!(
+            frame.scene.renderables[0]
+                .get_material()
+                .get_base_color()
+                .unwrap(),
+            Vec4::new(0., 0., 1., 1.)
+        );
+
+        let saved = to_string(&scene_file).unwrap();
+        let reloaded = parse(&saved, Path::new("saved.toml")).unwrap();
+        let frame = reloaded.timeline.unwrap().evaluate(0.5).unwrap();
+        assert_eq!(
+            frame.scene.renderables[0].get_position(),
+            Vec3A::new(5., 0., 0.)
+        );
+    }
+
+    #[test]
+    fn test_success_load_turntable_scene() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../scenes/turntable.toml");
+        let timeline = load(&path).unwrap().timeline.unwrap();
+        assert_eq!(timeline.get_frame_count(), 48);
+
+        let frame = timeline.evaluate(1.).unwrap();
+        assert!((frame.camera.get_position() - Vec3A::new(-300., 150., 0.)).length() < 1e-3);
+    }
+
+    #[test]
+    fn test_failure_evaluate() {
+        assert_eq!(
+            print_error(&SOURCE.replace("geometry.0.position", "geometry.1.position")),
+            "test.toml:25:10: animation.tracks[0].target: unknown target `geometry.1.position`"
+        );
+        assert_eq!(
+            print_error(&SOURCE.replace("geometry.0.position", "geometry.0.radius")),
+            "test.toml:25:10: animation.tracks[0].target: `geometry.0.radius` expects 1 value"
+        );
+        assert_eq!(
+            print_error(&SOURCE.replace("geometry.0.position", "geometry.0.material")),
+            "test.toml:25:10: animation.tracks[0].target: `geometry.0.material` is not a number or a vector"
+        );
+        assert_eq!(
+            print_error(&SOURCE.replace("time = 0.5", "time = 0.0")),
+            "test.toml:34:44: animation.tracks[1].keyframes[1]: the keyframes are not sorted by time"
+        );
+        assert_eq!(
+            print_error(&SOURCE.replace("time = 0.5", "time = nan")),
+            "test.toml:34:44: animation.tracks[1].keyframes[1]: must be at a finite time"
+        );
+        assert_eq!(
+            print_error(&SOURCE.replace("value = 80.0", "value = [80.0, 0.0]")),
+            "test.toml:34:44: animation.tracks[1].keyframes[1]: the keyframes' values have different sizes"
+        );
+        assert_eq!(
+            print_error(&SOURCE.replace("frame_rate = 10.0", "frame_rate = 0.0")),
+            "test.toml:22:14: animation.frame_rate: must be finite and positive"
+        );
+        // The file's own errors are located in the file, and the animated values' ones at their track.
+        assert_eq!(
+            print_error(&SOURCE.replace("material = \"red\"", "material = \"blue\"")),
+            "test.toml:19:12: geometry[0].material: unknown material `blue`"
+        );
+        assert_eq!(
+            print_error(
+                &SOURCE
+                    .replace("camera.projection.fov", "render.filter.radius")
+                    .replace("value = 40.0", "value = 0.0")
+            ),
+            "test.toml:32:10: animation.tracks[1]: the filter's radius must be finite and above zero"
+        );
+        let scene_file = parse(
+            &SOURCE
+                .replace("camera.projection.fov", "render.filter.radius")
+                .replace("value = 80.0", "value = -1.0"),
+            Path::new("test.toml"),
+        )
+        .unwrap();
+        assert_eq!(
+            scene_file
+                .timeline
+                .unwrap()
+                .evaluate(0.5)
+                .err()
+                .unwrap()
+                .to_string(),
+            "animation.tracks[1]: the filter's radius must be finite and above zero"
+        );
+    }
+}
@@ -0,0 +1,339 @@
+use glam::Vec3A;
+use sdl2::{event::Event, keyboard::Keycode};
+
+use tracer_core::{
+    entity::{
+        actor::{ActorTrait, DirectionalActorTrait},
+        geometry::ray::{Ray, RayType},
+        scene::Scene,
+    },
+    rendering::ray_emitter::{Projection, RayEmitter},
+};
+
+const LOOK_SENSITIVITY: f32 = 0.005;
+const PITCH_LIMIT: f32 = 89. * std::f32::consts::PI / 180.;
+const DEFAULT_SPEED: f32 = 10.;
+const DEFAULT_FOV: f32 = 60.;
+const DEFAULT_ORBIT_DISTANCE: f32 = 100.;
+
+/// Structure turning the window's keyboard and mouse events into camera moves.
+/// In fly mode, WASD moves along the view and QE along the world's vertical axis, while dragging with the left button
+/// turns the view. In orbit mode, dragging turns around a target set in front of the camera, W and S get closer to or
+/// further from it and the other keys pan both the camera and the target.
+/// The scroll wheel changes the field of view (or the zoom of an orthographic camera), Page Up and Page Down the speed,
+/// O toggles the orbit mode, P the projection, and R restores the scene's camera.
+pub struct CameraController {
+    home: (Vec3A, Vec3A, Projection),
+    position: Vec3A,
+    yaw: f32,
+    pitch: f32,
+    projection: Projection,
+    speed: f32,
+    orbit_target: Option<Vec3A>,
+}
+
+impl CameraController {
+    pub fn new(camera: &RayEmitter) -> Self {
+        let home = (
+            camera.get_position(),
+            camera.get_direction(),
+            camera.get_projection(),
+        );
+        let mut controller = Self {
+            home,
+            position: Vec3A::ZERO,
+            yaw: 0.,
+            pitch: 0.,
+            projection: Projection::default(),
+            speed: DEFAULT_SPEED,
+            orbit_target: None,
+        };
+        controller.reset();
+        controller
+    }
+
+    /// Restore the camera the controller was created from, leaving the orbit mode.
+    pub fn reset(&mut self) {
+        let (position, direction, projection) = self.home;
+        let direction = direction.normalize();
+        self.position = position;
+        self.yaw = direction.x.atan2(direction.z);
+        self.pitch = direction.y.clamp(-1., 1.).asin();
+        self.projection = projection;
+        self.speed = DEFAULT_SPEED;
+        self.orbit_target = None;
+    }
+
+    pub fn get_direction(&self) -> Vec3A {
+        Vec3A::new(
+            self.pitch.cos() * self.yaw.sin(),
+            self.pitch.sin(),
+            self.pitch.cos() * self.yaw.cos(),
+        )
+    }
+
+    pub fn is_orbiting(&self) -> bool {
+        self.orbit_target.is_some()
+    }
+
+    /// Move the given camera to the controller's current view.
+    pub fn apply(&self, camera: &mut RayEmitter) {
+        if camera.get_projection() != self.projection {
+            camera.set_projection(self.projection);
+        }
+        camera.set_view(self.position, self.get_direction());
+    }
+
+    /// Tell whether an event is handled by the controller, without handling it.
+    pub fn is_navigation_event(event: &Event) -> bool {
+        match event {
+            Event::KeyDown {
+                keycode: Some(keycode),
+                ..
+            } => matches!(
+                *keycode,
+                Keycode::W
+                    | Keycode::A
+                    | Keycode::S
+                    | Keycode::D
+                    | Keycode::Q
+                    | Keycode::E
+                    | Keycode::O
+                    | Keycode::P
+                    | Keycode::R
+                    | Keycode::PageUp
+                    | Keycode::PageDown
+            ),
+            Event::MouseMotion { mousestate, .. } => mousestate.left(),
+            Event::MouseWheel { .. } => true,
+            _ => false,
+        }
+    }
+
+    /// React to a window event, returning true if the view changed.
+    /// The scene is used to place the orbit target on the surface in front of the camera.
+    pub fn handle_event(&mut self, event: &Event, scene: &Scene) -> bool {
+        if !Self::is_navigation_event(event) {
+            return false;
+        }
+
+        match event {
+            Event::KeyDown {
+                keycode: Some(keycode),
+                ..
+            } => match *keycode {
+                Keycode::R => self.reset(),
+                Keycode::O => self.toggle_orbit(scene),
+                Keycode::P => {
+                    self.projection = match self.projection {
+                        Projection::Orthographic { .. } => {
+                            Projection::Perspective { fov: DEFAULT_FOV }
+                        }
+                        Projection::Perspective { .. } => Projection::default(),
+                    }
+                }
+                Keycode::PageUp => self.speed *= 2.,
+                Keycode::PageDown => self.speed /= 2.,
+                keycode => self.translate(keycode),
+            },
+            Event::MouseMotion { xrel, yrel, .. } => self.rotate(
+                -*xrel as f32 * LOOK_SENSITIVITY,
+                -*yrel as f32 * LOOK_SENSITIVITY,
+            ),
+            Event::MouseWheel { y, .. } => {
+                self.projection = match self.projection {
+                    Projection::Orthographic { pixel_size } => Projection::Orthographic {
+                        pixel_size: pixel_size * 0.9_f32.powi(*y),
+                    },
+                    Projection::Perspective { fov } => Projection::Perspective {
+                        fov: (fov - 5. * *y as f32).clamp(10., 150.),
+                    },
+                }
+            }
+            _ => {}
+        }
+        true
+    }
+
+    fn toggle_orbit(&mut self, scene: &Scene) {
+        self.orbit_target = match self.orbit_target {
+            Some(_) => None,
+            None => {
+                let direction = self.get_direction();
+                let distance = scene
+                    .closest_hit(&Ray::new(&self.position, &direction), &RayType::Camera)
+                    .map_or(DEFAULT_ORBIT_DISTANCE, |x| x.distance);
+                Some(self.position + direction * distance)
+            }
+        };
+    }
+
+    /// Turn the view by the given angles (in radians), around the orbit target if any.
+    fn rotate(&mut self, yaw: f32, pitch: f32) {
+        self.yaw += yaw;
+        self.pitch = (self.pitch + pitch).clamp(-PITCH_LIMIT, PITCH_LIMIT);
+
+        if let Some(target) = self.orbit_target {
+            let distance = self.position.distance(target);
+            self.position = target - self.get_direction() * distance;
+        }
+    }
+
+    fn translate(&mut self, keycode: Keycode) {
+        let forward = self.get_direction();
+        let right = forward.cross(Vec3A::Y).normalize();
+
+        match (keycode, self.orbit_target) {
+            (Keycode::W, Some(target)) => {
+                let distance = self.position.distance(target);
+                self.position += forward * self.speed.min(distance - 1.).max(0.);
+            }
+            (Keycode::W, None) => self.position += forward * self.speed,
+            (Keycode::S, _) => self.position -= forward * self.speed,
+            (keycode, _) => {
+                let offset = match keycode {
+                    Keycode::A => -right,
+                    Keycode::D => right,
+                    Keycode::Q => -Vec3A::Y,
+                    Keycode::E => Vec3A::Y,
+                    _ => Vec3A::ZERO,
+                } * self.speed;
+                self.position += offset;
+                self.orbit_target = self.orbit_target.map(|x| x + offset);
+            }
+        }
+    }
+}
+
+// #####################################
+
+#[cfg(test)]
+mod tests {
+    use glam::{Vec3A, Vec4};
+    use sdl2::{
+        event::Event,
+        keyboard::{Keycode, Mod},
+        mouse::MouseState,
+    };
+
+    use tracer_core::{
+        entity::{
+            actor::{ActorTrait, DirectionalActorTrait},
+            geometry::{GeometryType, sphere::Sphere},
+            rendering::material::{ColorMaterial, MaterialType},
+            scene::Scene,
+        },
+        rendering::ray_emitter::{Projection, RayEmitter},
+    };
+
+    use crate::camera::CameraController;
+
+    fn key(keycode: Keycode) -> Event {
+        Event::KeyDown {
+            timestamp: 0,
+            window_id: 0,
+            keycode: Some(keycode),
+            scancode: None,
+            keymod: Mod::NOMOD,
+            repeat: false,
+        }
+    }
+
+    fn drag(xrel: i32, yrel: i32) -> Event {
+        Event::MouseMotion {
+            timestamp: 0,
+            window_id: 0,
+            which: 0,
+            mousestate: MouseState::from_sdl_state(1),
+            x: 0,
+            y: 0,
+            xrel,
+            yrel,
+        }
+    }
+
+    fn scene() -> Scene {
+        let mut scene = Scene::new(&Vec4::ZERO);
+        scene.renderables.push(GeometryType::Sphere(
+            Sphere::new(
+                &Vec3A::new(0., 0., 50.),
+                10.,
+                &MaterialType::Color(ColorMaterial::new(Vec4::ONE)),
+            )
+            .unwrap(),
+        ));
+        scene
+    }
+
+    #[test]
+    fn test_success_handle_event() {
+        let scene = scene();
+        let mut camera = RayEmitter::new(Vec3A::ZERO, Vec3A::new(0., 0., 2.), 8, 8);
+        let mut controller = CameraController::new(&camera);
+        assert!((controller.get_direction() - Vec3A::Z).length() < 1e-6);
+
+        assert!(controller.handle_event(&key(Keycode::W), &scene));
+        assert!(controller.handle_event(&key(Keycode::D), &scene));
+        controller.apply(&mut camera);
+        assert!((camera.get_position() - Vec3A::new(-10., 0., 10.)).length() < 1e-5);
+
+        assert!(controller.handle_event(&drag(100, 0), &scene));
+        assert!(controller.get_direction().x < 0.);
+        assert!(controller.handle_event(&key(Keycode::P), &scene));
+
+        controller.apply(&mut camera);
+        assert_eq!(camera.get_direction(), controller.get_direction());
+        assert!(matches!(
+            camera.get_projection(),
+            Projection::Perspective { .. }
+        ));
+
+        assert!(controller.handle_event(&key(Keycode::R), &scene));
+        controller.apply(&mut camera);
+        assert_eq!(camera.get_position(), Vec3A::ZERO);
+        assert_eq!(controller.get_direction(), Vec3A::Z);
+    }
+
+    #[test]
+    fn test_success_orbit() {
+        let scene = scene();
+        let mut camera = RayEmitter::new(Vec3A::ZERO, Vec3A::Z, 8, 8);
+        let mut controller = CameraController::new(&camera);
+
+        controller.handle_event(&key(Keycode::O), &scene);
+        assert!(controller.is_orbiting());
+
+        let target = Vec3A::new(0., 0., 40.);
+        controller.handle_event(&drag(300, -100), &scene);
+        controller.apply(&mut camera);
+        assert!((camera.get_position().distance(target) - 40.).abs() < 1e-3);
+        let aim = camera.get_position() + camera.get_direction() * 40.;
+        assert!(aim.distance(target) < 1e-3);
+
+        controller.handle_event(&key(Keycode::O), &scene);
+        assert!(!controller.is_orbiting());
+    }
+
+    #[test]
+    fn test_failure_handle_event_ignored() {
+        let scene = scene();
+        let mut camera = RayEmitter::new(Vec3A::ZERO, Vec3A::Z, 8, 8);
+        let mut controller = CameraController::new(&camera);
+
+        let hover = Event::MouseMotion {
+            timestamp: 0,
+            window_id: 0,
+            which: 0,
+            mousestate: MouseState::from_sdl_state(0),
+            x: 0,
+            y: 0,
+            xrel: 10,
+            yrel: 10,
+        };
+        assert!(!controller.handle_event(&hover, &scene));
+        assert!(!controller.handle_event(&key(Keycode::Tab), &scene));
+        controller.apply(&mut camera);
+        assert_eq!(camera.get_position(), Vec3A::ZERO);
+        assert_eq!(controller.get_direction(), Vec3A::Z);
+    }
+}
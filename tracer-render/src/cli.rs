@@ -0,0 +1,178 @@
+use std::path::PathBuf;
+
+use clap::{Parser, ValueEnum};
+
+use tracer_core::{rendering::integrator::Integrator, scene_file::SceneFile};
+
+/// Command line options, overriding the scene file's values when set.
+#[derive(Parser)]
+#[command(
+    version,
+    about = "Render a scene file, either in a window or headless."
+)]
+pub struct Cli {
+    /// Scene file to render, reloaded on modification in windowed mode. The default one is relative to the working
+    /// directory, e.g. the repository's root.
+    #[arg(default_value = "scenes/default.toml")]
+    pub scene: PathBuf,
+
+    /// Width of the rendered image, in pixels.
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    pub width: Option<u32>,
+
+    /// Height of the rendered image, in pixels.
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    pub height: Option<u32>,
+
+    /// Samples per pixel accumulated by each frame.
+    #[arg(short, long = "spp", value_parser = clap::value_parser!(u32).range(1..))]
+    pub samples_per_pixel: Option<u32>,
+
+    /// Maximum number of bounces of the reflected rays.
+    #[arg(long)]
+    pub max_depth: Option<usize>,
+
+    /// Algorithm used to compute the color of the camera rays.
+    #[arg(short, long, value_enum)]
+    pub integrator: Option<IntegratorArg>,
+
+    /// PNG file the final image is written to.
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+
+    /// Render without opening any window.
+    #[arg(long)]
+    pub headless: bool,
+
+    /// Render every frame of the scene's animation without any window, to numbered PNG files named after the output
+    /// file, e.g. out_0000.png, out_0001.png...
+    #[arg(long, requires = "output")]
+    pub sequence: bool,
+
+    /// Number of rendering threads, defaulting to one per logical core.
+    #[arg(short = 'j', long)]
+    pub threads: Option<usize>,
+
+    /// Number of frames to render before exiting, defaulting to 1 when headless and unlimited otherwise.
+    #[arg(short, long, value_parser = clap::value_parser!(u32).range(1..))]
+    pub frames: Option<u32>,
+
+    /// Fraction of the window size rendered while the camera moves, 1 keeping the full resolution.
+    #[arg(long, default_value_t = 0.25, value_parser = parse_fraction)]
+    pub dynamic_resolution: f32,
+
+    /// Seed of the random sample positions.
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    /// Directory the viewer's screenshots are written to.
+    #[arg(long, default_value = ".")]
+    pub screenshot_dir: PathBuf,
+}
+
+/// Integrators selectable from the command line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum IntegratorArg {
+    Whitted,
+    AmbientOcclusion,
+    Normals,
+}
+
+impl From<IntegratorArg> for Integrator {
+    fn from(value: IntegratorArg) -> Self {
+        match value {
+            IntegratorArg::Whitted => Integrator::Whitted,
+            IntegratorArg::AmbientOcclusion => Integrator::AmbientOcclusion,
+            IntegratorArg::Normals => Integrator::Normals,
+        }
+    }
+}
+
+fn parse_fraction(value: &str) -> Result<f32, String> {
+    match value.parse::<f32>() {
+        Ok(x) if x > 0. && x <= 1. => Ok(x),
+        Ok(_) => Err("expected a value in ]0, 1]".to_string()),
+        Err(error) => Err(error.to_string()),
+    }
+}
+
+impl Cli {
+    /// Override the loaded scene file's values with the ones given on the command line.
+    pub fn apply(&self, scene_file: &mut SceneFile) {
+        let (width, height) = scene_file.camera.get_resolution();
+        if self.width.is_some() || self.height.is_some() {
+            scene_file
+                .camera
+                .set_resolution(self.width.unwrap_or(width), self.height.unwrap_or(height));
+        }
+
+        let settings = &mut scene_file.settings;
+        if let Some(samples_per_pixel) = self.samples_per_pixel {
+            settings.samples_per_pixel = samples_per_pixel;
+        }
+        if let Some(integrator) = self.integrator {
+            settings.integrator = integrator.into();
+        }
+        if let Some(seed) = self.seed {
+            settings.seed = seed;
+        }
+        if let Some(max_depth) = self.max_depth {
+            scene_file.scene.max_depth = max_depth;
+        }
+    }
+}
+
+// #####################################
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use clap::{CommandFactory, Parser};
+
+    use tracer_core::{rendering::integrator::Integrator, scene_file};
+
+    use crate::cli::Cli;
+
+    const DEFAULT_SCENE: &str = include_str!("../../scenes/default.toml");
+
+    #[test]
+    fn test_success_apply() {
+        Cli::command().debug_assert();
+
+        let cli = Cli::parse_from([
+            "tracer-render",
+            "--width",
+            "64",
+            "--spp",
+            "4",
+            "--integrator",
+            "ambient-occlusion",
+            "--max-depth",
+            "2",
+            "--headless",
+        ]);
+        assert_eq!(cli.scene, Path::new("scenes/default.toml"));
+        let mut scene_file = scene_file::parse(DEFAULT_SCENE, &cli.scene).unwrap();
+        cli.apply(&mut scene_file);
+
+        assert_eq!(scene_file.camera.get_resolution(), (64, 1000));
+        assert_eq!(scene_file.settings.samples_per_pixel, 4);
+        assert_eq!(scene_file.settings.integrator, Integrator::AmbientOcclusion);
+        assert_eq!(scene_file.scene.max_depth, 2);
+        assert!(cli.headless);
+    }
+
+    #[test]
+    fn test_failure_parse() {
+        assert!(Cli::try_parse_from(["tracer-render", "--sequence"]).is_err());
+        assert!(Cli::try_parse_from(["tracer-render", "--integrator", "path"]).is_err());
+        assert!(Cli::try_parse_from(["tracer-render", "--width", "-3"]).is_err());
+        assert!(Cli::try_parse_from(["tracer-render", "--width", "0"]).is_err());
+        assert!(Cli::try_parse_from(["tracer-render", "--height", "0"]).is_err());
+        assert!(Cli::try_parse_from(["tracer-render", "--spp", "0"]).is_err());
+        assert!(Cli::try_parse_from(["tracer-render", "--frames", "0"]).is_err());
+        assert!(Cli::try_parse_from(["tracer-render", "--dynamic-resolution", "0"]).is_err());
+        assert!(Cli::try_parse_from(["tracer-render", "--dynamic-resolution", "1.5"]).is_err());
+    }
+}
@@ -0,0 +1,120 @@
+use std::{
+    fs::File,
+    io::BufWriter,
+    path::{Path, PathBuf},
+    time::Instant,
+};
+
+use tracer_core::{
+    rendering::{
+        framebuffer::{Aov, FrameBuffer},
+        renderer::Renderer,
+    },
+    scene_file::SceneFile,
+};
+
+/// Render the given number of frames without any window, reporting each frame's duration on stderr.
+pub fn render(scene_file: &SceneFile, frames: u32) -> FrameBuffer {
+    let (width, height) = scene_file.camera.get_resolution();
+    let framebuffer = FrameBuffer::new(
+        width as usize,
+        height as usize,
+        scene_file.settings.denoise.is_some(),
+    );
+    let renderer = Renderer::new(scene_file.settings.clone());
+
+    (0..frames).for_each(|frame| {
+        let start = Instant::now();
+        renderer.render(
+            &scene_file.camera,
+            &scene_file.scene,
+            &scene_file.light,
+            &framebuffer,
+        );
+        eprintln!(
+            "frame {}: {:.1}ms",
+            frame + 1,
+            start.elapsed().as_secs_f64() * 1000.
+        );
+    });
+    framebuffer
+}
+
+/// Get the path of a sequence's frame, numbering the output file's name.
+pub fn get_frame_path(output: &Path, frame: u32) -> PathBuf {
+    let stem = output.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match output.extension() {
+        Some(extension) => format!("{}_{:04}.{}", stem, frame, extension.to_string_lossy()),
+        None => format!("{}_{:04}", stem, frame),
+    };
+    output.with_file_name(name)
+}
+
+/// Write the given pass of the framebuffer to an 8 bits RGBA PNG file.
+pub fn write_png(
+    framebuffer: &FrameBuffer,
+    aov: Aov,
+    path: &Path,
+) -> Result<(), png::EncodingError> {
+    let mut buffer = vec![0; framebuffer.get_width() * framebuffer.get_height() * 4];
+    framebuffer.write_rgba8(aov, &mut buffer);
+
+    let mut encoder = png::Encoder::new(
+        BufWriter::new(File::create(path)?),
+        framebuffer.get_width() as u32,
+        framebuffer.get_height() as u32,
+    );
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&buffer)?;
+    writer.finish()
+}
+
+// #####################################
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path};
+
+    use clap::Parser;
+    use tracer_core::{rendering::framebuffer::Aov, scene_file};
+
+    use crate::{
+        cli::Cli,
+        headless::{get_frame_path, render, write_png},
+    };
+
+    const DEFAULT_SCENE: &str = include_str!("../../scenes/default.toml");
+
+    #[test]
+    fn test_success_write_png() {
+        let cli = Cli::parse_from(["tracer-render", "--width", "48", "--height", "32"]);
+        let mut scene_file = scene_file::parse(DEFAULT_SCENE, &cli.scene).unwrap();
+        let path = std::env::temp_dir().join("tracer-render-test-headless.png");
+        cli.apply(&mut scene_file);
+
+        let framebuffer = render(&scene_file, 2);
+        assert_eq!(framebuffer.get_sample_count(), 2);
+        write_png(&framebuffer, Aov::Beauty, &path).unwrap();
+
+        let decoder = png::Decoder::new(std::io::BufReader::new(fs::File::open(&path).unwrap()));
+        let info = decoder.read_info().unwrap().info().clone();
+        assert_eq!((info.width, info.height), (48, 32));
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_success_get_frame_path() {
+        assert_eq!(
+            get_frame_path(Path::new("out/turntable.png"), 12),
+            Path::new("out/turntable_0012.png")
+        );
+        assert_eq!(
+            get_frame_path(Path::new("frame"), 3),
+            Path::new("frame_0003")
+        );
+    }
+}
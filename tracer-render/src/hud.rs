@@ -0,0 +1,424 @@
+use std::time::Duration;
+
+/// Size of a glyph of the built-in font, in font pixels.
+const GLYPH_WIDTH: usize = 5;
+const GLYPH_HEIGHT: usize = 7;
+/// Number of screen pixels covered by a font pixel, along both axes.
+const SCALE: usize = 2;
+const MARGIN: usize = 4;
+
+/// Built-in 5x7 bitmap font, one bit per pixel (the most significant of the 5 bits being the leftmost pixel).
+/// Lowercase letters are drawn with their uppercase glyph, and unknown characters with the question mark's.
+const GLYPHS: [(char, [u8; GLYPH_HEIGHT]); 44] = [
+    (
+        ' ',
+        [
+            0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000,
+        ],
+    ),
+    (
+        '.',
+        [
+            0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b01100, 0b01100,
+        ],
+    ),
+    (
+        ':',
+        [
+            0b00000, 0b01100, 0b01100, 0b00000, 0b01100, 0b01100, 0b00000,
+        ],
+    ),
+    (
+        '/',
+        [
+            0b00000, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b00000,
+        ],
+    ),
+    (
+        '%',
+        [
+            0b11000, 0b11001, 0b00010, 0b00100, 0b01000, 0b10011, 0b00011,
+        ],
+    ),
+    (
+        '-',
+        [
+            0b00000, 0b00000, 0b00000, 0b11111, 0b00000, 0b00000, 0b00000,
+        ],
+    ),
+    (
+        '_',
+        [
+            0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b11111,
+        ],
+    ),
+    (
+        '?',
+        [
+            0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b00000, 0b00100,
+        ],
+    ),
+    (
+        '0',
+        [
+            0b01110, 0b10001, 0b10011, 0b10101, 0b11001, 0b10001, 0b01110,
+        ],
+    ),
+    (
+        '1',
+        [
+            0b00100, 0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110,
+        ],
+    ),
+    (
+        '2',
+        [
+            0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b01000, 0b11111,
+        ],
+    ),
+    (
+        '3',
+        [
+            0b11111, 0b00010, 0b00100, 0b00010, 0b00001, 0b10001, 0b01110,
+        ],
+    ),
+    (
+        '4',
+        [
+            0b00010, 0b00110, 0b01010, 0b10010, 0b11111, 0b00010, 0b00010,
+        ],
+    ),
+    (
+        '5',
+        [
+            0b11111, 0b10000, 0b11110, 0b00001, 0b00001, 0b10001, 0b01110,
+        ],
+    ),
+    (
+        '6',
+        [
+            0b00110, 0b01000, 0b10000, 0b11110, 0b10001, 0b10001, 0b01110,
+        ],
+    ),
+    (
+        '7',
+        [
+            0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b01000, 0b01000,
+        ],
+    ),
+    (
+        '8',
+        [
+            0b01110, 0b10001, 0b10001, 0b01110, 0b10001, 0b10001, 0b01110,
+        ],
+    ),
+    (
+        '9',
+        [
+            0b01110, 0b10001, 0b10001, 0b01111, 0b00001, 0b00010, 0b01100,
+        ],
+    ),
+    (
+        'A',
+        [
+            0b01110, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001,
+        ],
+    ),
+    (
+        'B',
+        [
+            0b11110, 0b10001, 0b10001, 0b11110, 0b10001, 0b10001, 0b11110,
+        ],
+    ),
+    (
+        'C',
+        [
+            0b01110, 0b10001, 0b10000, 0b10000, 0b10000, 0b10001, 0b01110,
+        ],
+    ),
+    (
+        'D',
+        [
+            0b11100, 0b10010, 0b10001, 0b10001, 0b10001, 0b10010, 0b11100,
+        ],
+    ),
+    (
+        'E',
+        [
+            0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b11111,
+        ],
+    ),
+    (
+        'F',
+        [
+            0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b10000,
+        ],
+    ),
+    (
+        'G',
+        [
+            0b01110, 0b10001, 0b10000, 0b10111, 0b10001, 0b10001, 0b01111,
+        ],
+    ),
+    (
+        'H',
+        [
+            0b10001, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001,
+        ],
+    ),
+    (
+        'I',
+        [
+            0b01110, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110,
+        ],
+    ),
+    (
+        'J',
+        [
+            0b00111, 0b00010, 0b00010, 0b00010, 0b00010, 0b10010, 0b01100,
+        ],
+    ),
+    (
+        'K',
+        [
+            0b10001, 0b10010, 0b10100, 0b11000, 0b10100, 0b10010, 0b10001,
+        ],
+    ),
+    (
+        'L',
+        [
+            0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b11111,
+        ],
+    ),
+    (
+        'M',
+        [
+            0b10001, 0b11011, 0b10101, 0b10101, 0b10001, 0b10001, 0b10001,
+        ],
+    ),
+    (
+        'N',
+        [
+            0b10001, 0b10001, 0b11001, 0b10101, 0b10011, 0b10001, 0b10001,
+        ],
+    ),
+    (
+        'O',
+        [
+            0b01110, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110,
+        ],
+    ),
+    (
+        'P',
+        [
+            0b11110, 0b10001, 0b10001, 0b11110, 0b10000, 0b10000, 0b10000,
+        ],
+    ),
+    (
+        'Q',
+        [
+            0b01110, 0b10001, 0b10001, 0b10001, 0b10101, 0b10010, 0b01101,
+        ],
+    ),
+    (
+        'R',
+        [
+            0b11110, 0b10001, 0b10001, 0b11110, 0b10100, 0b10010, 0b10001,
+        ],
+    ),
+    (
+        'S',
+        [
+            0b01111, 0b10000, 0b10000, 0b01110, 0b00001, 0b00001, 0b11110,
+        ],
+    ),
+    (
+        'T',
+        [
+            0b11111, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100,
+        ],
+    ),
+    (
+        'U',
+        [
+            0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110,
+        ],
+    ),
+    (
+        'V',
+        [
+            0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01010, 0b00100,
+        ],
+    ),
+    (
+        'W',
+        [
+            0b10001, 0b10001, 0b10001, 0b10101, 0b10101, 0b10101, 0b01010,
+        ],
+    ),
+    (
+        'X',
+        [
+            0b10001, 0b10001, 0b01010, 0b00100, 0b01010, 0b10001, 0b10001,
+        ],
+    ),
+    (
+        'Y',
+        [
+            0b10001, 0b10001, 0b10001, 0b01010, 0b00100, 0b00100, 0b00100,
+        ],
+    ),
+    (
+        'Z',
+        [
+            0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b11111,
+        ],
+    ),
+];
+
+/// Measures of the last completed frame, displayed over the rendered image.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameStats {
+    pub frame_time: Duration,
+    pub samples_per_frame: u32,
+    pub accumulated_samples: u32,
+    pub resolution: (u32, u32),
+    pub threads: usize,
+    pub objects: usize,
+}
+
+impl FrameStats {
+    /// Get the number of camera rays traced per second during the frame.
+    pub fn get_rays_per_second(&self) -> f64 {
+        let rays =
+            self.resolution.0 as f64 * self.resolution.1 as f64 * self.samples_per_frame as f64;
+        rays / self.frame_time.as_secs_f64().max(f64::EPSILON)
+    }
+
+    /// Format the measures as the overlay's lines of text.
+    pub fn lines(&self) -> Vec<String> {
+        vec![
+            format!("frame: {:.1} ms", self.frame_time.as_secs_f64() * 1000.),
+            format!("rays: {:.2} M/s", self.get_rays_per_second() / 1e6),
+            format!("samples: {} spp", self.accumulated_samples),
+            format!("resolution: {}x{}", self.resolution.0, self.resolution.1),
+            format!("threads: {}", self.threads),
+            format!("objects: {}", self.objects),
+        ]
+    }
+}
+
+fn glyph(character: char) -> &'static [u8; GLYPH_HEIGHT] {
+    let character = character.to_ascii_uppercase();
+    GLYPHS
+        .iter()
+        .find(|x| x.0 == character)
+        .or_else(|| GLYPHS.iter().find(|x| x.0 == '?'))
+        .map(|x| &x.1)
+        .unwrap()
+}
+
+/// Draw lines of text in the top left corner of a 4 bytes per pixel buffer (whose rows are pitch bytes long),
+/// over a darkened background. Whatever doesn't fit in the buffer is cropped.
+pub fn draw(buffer: &mut [u8], size: (usize, usize), pitch: usize, lines: &[String]) {
+    let advance = (GLYPH_WIDTH + 1) * SCALE;
+    let line_height = (GLYPH_HEIGHT + 2) * SCALE;
+    let columns = lines.iter().map(|x| x.chars().count()).max().unwrap_or(0);
+    let box_width = (columns * advance + 2 * MARGIN).min(size.0);
+    let box_height = (lines.len() * line_height + 2 * MARGIN).min(size.1);
+
+    (0..box_height).for_each(|y| {
+        buffer[y * pitch..y * pitch + box_width * 4]
+            .iter_mut()
+            .for_each(|x| *x /= 3);
+    });
+
+    lines.iter().enumerate().for_each(|(row, line)| {
+        line.chars().enumerate().for_each(|(column, character)| {
+            let origin = (
+                MARGIN + column * advance,
+                MARGIN + row * line_height + SCALE,
+            );
+            glyph(character)
+                .iter()
+                .enumerate()
+                .for_each(|(glyph_y, bits)| {
+                    (0..GLYPH_WIDTH)
+                        .filter(|glyph_x| bits & (1 << (GLYPH_WIDTH - 1 - glyph_x)) != 0)
+                        .for_each(|glyph_x| {
+                            (0..SCALE * SCALE).for_each(|i| {
+                                let x = origin.0 + glyph_x * SCALE + i % SCALE;
+                                let y = origin.1 + glyph_y * SCALE + i / SCALE;
+                                if x < size.0 && y < size.1 {
+                                    buffer[y * pitch + x * 4..y * pitch + x * 4 + 4].fill(255);
+                                }
+                            });
+                        });
+                });
+        });
+    });
+}
+
+// #####################################
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::hud::{FrameStats, GLYPHS, draw, glyph};
+
+    #[test]
+    fn test_success_lines() {
+        let stats = FrameStats {
+            frame_time: Duration::from_millis(250),
+            samples_per_frame: 2,
+            accumulated_samples: 8,
+            resolution: (1000, 500),
+            threads: 4,
+            objects: 3,
+        };
+
+        assert_eq!(stats.get_rays_per_second(), 4e6);
+        assert_eq!(
+            stats.lines(),
+            [
+                "frame: 250.0 ms",
+                "rays: 4.00 M/s",
+                "samples: 8 spp",
+                "resolution: 1000x500",
+                "threads: 4",
+                "objects: 3",
+            ]
+        );
+        stats.lines().iter().flat_map(|x| x.chars()).for_each(|x| {
+            assert!(GLYPHS.iter().any(|glyph| glyph.0 == x.to_ascii_uppercase()));
+        });
+    }
+
+    #[test]
+    fn test_success_draw() {
+        let (width, height, pitch) = (40, 30, 42 * 4);
+        let mut buffer = vec![90; pitch * height];
+        draw(&mut buffer, (width, height), pitch, &["1".to_string()]);
+
+        // The glyph starts after the margin and half a line spacing, each font pixel covering 2x2 pixels.
+        let pixel = |x: usize, y: usize| &buffer[y * pitch + x * 4..y * pitch + x * 4 + 4];
+        assert_eq!(pixel(4, 6), [30; 4]);
+        assert_eq!(pixel(8, 6), [255; 4]);
+        assert_eq!(pixel(9, 7), [255; 4]);
+        assert_eq!(pixel(4, 8), [30; 4]);
+        assert_eq!(pixel(6, 8), [255; 4]);
+        assert_eq!(pixel(39, 29), [90; 4]);
+        assert_eq!(buffer[width * 4..pitch], [90; 8]);
+    }
+
+    #[test]
+    fn test_failure_draw_unknown_and_cropped() {
+        assert_eq!(glyph('~'), glyph('?'));
+        assert_eq!(glyph('a'), glyph('A'));
+        assert_ne!(glyph('_'), glyph('?'));
+
+        let mut buffer = vec![0; 4 * 4 * 4];
+        draw(&mut buffer, (4, 4), 4 * 4, &["too long to fit".to_string()]);
+    }
+}
@@ -0,0 +1,162 @@
+use glam::{FloatExt, Vec4};
+
+use tracer_core::{
+    entity::{
+        actor::ActorTrait,
+        geometry::ray::RayType,
+        rendering::material::MaterialBound,
+        scene::{Scene, SurfaceHit},
+    },
+    rendering::{framebuffer::FrameBuffer, ray_emitter::RayEmitter},
+};
+
+/// Color the picked object's pixels are blended toward, and the blend factor.
+const HIGHLIGHT_COLOR: Vec4 = Vec4::new(1., 0.5, 0., 1.);
+const HIGHLIGHT_STRENGTH: f32 = 0.5;
+
+/// Cast the ray of the camera's pixel containing the given window position, and find the renderable it hits.
+/// The window position is given in window pixels, for a window of the given size showing the whole camera's image.
+pub fn pick(
+    scene: &Scene,
+    camera: &RayEmitter,
+    position: (i32, i32),
+    window_size: (u32, u32),
+) -> Option<SurfaceHit> {
+    let (width, height) = camera.get_resolution();
+    let x = (position.0.max(0) as u64 * width as u64 / window_size.0.max(1) as u64)
+        .min(width as u64 - 1);
+    let y = (position.1.max(0) as u64 * height as u64 / window_size.1.max(1) as u64)
+        .min(height as u64 - 1);
+    scene.closest_hit(
+        &camera.ray_at(x as f32 + 0.5, y as f32 + 0.5),
+        &RayType::Camera,
+    )
+}
+
+/// Describe the renderable of a hit, in lines short enough to be drawn over the image.
+pub fn lines(scene: &Scene, hit: &SurfaceHit) -> Vec<String> {
+    let renderable = &scene.renderables[hit.renderable_index];
+    let position = renderable.get_position();
+    vec![
+        format!("object: {} {}", hit.renderable_index, renderable.get_name()),
+        format!(
+            "position: {:.1} {:.1} {:.1}",
+            position.x, position.y, position.z
+        ),
+        format!("material: {}", renderable.get_material().get_name()),
+        format!("distance: {:.1}", hit.distance),
+    ]
+}
+
+/// Describe the renderable of a hit in full, material parameters included, for the console.
+pub fn describe(scene: &Scene, hit: &SurfaceHit) -> String {
+    let renderable = &scene.renderables[hit.renderable_index];
+    format!(
+        "picked object {} ({}) at {}, hit at distance {} - material: {:?}",
+        hit.renderable_index,
+        renderable.get_name(),
+        renderable.get_position(),
+        hit.distance,
+        renderable.get_material(),
+    )
+}
+
+/// Tint the RGBA8 pixels whose object id pass holds the given renderable index.
+/// Framebuffers without extra passes are left untouched.
+pub fn highlight(framebuffer: &FrameBuffer, renderable_index: usize, pixels: &mut [u8]) {
+    let Some(aovs) = framebuffer.get_aovs() else {
+        return;
+    };
+    aovs.iter()
+        .zip(pixels.as_chunks_mut::<4>().0)
+        .filter(|(sample, _)| sample.object_id == Some(renderable_index))
+        .for_each(|(_, pixel)| {
+            pixel.iter_mut().enumerate().for_each(|(channel, value)| {
+                let target = HIGHLIGHT_COLOR[channel] * 255.;
+                *value = (*value as f32).lerp(target, HIGHLIGHT_STRENGTH) as u8;
+            });
+        });
+}
+
+// #####################################
+
+#[cfg(test)]
+mod tests {
+    use glam::{Vec3A, Vec4};
+
+    use tracer_core::{
+        entity::{
+            geometry::{GeometryType, sphere::Sphere},
+            rendering::material::{DiffuseMaterial, MaterialType},
+            scene::Scene,
+        },
+        rendering::{
+            framebuffer::{AovSample, FrameBuffer},
+            ray_emitter::RayEmitter,
+        },
+    };
+
+    use crate::inspect::{highlight, lines, pick};
+
+    fn scene() -> Scene {
+        let mut scene = Scene::new(&Vec4::ZERO);
+        scene.renderables.push(GeometryType::Sphere(
+            Sphere::new(
+                &Vec3A::new(0., 0., 50.),
+                10.,
+                &MaterialType::Diffuse(DiffuseMaterial::new(1.)),
+            )
+            .unwrap(),
+        ));
+        scene
+    }
+
+    #[test]
+    fn test_success_pick() {
+        let scene = scene();
+        let camera = RayEmitter::new(Vec3A::ZERO, Vec3A::Z, 100, 100);
+
+        let hit = pick(&scene, &camera, (200, 200), (400, 400)).unwrap();
+        assert_eq!(hit.renderable_index, 0);
+        assert!((hit.distance - 40.).abs() < 0.2);
+        assert_eq!(
+            lines(&scene, &hit),
+            [
+                "object: 0 sphere",
+                "position: 0.0 0.0 50.0",
+                "material: diffuse",
+                "distance: 40.1"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_failure_pick_background() {
+        let scene = scene();
+        let camera = RayEmitter::new(Vec3A::ZERO, Vec3A::Z, 100, 100);
+
+        assert!(pick(&scene, &camera, (0, 0), (400, 400)).is_none());
+        assert!(pick(&scene, &camera, (-5, 1000), (400, 400)).is_none());
+    }
+
+    #[test]
+    fn test_success_highlight() {
+        let framebuffer = FrameBuffer::new(2, 1, true);
+        framebuffer.set_aovs([(
+            1,
+            AovSample {
+                object_id: Some(3),
+                ..Default::default()
+            },
+        )]);
+        let mut pixels = vec![0; 2 * 4];
+        highlight(&framebuffer, 3, &mut pixels);
+
+        assert_eq!(pixels[0..4], [0; 4]);
+        assert_eq!(pixels[4..8], [127, 63, 0, 127]);
+
+        let mut pixels = vec![0; 2 * 4];
+        highlight(&FrameBuffer::new(2, 1, false), 3, &mut pixels);
+        assert_eq!(pixels, [0; 8]);
+    }
+}
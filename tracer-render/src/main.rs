@@ -1,20 +1,20 @@
-use glam::{Vec3A, Vec4};
-
+use clap::Parser;
+use cli::Cli;
 use renderer::Renderer;
-use tracer_core::entity::geometry::GeometryType;
-use tracer_core::entity::geometry::plane::Plane;
-use tracer_core::entity::geometry::sphere::Sphere;
-use tracer_core::entity::rendering::light::Light;
-use tracer_core::entity::rendering::material::{
-    ColorMaterial, DiffuseMaterial, MaterialMixer, MaterialType, ReflectiveMaterial,
-    SpecularMaterial,
+use tracer_core::{
+    rendering::framebuffer::{Aov, FrameBuffer},
+    scene_file::{self, SceneFile},
 };
-use tracer_core::entity::scene::Scene;
-use tracer_core::rendering::ray_emitter::RayEmitter;
+use watcher::SceneWatcher;
 
+mod camera;
+mod cli;
+mod headless;
+mod hud;
+mod inspect;
 mod renderer;
-
-const RESOLUTION: (u32, u32) = (1000, 1000);
+mod screenshot;
+mod watcher;
 
 #[cfg(feature = "dhat-heap")]
 #[global_allocator]
@@ -24,73 +24,119 @@ pub fn main() {
     #[cfg(feature = "dhat-heap")]
     let _profiler = dhat::Profiler::new_heap();
 
-    let sdl_context = sdl2::init().unwrap();
-    let video_subsystem = sdl_context.video().unwrap();
-    let renderer = Renderer::new(&video_subsystem, &sdl_context, RESOLUTION.0, RESOLUTION.1);
-    let camera_emitter = RayEmitter::new(
-        Vec3A::new(0., 0., -10.),
-        Vec3A::new(0., 0., 1.),
-        RESOLUTION.0,
-        RESOLUTION.1,
-    );
+    let cli = Cli::parse();
+    if let Some(threads) = cli.threads {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build_global()
+            .unwrap();
+    }
 
-    let mut scene: Scene = Scene::new(&Vec4::new(0.1, 0.1, 0.1, 1.));
+    let mut scene_file = match scene_file::load(&cli.scene) {
+        Ok(scene_file) => scene_file,
+        Err(error) => {
+            eprintln!("{error}");
+            std::process::exit(1);
+        }
+    };
+    cli.apply(&mut scene_file);
 
-    let mut material_mixer_sphere = MaterialMixer::default();
-    material_mixer_sphere
-        .materials
-        .push(MaterialType::Color(ColorMaterial::new(Vec4::new(
-            0., 1., 0., 1.,
-        ))));
-    material_mixer_sphere
-        .materials
-        .push(MaterialType::Diffuse(DiffuseMaterial::new(1.)));
-    material_mixer_sphere
-        .materials
-        .push(MaterialType::Specular(SpecularMaterial::new(100., 250.)));
+    match (cli.sequence, cli.headless) {
+        (true, _) => render_sequence(&cli, &scene_file),
+        (false, true) => {
+            let framebuffer = headless::render(&scene_file, cli.frames.unwrap_or(1));
+            save(&cli, &framebuffer);
+        }
+        (false, false) => run_windowed(&cli, scene_file),
+    }
+}
 
-    let sphere: Sphere = Sphere::new(
-        &Vec3A::new(0., 0., 0.),
-        50.,
-        &MaterialType::Mixer(material_mixer_sphere.clone()),
-    );
-    scene.renderables.push(GeometryType::Sphere(sphere));
+/// Render every frame of the scene's animation, each one built with the command line's options and accumulating the
+/// requested number of frames, and write them next to the output file.
+fn render_sequence(cli: &Cli, scene_file: &SceneFile) {
+    let Some(timeline) = &scene_file.timeline else {
+        eprintln!("{}: the scene is not animated", cli.scene.display());
+        std::process::exit(1);
+    };
+    let output = cli.output.as_deref().unwrap();
 
-    let mut material_mixer_plane = MaterialMixer::default();
-    material_mixer_plane
-        .materials
-        .push(MaterialType::Color(ColorMaterial::new(Vec4::new(
-            0., 0., 1., 1.,
-        ))));
-    material_mixer_plane
-        .materials
-        .push(MaterialType::Diffuse(DiffuseMaterial::new(1.)));
+    (0..timeline.get_frame_count()).for_each(|frame| {
+        let mut still = match timeline.evaluate(timeline.get_frame_time(frame)) {
+            Ok(still) => still,
+            Err(error) => {
+                eprintln!("{error}");
+                std::process::exit(1);
+            }
+        };
+        cli.apply(&mut still);
 
-    let plane: Plane = Plane::new(
-        &Vec3A::new(0., -100., 0.),
-        &Vec3A::new(0., 1., 1.),
-        &MaterialType::Mixer(material_mixer_plane),
-    );
-    scene.renderables.push(GeometryType::Plane(plane));
+        let framebuffer = headless::render(&still, cli.frames.unwrap_or(1));
+        let path = headless::get_frame_path(output, frame);
+        if let Err(error) = headless::write_png(&framebuffer, Aov::Beauty, &path) {
+            eprintln!("{}: {error}", path.display());
+            std::process::exit(1);
+        }
+        eprintln!("wrote {}", path.display());
+    });
+}
 
-    let light = Light::new(
-        &Vec3A::new(200., 500., 50.),
-        &Vec3A::new(0., -1., 0.),
-        50.,
-        &MaterialType::Color(ColorMaterial::new(Vec4::new(0., 1., 1., 1.))),
+/// Display the scene in a window until it is closed or the requested number of frames is rendered,
+/// reloading the scene file whenever it is modified.
+fn run_windowed(cli: &Cli, mut scene_file: SceneFile) {
+    let resolution = scene_file.camera.get_resolution();
+
+    let sdl_context = sdl2::init().unwrap();
+    let video_subsystem = sdl_context.video().unwrap();
+    let mut renderer = Renderer::new(
+        &video_subsystem,
+        &sdl_context,
+        resolution.0,
+        resolution.1,
+        scene_file.settings.clone(),
+        &scene_file.camera,
+        cli.dynamic_resolution,
     );
+    renderer.set_screenshot_dir(&cli.screenshot_dir);
+    let mut watcher = SceneWatcher::new(&cli.scene);
+    let mut frames = 0;
 
-    loop {
-        #[cfg(feature = "hyperfine")]
-        {
-            for _ in 0..100 {
-                renderer.render(&camera_emitter, &mut scene, &light);
+    while cli.frames.is_none_or(|x| frames < x) {
+        match watcher.poll() {
+            None => {}
+            Some(Err(error)) => eprintln!("{error}"),
+            Some(Ok(mut reloaded)) => {
+                eprintln!("reloaded {}", watcher.get_path().display());
+                cli.apply(&mut reloaded);
+                scene_file = reloaded;
+                // The window keeps its current size, whatever the reloaded resolution.
+                let (width, height) = renderer.get_resolution();
+                if scene_file.camera.get_resolution() != (width, height) {
+                    scene_file.camera.set_resolution(width, height);
+                }
+                renderer.reset(scene_file.settings.clone(), &scene_file.camera);
             }
-            break;
         }
 
-        if renderer.render(&camera_emitter, &mut scene, &light) {
+        if renderer.render(
+            &mut scene_file.camera,
+            &mut scene_file.scene,
+            &scene_file.light,
+            &|| watcher.is_modified(),
+        ) {
             break;
         }
+        frames += 1;
+    }
+
+    save(cli, renderer.get_framebuffer());
+}
+
+/// Write the rendered image to the requested output file, if any.
+fn save(cli: &Cli, framebuffer: &FrameBuffer) {
+    if let Some(output) = &cli.output
+        && let Err(error) = headless::write_png(framebuffer, Aov::Beauty, output)
+    {
+        eprintln!("{}: {error}", output.display());
+        std::process::exit(1);
     }
 }
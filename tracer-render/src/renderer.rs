@@ -1,21 +1,61 @@
-use glam::Vec4;
-use rayon::prelude::*;
-use sdl2::{Sdl, VideoSubsystem, event::Event, keyboard::Keycode, video::Window};
+use std::{
+    path::{Path, PathBuf},
+    sync::mpsc,
+    thread,
+    time::{Duration, Instant},
+};
+
+use sdl2::{
+    EventPump, Sdl, VideoSubsystem,
+    event::{Event, WindowEvent},
+    keyboard::{Keycode, Mod},
+    mouse::MouseButton,
+    video::Window,
+};
 
+use crate::{
+    camera::CameraController,
+    hud::{self, FrameStats},
+    inspect, screenshot,
+};
 use tracer_core::{
     entity::{
-        geometry::ray::RayType,
-        rendering::{Renderable, light::Light},
-        scene::Scene,
+        rendering::light::Light,
+        scene::{Scene, SurfaceHit},
+    },
+    rendering::{
+        denoiser::DenoiseSettings,
+        framebuffer::{Aov, FrameBuffer},
+        progress::{CancellationToken, RenderProgress, RenderStatus},
+        ray_emitter::RayEmitter,
+        renderer::{self, RenderSettings},
     },
-    rendering::{image_filter, ray_emitter::RayEmitter},
 };
 
 /// Structure in charge of managing the window and the window's render target.
+/// While the camera moves, the frames are rendered at the dynamic resolution's fraction of the window size, and
+/// upscaled to it.
+/// The statistics of the last completed frame are drawn over the image, unless hidden.
+/// Clicking the image picks the renderable under the cursor, which is described on the console and the overlay, and
+/// highlighted until the next click.
+/// F12 saves a screenshot of the displayed pass as it is on screen, the preview one while moving, with an EXR of
+/// the linear image as well if Shift is held.
 pub struct Renderer<'a> {
     window: Window,
     sdl_context: &'a Sdl,
-    w: usize,
+    renderer: renderer::Renderer,
+    framebuffer: FrameBuffer,
+    displayed_aov: Aov,
+    camera: CameraController,
+    dynamic_resolution: f32,
+    moving: bool,
+    stats: Option<FrameStats>,
+    hud_visible: bool,
+    click_start: Option<(i32, i32)>,
+    picked: Option<SurfaceHit>,
+    screenshot_dir: PathBuf,
+    /// Screenshot requested by the last events, along with whether an EXR file is requested as well.
+    pending_screenshot: Option<bool>,
 }
 
 impl<'a> Renderer<'a> {
@@ -24,66 +64,396 @@ impl<'a> Renderer<'a> {
         sdl_context: &'a Sdl,
         width: u32,
         height: u32,
+        settings: RenderSettings,
+        camera: &RayEmitter,
+        dynamic_resolution: f32,
     ) -> Self {
         let window = video_subsystem
             .window("raytracer", width, height)
             .position_centered()
+            .resizable()
             .build()
             .unwrap();
 
-        let uswidth = width as usize;
-
         Self {
             window,
             sdl_context,
-            w: uswidth,
+            renderer: renderer::Renderer::new(settings),
+            framebuffer: FrameBuffer::new(width as usize, height as usize, true),
+            displayed_aov: Aov::Beauty,
+            camera: CameraController::new(camera),
+            dynamic_resolution,
+            moving: false,
+            stats: None,
+            hud_visible: true,
+            click_start: None,
+            picked: None,
+            screenshot_dir: PathBuf::from("."),
+            pending_screenshot: None,
         }
     }
 
-    /// Draw each object on the window surface, from the furthest to the nearest.
-    pub fn render(&self, ray_emitter: &RayEmitter, scene: &mut Scene, light: &Light) -> bool {
+    /// Accumulate a new sample per pixel and draw the result on the window surface.
+    /// The render runs on a separate thread, so the window keeps displaying the completed tiles and the progress
+    /// in its title, and Escape aborts the render in flight before quitting.
+    /// The displayed pass can be cycled with the Tab key, the denoiser toggled with the N key, and the statistics
+    /// overlay with the H key.
+    /// Navigating moves the ray emitter (see CameraController) and restarts the accumulation, and resizing the window
+    /// changes the ray emitter's resolution.
+    /// The render in flight is aborted by any navigation, as well as soon as interrupt returns true, e.g. when the
+    /// scene was modified.
+    pub fn render(
+        &mut self,
+        ray_emitter: &mut RayEmitter,
+        scene: &mut Scene,
+        light: &Light,
+        interrupt: &dyn Fn() -> bool,
+    ) -> bool {
         let mut event_pump = self.sdl_context.event_pump().unwrap();
-        // .render(ray, light, &RayType::Camera, &0)
-        {
-            let mut surface = self.window.surface(&event_pump).unwrap();
-            surface.enable_RLE();
-            surface.with_lock_mut(|buffer: &mut [u8]| {
-                ray_emitter
-                    .rays
-                    .par_iter()
-                    .map(|ray| scene.render(ray, light, &RayType::Camera, &0))
-                    .collect::<Vec<Option<Vec4>>>()
-                    .iter()
-                    .enumerate()
-                    .for_each(|it| match it.1 {
-                        None => {
-                            buffer[it.0 * 4..(it.0 * 4) + 4].copy_from_slice(&[0, 0, 0, 1]);
-                        }
-                        Some(result) => {
-                            buffer[it.0 * 4..(it.0 * 4) + 4].copy_from_slice(&[
-                                (result.x * 255.) as u8,
-                                (result.y * 255.) as u8,
-                                (result.z * 255.) as u8,
-                                (result.w * 255.) as u8,
-                            ]);
-                        }
-                    });
-                image_filter::apply_msaa(self.w as isize, buffer, (1, 1));
+        let cancellation = CancellationToken::new();
+        let (sender, receiver) = mpsc::channel::<RenderProgress>();
+
+        let preview = (self.moving && self.dynamic_resolution < 1.).then(|| {
+            let emitter = ray_emitter.downscaled(self.dynamic_resolution);
+            let (width, height) = emitter.get_resolution();
+            (
+                emitter,
+                FrameBuffer::new(width as usize, height as usize, false),
+            )
+        });
+        let (emitter, framebuffer) = match &preview {
+            Some((emitter, framebuffer)) => (emitter, framebuffer),
+            None => (&*ray_emitter, &self.framebuffer),
+        };
+
+        let overlay = self.get_overlay(scene);
+        let picked = self.picked.map(|x| x.renderable_index);
+        let start = Instant::now();
+        let (events, status) = thread::scope(|scope| {
+            let renderer = &self.renderer;
+            let scene = &*scene;
+            let cancellation = &cancellation;
+            let worker = scope.spawn(move || {
+                renderer.render_with_progress(
+                    emitter,
+                    scene,
+                    light,
+                    framebuffer,
+                    &|progress| {
+                        let _ = sender.send(*progress);
+                    },
+                    cancellation,
+                )
             });
 
-            let _ = surface.finish();
+            let mut events = Vec::new();
+            while !worker.is_finished() {
+                if let Ok(progress) = receiver.recv_timeout(Duration::from_millis(100)) {
+                    let progress = receiver.try_iter().last().unwrap_or(progress);
+                    let _ = self.window.set_title(&format!(
+                        "raytracer - {} - {:.0}% - {} spp - eta {:.1}s",
+                        self.displayed_aov.name(),
+                        progress.get_fraction() * 100.,
+                        progress.samples_per_pixel,
+                        progress.eta.unwrap_or_default().as_secs_f32(),
+                    ));
+                    present(
+                        &self.window,
+                        &event_pump,
+                        framebuffer,
+                        self.displayed_aov,
+                        overlay.as_deref(),
+                        picked,
+                    );
+                }
+
+                event_pump.poll_iter().for_each(|event| {
+                    if is_quit_event(&event)
+                        || is_resize_event(&event).is_some()
+                        || CameraController::is_navigation_event(&event)
+                    {
+                        cancellation.cancel();
+                    }
+                    events.push(event);
+                });
+                if interrupt() {
+                    cancellation.cancel();
+                }
+            }
+            (events, worker.join().unwrap())
+        });
+
+        if status == RenderStatus::Completed {
+            let (width, height) = emitter.get_resolution();
+            self.stats = Some(FrameStats {
+                frame_time: start.elapsed(),
+                samples_per_frame: self.renderer.settings.samples_per_pixel,
+                accumulated_samples: self.framebuffer.get_sample_count(),
+                resolution: (width, height),
+                threads: rayon::current_num_threads(),
+                objects: scene.renderables.len(),
+            });
         }
+        present(
+            &self.window,
+            &event_pump,
+            framebuffer,
+            self.displayed_aov,
+            self.get_overlay(scene).as_deref(),
+            picked,
+        );
+        let _ = self.window.set_title(&format!(
+            "raytracer - {} - {} - {}",
+            self.displayed_aov.name(),
+            match self.camera.is_orbiting() {
+                true => "orbit",
+                false => "fly",
+            },
+            match preview {
+                Some(_) => "preview".to_string(),
+                None => format!("{} spp", self.framebuffer.get_sample_count()),
+            }
+        ));
+
+        let mut view_changed = false;
+        let mut resized = None;
+        let quit = events
+            .into_iter()
+            .chain(event_pump.poll_iter())
+            .any(|event| {
+                view_changed |= self.camera.handle_event(&event, scene);
+                resized = is_resize_event(&event).or(resized);
+                self.handle_click(&event, ray_emitter, scene);
+                self.handle_event(event)
+            });
 
-        for event in event_pump.poll_iter() {
-            match event {
-                Event::Quit { .. }
-                | Event::KeyDown {
-                    keycode: Some(Keycode::Escape),
-                    ..
-                } => return true,
-                _ => {}
+        // The screenshot is saved before the framebuffer is cleared, out of the displayed buffer.
+        if let Some(with_exr) = self.pending_screenshot.take() {
+            let framebuffer = match &preview {
+                Some((_, framebuffer)) => framebuffer,
+                None => &self.framebuffer,
+            };
+            match screenshot::save(
+                framebuffer,
+                self.displayed_aov,
+                &self.screenshot_dir,
+                with_exr,
+            ) {
+                Ok(screenshot) => println!("{}", screenshot),
+                Err(error) => eprintln!("screenshot failed: {}", error),
             }
         }
+        if let Some((width, height)) = resized {
+            ray_emitter.set_resolution(width, height);
+            self.framebuffer = FrameBuffer::new(width as usize, height as usize, true);
+        }
+        if view_changed {
+            self.camera.apply(ray_emitter);
+            self.framebuffer.clear();
+        }
+        self.moving = view_changed;
+        quit
+    }
+
+    pub fn get_framebuffer(&self) -> &FrameBuffer {
+        &self.framebuffer
+    }
+
+    /// Get the lines of text drawn over the image, if any.
+    fn get_overlay(&self, scene: &Scene) -> Option<Vec<String>> {
+        let lines: Vec<String> = self
+            .stats
+            .iter()
+            .flat_map(|stats| stats.lines())
+            .chain(
+                self.picked
+                    .iter()
+                    .flat_map(|hit| inspect::lines(scene, hit)),
+            )
+            .collect();
+        (self.hud_visible && !lines.is_empty()).then_some(lines)
+    }
+
+    pub fn set_screenshot_dir(&mut self, directory: &Path) {
+        self.screenshot_dir = directory.to_path_buf();
+    }
+
+    pub fn get_resolution(&self) -> (u32, u32) {
+        (
+            self.framebuffer.get_width() as u32,
+            self.framebuffer.get_height() as u32,
+        )
+    }
+
+    /// Replace the render settings and the camera restored by the navigation's reset, and drop the accumulated
+    /// samples, so the next render starts from scratch.
+    pub fn reset(&mut self, settings: RenderSettings, camera: &RayEmitter) {
+        self.renderer.settings = settings;
+        self.camera = CameraController::new(camera);
+        self.picked = None;
+        self.framebuffer.clear();
+    }
+
+    /// Pick the renderable under the cursor when the left button is released where it was pressed, so dragging the
+    /// view does not change the selection. Clicking the background clears it.
+    fn handle_click(&mut self, event: &Event, ray_emitter: &RayEmitter, scene: &Scene) {
+        match *event {
+            Event::MouseButtonDown {
+                mouse_btn: MouseButton::Left,
+                x,
+                y,
+                ..
+            } => self.click_start = Some((x, y)),
+            Event::MouseButtonUp {
+                mouse_btn: MouseButton::Left,
+                x,
+                y,
+                ..
+            } if self.click_start.take() == Some((x, y)) => {
+                self.picked = inspect::pick(scene, ray_emitter, (x, y), self.window.size());
+                match &self.picked {
+                    Some(hit) => println!("{}", inspect::describe(scene, hit)),
+                    None => println!("picked nothing"),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// React to a window event, returning true if the application should quit.
+    fn handle_event(&mut self, event: Event) -> bool {
+        match event {
+            event if is_quit_event(&event) => return true,
+            Event::KeyDown {
+                keycode: Some(Keycode::Tab),
+                ..
+            } => {
+                self.displayed_aov = self.displayed_aov.next();
+            }
+            Event::KeyDown {
+                keycode: Some(Keycode::H),
+                ..
+            } => {
+                self.hud_visible = !self.hud_visible;
+            }
+            Event::KeyDown {
+                keycode: Some(Keycode::N),
+                ..
+            } => {
+                self.renderer.settings.denoise = match self.renderer.settings.denoise {
+                    None => Some(DenoiseSettings::default()),
+                    Some(_) => None,
+                };
+            }
+            Event::KeyDown {
+                keycode: Some(Keycode::F12),
+                keymod,
+                ..
+            } => {
+                self.pending_screenshot = Some(keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD));
+            }
+            _ => {}
+        }
         false
     }
 }
+
+fn is_quit_event(event: &Event) -> bool {
+    matches!(
+        event,
+        Event::Quit { .. }
+            | Event::KeyDown {
+                keycode: Some(Keycode::Escape),
+                ..
+            }
+    )
+}
+
+/// Get the new size of a resized window.
+fn is_resize_event(event: &Event) -> Option<(u32, u32)> {
+    match event {
+        Event::Window {
+            win_event: WindowEvent::SizeChanged(width, height),
+            ..
+        } => Some(((*width).max(1) as u32, (*height).max(1) as u32)),
+        _ => None,
+    }
+}
+
+/// Copy the framebuffer's displayed pass to the window surface, stretching it to the surface's size,
+/// highlighting the picked renderable's pixels, and draw the overlay's lines of text over it.
+fn present(
+    window: &Window,
+    event_pump: &EventPump,
+    framebuffer: &FrameBuffer,
+    aov: Aov,
+    overlay: Option<&[String]>,
+    picked: Option<usize>,
+) {
+    let size = (framebuffer.get_width(), framebuffer.get_height());
+    let mut pixels = vec![0; size.0 * size.1 * 4];
+    framebuffer.write_rgba8(aov, &mut pixels);
+    if let Some(renderable_index) = picked {
+        inspect::highlight(framebuffer, renderable_index, &mut pixels);
+    }
+
+    let mut surface = window.surface(event_pump).unwrap();
+    let surface_size = (surface.width() as usize, surface.height() as usize);
+    let pitch = surface.pitch() as usize;
+    surface.enable_RLE();
+    surface.with_lock_mut(|buffer: &mut [u8]| {
+        stretch(&pixels, size, buffer, surface_size, pitch);
+        if let Some(lines) = overlay {
+            hud::draw(buffer, surface_size, pitch, lines);
+        }
+    });
+
+    let _ = surface.finish();
+}
+
+/// Copy 4 bytes pixels to a buffer of another size (whose rows are pitch bytes long), picking the nearest pixels.
+fn stretch(
+    pixels: &[u8],
+    size: (usize, usize),
+    buffer: &mut [u8],
+    buffer_size: (usize, usize),
+    pitch: usize,
+) {
+    (0..buffer_size.1).for_each(|y| {
+        let row = (y * size.1 / buffer_size.1) * size.0;
+        (0..buffer_size.0).for_each(|x| {
+            let source = (row + x * size.0 / buffer_size.0) * 4;
+            buffer[y * pitch + x * 4..y * pitch + x * 4 + 4]
+                .copy_from_slice(&pixels[source..source + 4]);
+        });
+    });
+}
+
+// #####################################
+
+#[cfg(test)]
+mod tests {
+    use crate::renderer::stretch;
+
+    #[test]
+    fn test_success_stretch() {
+        let pixels: Vec<u8> = (0..2 * 2 * 4).collect();
+        let mut buffer = vec![0; 5 * 4 * 4];
+        stretch(&pixels, (2, 2), &mut buffer, (4, 4), 5 * 4);
+
+        assert_eq!(buffer[0..4], pixels[0..4]);
+        assert_eq!(buffer[4..8], pixels[0..4]);
+        assert_eq!(buffer[8..12], pixels[4..8]);
+        assert_eq!(buffer[16..20], [0; 4]);
+        assert_eq!(buffer[3 * 20 + 12..3 * 20 + 16], pixels[12..16]);
+    }
+
+    #[test]
+    fn test_success_stretch_same_size() {
+        let pixels: Vec<u8> = (0..3 * 2 * 4).collect();
+        let mut buffer = vec![0; 3 * 2 * 4];
+        stretch(&pixels, (3, 2), &mut buffer, (3, 2), 3 * 4);
+
+        assert_eq!(buffer, pixels);
+    }
+}
@@ -0,0 +1,188 @@
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use tracer_core::rendering::framebuffer::{Aov, FrameBuffer};
+
+use crate::headless;
+
+#[derive(Debug)]
+pub enum ScreenshotError {
+    Png {
+        path: PathBuf,
+        error: png::EncodingError,
+    },
+    Exr {
+        path: PathBuf,
+        error: exr::error::Error,
+    },
+}
+
+impl fmt::Display for ScreenshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScreenshotError::Png { path, error } => write!(f, "{}: {}", path.display(), error),
+            ScreenshotError::Exr { path, error } => write!(f, "{}: {}", path.display(), error),
+        }
+    }
+}
+
+impl std::error::Error for ScreenshotError {}
+
+/// Files written for a single screenshot, along with the number of samples per pixel they hold.
+#[derive(Debug)]
+pub struct Screenshot {
+    pub png: PathBuf,
+    pub exr: Option<PathBuf>,
+    pub samples_per_pixel: u32,
+}
+
+impl fmt::Display for Screenshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "saved {}", self.png.display())?;
+        if let Some(exr) = &self.exr {
+            write!(f, " and {}", exr.display())?;
+        }
+        write!(f, " ({} spp)", self.samples_per_pixel)
+    }
+}
+
+/// Write the framebuffer's displayed pass to a PNG file named after the current time in the given directory,
+/// along with an EXR file of the linear beauty buffer if requested.
+pub fn save(
+    framebuffer: &FrameBuffer,
+    aov: Aov,
+    directory: &Path,
+    with_exr: bool,
+) -> Result<Screenshot, ScreenshotError> {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    let stem = directory.join(format!("screenshot-{}", timestamp));
+
+    let png = stem.with_extension("png");
+    headless::write_png(framebuffer, aov, &png).map_err(|error| ScreenshotError::Png {
+        path: png.clone(),
+        error,
+    })?;
+
+    let exr = match with_exr {
+        true => {
+            let path = stem.with_extension("exr");
+            write_exr(framebuffer, &path).map_err(|error| ScreenshotError::Exr {
+                path: path.clone(),
+                error,
+            })?;
+            Some(path)
+        }
+        false => None,
+    };
+
+    Ok(Screenshot {
+        png,
+        exr,
+        samples_per_pixel: framebuffer.get_sample_count(),
+    })
+}
+
+/// Write the framebuffer's accumulated colors, without any conversion, to a 32 bits float RGBA EXR file.
+pub fn write_exr(framebuffer: &FrameBuffer, path: &Path) -> Result<(), exr::error::Error> {
+    let width = framebuffer.get_width();
+    exr::prelude::write_rgba_file(path, width, framebuffer.get_height(), |x, y| {
+        let color = framebuffer.get_color(y * width + x);
+        (color.x, color.y, color.z, color.w)
+    })
+}
+
+// #####################################
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use glam::{Vec3A, Vec4};
+    use tracer_core::rendering::{
+        framebuffer::{Aov, AovSample, FrameBuffer},
+        pixel_filter::PixelFilter,
+    };
+
+    use crate::screenshot::save;
+
+    #[test]
+    fn test_success_save() {
+        let directory = std::env::temp_dir().join("tracer-render-test-screenshot");
+        fs::create_dir_all(&directory).unwrap();
+        let framebuffer = FrameBuffer::new(4, 3, false);
+        framebuffer.splat(
+            1.5,
+            1.5,
+            Vec4::new(2., 0.5, 0., 1.),
+            &PixelFilter::default(),
+        );
+        framebuffer.add_sample_count(1);
+
+        let screenshot = save(&framebuffer, Aov::Beauty, &directory, true).unwrap();
+        assert_eq!(screenshot.samples_per_pixel, 1);
+        assert!(fs::metadata(&screenshot.png).unwrap().len() > 0);
+
+        let exr = screenshot.exr.unwrap();
+        let image = exr::prelude::read_first_rgba_layer_from_file(
+            &exr,
+            |resolution, _| vec![Vec4::ZERO; resolution.width() * resolution.height()],
+            |pixels, position, (r, g, b, a): (f32, f32, f32, f32)| {
+                pixels[position.y() * 4 + position.x()] = Vec4::new(r, g, b, a)
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            image.layer_data.channel_data.pixels[5],
+            Vec4::new(2., 0.5, 0., 1.)
+        );
+
+        fs::remove_file(screenshot.png).unwrap();
+        fs::remove_file(exr).unwrap();
+    }
+
+    #[test]
+    fn test_success_save_aov() {
+        let directory = std::env::temp_dir().join("tracer-render-test-screenshot-aov");
+        fs::create_dir_all(&directory).unwrap();
+        let framebuffer = FrameBuffer::new(2, 1, true);
+        framebuffer.splat(0.5, 0.5, Vec4::ONE, &PixelFilter::default());
+        framebuffer.set_aovs([(
+            0,
+            AovSample {
+                depth: 1.,
+                normal: Vec3A::X,
+                albedo: Vec4::ONE,
+                position: Vec3A::ZERO,
+                object_id: Some(0),
+                shadow: 1.,
+            },
+        )]);
+
+        let screenshot = save(&framebuffer, Aov::Normal, &directory, false).unwrap();
+        let decoder = png::Decoder::new(std::io::BufReader::new(
+            fs::File::open(&screenshot.png).unwrap(),
+        ));
+        let mut reader = decoder.read_info().unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size().unwrap()];
+        reader.next_frame(&mut pixels).unwrap();
+        let mut expected = vec![0; 8];
+        framebuffer.write_rgba8(Aov::Normal, &mut expected);
+        assert_eq!(pixels, expected);
+        assert_eq!(pixels[..4], [255, 127, 127, 255]);
+
+        fs::remove_file(screenshot.png).unwrap();
+    }
+
+    #[test]
+    fn test_failure_save_missing_directory() {
+        let directory = std::env::temp_dir().join("tracer-render-test-screenshot-missing");
+        let framebuffer = FrameBuffer::new(2, 2, false);
+        assert!(save(&framebuffer, Aov::Beauty, &directory, false).is_err());
+    }
+}
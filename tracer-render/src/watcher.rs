@@ -0,0 +1,96 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use tracer_core::scene_file::{self, SceneFile, SceneFileError};
+
+/// Structure polling a scene file's modification time, reloading it whenever it changes on disk.
+pub struct SceneWatcher {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+}
+
+impl SceneWatcher {
+    pub fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            modified: modification_time(path),
+        }
+    }
+
+    pub fn get_path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Check whether the scene file was modified since the last poll, without reloading it.
+    pub fn is_modified(&self) -> bool {
+        modification_time(&self.path) != self.modified
+    }
+
+    /// Parse the scene file again if it was modified since the last poll.
+    /// A file which can't be read is reported once, then only retried after its next modification.
+    pub fn poll(&mut self) -> Option<Result<SceneFile, SceneFileError>> {
+        if !self.is_modified() {
+            return None;
+        }
+
+        self.modified = modification_time(&self.path);
+        Some(scene_file::load(&self.path))
+    }
+}
+
+fn modification_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|x| x.modified()).ok()
+}
+
+// #####################################
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        time::{Duration, SystemTime},
+    };
+
+    use crate::watcher::SceneWatcher;
+
+    const DEFAULT_SCENE: &str = include_str!("../../scenes/default.toml");
+
+    #[test]
+    fn test_success_poll() {
+        let path = std::env::temp_dir().join("tracer-render-test-watcher-success.toml");
+        fs::write(&path, DEFAULT_SCENE).unwrap();
+
+        let mut watcher = SceneWatcher::new(&path);
+        assert!(watcher.poll().is_none());
+        assert!(!watcher.is_modified());
+
+        let file = fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(1))
+            .unwrap();
+        assert!(watcher.is_modified());
+        assert!(watcher.poll().unwrap().is_ok());
+        assert!(watcher.poll().is_none());
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_failure_poll() {
+        let path = std::env::temp_dir().join("tracer-render-test-watcher-failure.toml");
+        fs::write(&path, DEFAULT_SCENE).unwrap();
+
+        let mut watcher = SceneWatcher::new(&path);
+        fs::write(&path, "ambient = [0.1]").unwrap();
+        let file = fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(1))
+            .unwrap();
+        assert!(watcher.poll().unwrap().is_err());
+
+        fs::remove_file(&path).unwrap();
+        assert!(watcher.poll().unwrap().is_err());
+        assert!(watcher.poll().is_none());
+    }
+}